@@ -0,0 +1,84 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use np_feasibility::bounds::strengthen_bounds_using_core_occupation;
+use np_feasibility::generator::{GeneratorConfig, generate_problem};
+use np_feasibility::necessary::*;
+use np_feasibility::problem::Problem;
+use np_feasibility::simulator::simulate_earliest_deadline_first;
+
+/// Generates a feasible-looking problem with `num_jobs` jobs, and a layered DAG when `with_dag`
+fn create_problem(num_jobs: usize, with_dag: bool) -> Problem {
+	generate_problem(&GeneratorConfig {
+		seed: 123,
+		num_jobs,
+		num_cores: 4,
+		utilization: 0.4,
+		horizon: 100 * num_jobs as i64,
+		max_window_factor: 4.0,
+		num_layers: if with_dag { 10 } else { 1 },
+		max_fan_in: 3,
+		max_delay: 10,
+	})
+}
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// The EDF simulation is quadratic in the number of jobs, so 100k jobs would take about a minute
+const SIMULATOR_SIZES: [usize; 2] = [1_000, 10_000];
+
+fn bench_necessary_tests(c: &mut Criterion) {
+	let mut group = c.benchmark_group("necessary");
+	group.sample_size(10);
+	for num_jobs in SIZES {
+		let problem = create_problem(num_jobs, false);
+		let index = ProblemIndex::new(&problem);
+		group.bench_with_input(BenchmarkId::new("index", num_jobs), &problem, |b, problem| {
+			b.iter(|| ProblemIndex::new(problem))
+		});
+		group.bench_with_input(BenchmarkId::new("load", num_jobs), &problem, |b, problem| {
+			b.iter(|| run_feasibility_load_test_with_index(problem, &index))
+		});
+		group.bench_with_input(BenchmarkId::new("interval", num_jobs), &problem, |b, problem| {
+			b.iter(|| run_feasibility_interval_test_with_index(problem, &index))
+		});
+	}
+	group.finish();
+}
+
+fn bench_occupation_timeline(c: &mut Criterion) {
+	let mut group = c.benchmark_group("occupation");
+	group.sample_size(10);
+	for num_jobs in SIZES {
+		let problem = create_problem(num_jobs, false);
+		group.bench_with_input(BenchmarkId::new("strengthen", num_jobs), &problem, |b, problem| {
+			b.iter_batched_ref(|| problem.clone(), strengthen_bounds_using_core_occupation, BatchSize::LargeInput)
+		});
+	}
+	group.finish();
+}
+
+fn bench_simulator(c: &mut Criterion) {
+	let mut group = c.benchmark_group("simulator");
+	group.sample_size(10);
+	for num_jobs in SIMULATOR_SIZES {
+		let problem = create_problem(num_jobs, true);
+		group.bench_with_input(BenchmarkId::new("edf", num_jobs), &problem, |b, problem| {
+			b.iter(|| simulate_earliest_deadline_first(problem).has_missed_deadline())
+		});
+	}
+	group.finish();
+}
+
+fn bench_combined(c: &mut Criterion) {
+	let mut group = c.benchmark_group("combined");
+	group.sample_size(10);
+	for num_jobs in SIZES {
+		let problem = create_problem(num_jobs, true);
+		group.bench_with_input(BenchmarkId::new("strengthen_and_test", num_jobs), &problem, |b, problem| {
+			b.iter_batched_ref(|| problem.clone(), strengthen_and_test, BatchSize::LargeInput)
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, bench_necessary_tests, bench_occupation_timeline, bench_simulator, bench_combined);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Runs the analyses on arbitrary small problems (which may have cyclic constraints), and checks
+//! that they don't panic and don't contradict each other. Since `cargo fuzz` enables debug
+//! assertions and overflow checks by default, this also checks the internal invariants of the
+//! analyses.
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use np_feasibility::certificate::*;
+use np_feasibility::necessary::*;
+use np_feasibility::permutation::ProblemPermutation;
+use np_feasibility::problem::*;
+use np_feasibility::simulator::simulate_earliest_deadline_first;
+
+#[derive(Debug, Arbitrary)]
+struct FuzzJob {
+	release: u16,
+	execution_time: u8,
+	slack: u16,
+}
+
+#[derive(Debug, Arbitrary)]
+struct FuzzConstraint {
+	before: u8,
+	after: u8,
+	delay: u8,
+	is_start_to_start: bool,
+}
+
+#[derive(Debug, Arbitrary)]
+struct FuzzProblem {
+	jobs: Vec<FuzzJob>,
+	constraints: Vec<FuzzConstraint>,
+	num_cores: u8,
+}
+
+/// Keeps the problems small, so that the fuzzer can try many of them
+const MAX_NUM_JOBS: usize = 20;
+
+fn create_problem(input: &FuzzProblem) -> Option<Problem> {
+	if input.jobs.is_empty() || input.jobs.len() > MAX_NUM_JOBS {
+		return None;
+	}
+	let jobs = input.jobs.iter().enumerate().map(|(index, job)| {
+		let execution_time = job.execution_time as Time + 1;
+		Job::release_to_deadline(index, job.release as Time, execution_time, job.release as Time + execution_time + job.slack as Time)
+	}).collect();
+	let num_jobs = input.jobs.len();
+	let constraints = input.constraints.iter().take(2 * MAX_NUM_JOBS).map(|constraint| Constraint::new(
+		constraint.before as usize % num_jobs, constraint.after as usize % num_jobs, constraint.delay as Time,
+		if constraint.is_start_to_start { ConstraintType::StartToStart } else { ConstraintType::FinishToStart }
+	)).collect();
+	Some(Problem { jobs, constraints, num_cores: 1 + input.num_cores as u32 % 4, resources: Vec::new() })
+}
+
+fuzz_target!(|input: FuzzProblem| {
+	let Some(problem) = create_problem(&input) else { return };
+	problem.validate();
+
+	let is_infeasible = is_proven_infeasible(&problem);
+	let infeasibility_certificate = find_infeasibility_certificate(&problem);
+	if let Some(certificate) = &infeasibility_certificate {
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, certificate));
+	}
+
+	// The simulator can't handle cyclic constraints
+	if ProblemPermutation::possible(&mut problem.clone()).is_ok() {
+		let simulator = simulate_earliest_deadline_first(&problem);
+		if !simulator.has_missed_deadline() {
+			let schedule = simulator.get_schedule().unwrap();
+			assert_eq!(Ok(()), schedule.validate(&problem));
+			assert_eq!(Ok(()), check_schedule_certificate(&problem, &create_schedule_certificate(schedule)));
+			assert!(!is_infeasible, "The necessary tests rejected a problem that EDF can schedule");
+			assert_eq!(None, infeasibility_certificate, "Found an infeasibility certificate for a feasible problem");
+		}
+	}
+});
@@ -0,0 +1,20 @@
+//! Feeds arbitrary bytes to the parser: everything before the first zero byte is the jobs file, and
+//! everything after it is the constraints file. Malformed input must be rejected with an error
+//! instead of a panic, and every problem that is accepted must be valid.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use np_feasibility::parser::try_parse_problem_text;
+
+fuzz_target!(|data: &[u8]| {
+	let (jobs_bytes, constraints_bytes) = match data.iter().position(|byte| *byte == 0) {
+		Some(separator) => (&data[.. separator], Some(&data[separator + 1 ..])),
+		None => (data, None),
+	};
+	let jobs_text = String::from_utf8_lossy(jobs_bytes);
+	let constraints_text = constraints_bytes.map(String::from_utf8_lossy);
+
+	if let Ok(problem) = try_parse_problem_text(&jobs_text, constraints_text.as_deref(), 2) {
+		problem.validate();
+	}
+});
@@ -0,0 +1,119 @@
+use crate::exact::{solve_by_enumeration, ExactResult};
+use crate::necessary::strengthen_and_test;
+use crate::problem::*;
+use crate::schedule::Schedule;
+use crate::simulator::simulate_earliest_deadline_first;
+use crate::stats::Verdict;
+#[cfg(feature = "batch")]
+use rayon::prelude::*;
+
+/// How hard `analyze_problem` and `analyze_many` should try to reach a verdict
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct AnalysisOptions {
+	/// When the necessary tests are inconclusive and EDF misses a deadline, try all dispatch orders
+	/// of problems with at most this number of jobs. When this is 0, no dispatch orders are tried.
+	pub max_enumerated_jobs: usize,
+}
+
+/// The result of `analyze_problem`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AnalysisResult {
+	Feasible(Schedule),
+	Infeasible,
+	Unknown,
+}
+
+impl AnalysisResult {
+	pub fn get_verdict(&self) -> Verdict {
+		match self {
+			AnalysisResult::Feasible(_) => Verdict::Feasible,
+			AnalysisResult::Infeasible => Verdict::Infeasible,
+			AnalysisResult::Unknown => Verdict::Unknown,
+		}
+	}
+}
+
+/// Runs the necessary tests on `problem`, and simulates EDF on `problem` when they are
+/// inconclusive. EDF is simulated on the original problem rather than the strengthened one, since
+/// its schedulability should not depend on strengthened earliest start times. When EDF misses a
+/// deadline, all dispatch orders are tried if `problem` has at most `options.max_enumerated_jobs`
+/// jobs. The schedule of a feasible result is a schedule of
+/// `problem`.
+pub fn analyze_problem(problem: &Problem, options: AnalysisOptions) -> AnalysisResult {
+	let mut strengthened = problem.clone();
+	if strengthen_and_test(&mut strengthened) {
+		return AnalysisResult::Infeasible;
+	}
+
+	let simulator = simulate_earliest_deadline_first(problem);
+	if !simulator.has_missed_deadline() {
+		return AnalysisResult::Feasible(simulator.get_schedule().unwrap().clone());
+	}
+	if problem.jobs.len() <= options.max_enumerated_jobs {
+		return match solve_by_enumeration(&strengthened) {
+			ExactResult::Feasible(schedule) => AnalysisResult::Feasible(schedule),
+			// The enumeration can't prove infeasibility when it could exceed maximum delays, or when
+			// it could put jobs on cores that are needed by jobs with an affinity
+			ExactResult::Infeasible if problem.has_max_delays() || problem.has_affinities() => AnalysisResult::Unknown,
+			ExactResult::Infeasible => AnalysisResult::Infeasible,
+		};
+	}
+	AnalysisResult::Unknown
+}
+
+/// Analyzes each problem of `problems` using `analyze_problem`, and returns their results in the
+/// same order. The problems are analyzed in parallel on the current rayon thread pool: the global
+/// pool, or the pool in which the caller runs (for instance using `ThreadPool::install`). Since the
+/// analysis itself doesn't spawn any threads, applications that use rayon themselves can call this
+/// without oversubscribing their cores.
+#[cfg(feature = "batch")]
+pub fn analyze_many(problems: &[Problem], options: AnalysisOptions) -> Vec<AnalysisResult> {
+	problems.par_iter().map(|problem| analyze_problem(problem, options)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::batch::*;
+
+	fn create_problems() -> Vec<Problem> {
+		// Job 1 must run from time 2 to 12, so job 0 can only run after it
+		let feasible = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 2, 10, 12),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let mut infeasible = feasible.clone();
+		infeasible.jobs[0] = Job::release_to_deadline(0, 0, 10, 15);
+		vec![feasible, infeasible]
+	}
+
+	#[test]
+	fn test_analyze_problem() {
+		let problems = create_problems();
+
+		// EDF starts job 0 at time 0, so only the enumeration finds the schedule that waits for job 1
+		assert_eq!(AnalysisResult::Unknown, analyze_problem(&problems[0], AnalysisOptions::default()));
+		let options = AnalysisOptions { max_enumerated_jobs: 2 };
+		let AnalysisResult::Feasible(schedule) = analyze_problem(&problems[0], options) else {
+			panic!("Expected a feasible schedule");
+		};
+		assert_eq!(Ok(()), schedule.validate(&problems[0]));
+		assert_eq!(Verdict::Infeasible, analyze_problem(&problems[1], AnalysisOptions::default()).get_verdict());
+	}
+
+	#[cfg(feature = "batch")]
+	#[test]
+	fn test_analyze_many() {
+		let problems = create_problems();
+		let options = AnalysisOptions { max_enumerated_jobs: 10 };
+		let results = analyze_many(&problems, options);
+		assert_eq!(problems.len(), results.len());
+		for (problem, result) in problems.iter().zip(&results) {
+			assert_eq!(&analyze_problem(problem, options), result);
+		}
+	}
+}
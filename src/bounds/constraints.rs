@@ -9,34 +9,71 @@ use crate::problem::*;
 /// Furthermore, for all start-to-start constraints `c`:
 /// - `problem.jobs[c.before].earliest_start + c.delay <= problem.jobs[c.after].earliest_start`
 ///
+/// Constraints with a maximum delay also ensure that the `after` job starts at most
+/// `c.max_delay` after the start or finish of the `before` job, which bounds the `latest_start`
+/// of the `after` job and the `earliest_start` of the `before` job.
+///
 /// Returns true if and only if the `earliest_start` or `latest_start` of at least 1 job has
 /// been changed.
 pub fn strengthen_bounds_using_constraints(problem: &mut Problem) -> bool {
 	debug_assert!(problem.is_job_order_possible());
 
+	// Maximum delays propagate bounds against the order of the jobs, so then the passes need to be
+	// repeated until nothing changes, or until the bounds prove that the problem is infeasible.
+	let has_max_delays = problem.constraints.iter().any(|constraint| constraint.get_max_delay().is_some());
+	let mut result = false;
+	while propagate_bounds(problem) {
+		result = true;
+		if !has_max_delays || problem.is_certainly_infeasible() {
+			break;
+		}
+	}
+	result
+}
+
+/// Propagates the bounds once along all constraints: forward in the first pass, and backward in
+/// the second pass. Returns true if any bound was changed.
+fn propagate_bounds(problem: &mut Problem) -> bool {
 	let mut result = false;
 	for index in 0 .. problem.constraints.len() {
 		let constraint = problem.constraints[index];
-		let mut earliest_start = problem.jobs[constraint.get_before()].earliest_start + constraint.get_delay();
+		let before = problem.jobs[constraint.get_before()];
+		let mut earliest_start = before.earliest_start + constraint.get_delay();
 		if constraint.get_type() == ConstraintType::FinishToStart {
-			earliest_start += problem.jobs[constraint.get_before()].get_execution_time();
+			earliest_start += before.get_execution_time();
 		}
 		if earliest_start > problem.jobs[constraint.get_after()].earliest_start {
 			problem.jobs[constraint.get_after()].earliest_start = earliest_start;
 			result = true;
 		}
+		if let Some(max_separation) = constraint.get_max_separation(before.get_execution_time()) {
+			let latest_start = before.latest_start + max_separation;
+			if latest_start < problem.jobs[constraint.get_after()].latest_start {
+				problem.jobs[constraint.get_after()].latest_start = latest_start;
+				result = true;
+			}
+		}
 	}
 
 	for index in (0 .. problem.constraints.len()).rev() {
 		let constraint = problem.constraints[index];
-		let mut latest_start = problem.jobs[constraint.get_after()].latest_start - constraint.get_delay();
+		let after = problem.jobs[constraint.get_after()];
+		let before_execution_time = problem.jobs[constraint.get_before()].get_execution_time();
+		let mut latest_start = after.latest_start - constraint.get_delay();
 		if constraint.get_type() == ConstraintType::FinishToStart {
-			latest_start -= problem.jobs[constraint.get_before()].get_execution_time();
+			latest_start -= before_execution_time;
 		}
 		if latest_start < problem.jobs[constraint.get_before()].latest_start {
 			problem.jobs[constraint.get_before()].latest_start = latest_start;
 			result = true;
 		}
+		if let Some(max_separation) = constraint.get_max_separation(before_execution_time) {
+			let earliest_start = after.earliest_start - max_separation;
+			if earliest_start > problem.jobs[constraint.get_before()].earliest_start {
+				problem.jobs[constraint.get_before()].earliest_start = earliest_start;
+				result = true;
+			}
+		}
 	}
 
 	result
@@ -45,9 +82,9 @@ pub fn strengthen_bounds_using_constraints(problem: &mut Problem) -> bool {
 #[cfg(test)]
 mod tests {
 	use crate::bounds::*;
-	use crate::parse_problem;
+	use crate::parser::parse_problem;
 	use crate::permutation::ProblemPermutation;
-	use crate::problem::Job;
+	use crate::problem::*;
 
 	#[test]
 	fn sanity_check_without_precedence_constraints() {
@@ -56,9 +93,8 @@ mod tests {
 		let permutation = ProblemPermutation::possible(&mut problem).unwrap();
 		assert!(!strengthen_bounds_using_constraints(&mut problem));
 		permutation.transform_back(&mut problem);
-		assert_eq!(Job::release_to_deadline(0, 40, 10, 100), problem.jobs[0]);
-		assert_eq!(Job::release_to_deadline(1, 0, 20, 100), problem.jobs[1]);
-		assert_eq!(Job::release_to_deadline(2, 75, 30, 100), problem.jobs[2]);
+		let bounds: Vec<(Time, Time)> = problem.jobs.iter().map(|job| (job.earliest_start, job.get_latest_finish())).collect();
+		assert_eq!(vec![(40, 100), (0, 100), (75, 100)], bounds);
 	}
 
 	#[test]
@@ -147,4 +183,32 @@ mod tests {
 		assert!(strengthen_bounds_using_constraints(&mut problem));
 		assert!(!strengthen_bounds_using_constraints(&mut problem));
 	}
+
+	#[test]
+	fn test_max_delays() {
+		let mut bounded = Constraint::new(0, 1, 0, ConstraintType::FinishToStart);
+		bounded.set_max_delay(Some(5));
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 50, 10, 100),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![bounded, Constraint::new(0, 2, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		assert!(strengthen_bounds_using_constraints(&mut problem));
+		// Job 0 must finish at most 5 time units before job 1 starts, which delays job 2 as well
+		assert_eq!(35, problem.jobs[0].earliest_start);
+		assert_eq!(45, problem.jobs[2].earliest_start);
+		assert_eq!(80, problem.jobs[0].latest_start);
+		assert_eq!(90, problem.jobs[1].latest_start);
+		assert!(!strengthen_bounds_using_constraints(&mut problem));
+
+		// The maximum delay can't be met when job 0 has to start at time 0
+		problem.jobs[0] = Job::release_to_deadline(0, 0, 10, 10);
+		strengthen_bounds_using_constraints(&mut problem);
+		assert!(problem.is_certainly_infeasible());
+	}
 }
@@ -1,5 +1,7 @@
 mod constraints;
 mod occupation;
+mod ordering;
 
 pub use constraints::*;
 pub use occupation::*;
+pub use ordering::*;
@@ -41,11 +41,20 @@ pub enum OccupationStrengthenResult {
 ///
 /// This function will repeatedly try to strengthen the `earliest_start` and `latest_start` of all
 /// jobs using this reasoning.
+///
+/// Besides the timeline of all cores, it maintains a timeline for each group of cores in
+/// `Problem::get_affinity_groups`, which contains only the jobs whose affinity is a subset of
+/// that group, and whose capacity is the number of cores in that group.
 pub fn strengthen_bounds_using_core_occupation(problem: &mut Problem) -> OccupationStrengthenResult {
-	let mut timeline = OccupationTimeline::new(problem.num_cores);
+	let mut timelines = vec![(CoreSet::all(), OccupationTimeline::new(problem.num_cores))];
+	for group in problem.get_affinity_groups() {
+		timelines.push((group, OccupationTimeline::new(group.get_cores(problem.num_cores).count() as u32)));
+	}
 	for job in &problem.jobs {
-		if timeline.insert(*job) {
-			return OccupationStrengthenResult::Infeasible;
+		for (group, timeline) in &mut timelines {
+			if job.get_affinity().is_subset_of(*group) && timeline.insert(*job) {
+				return OccupationStrengthenResult::Infeasible;
+			}
 		}
 	}
 
@@ -53,16 +62,36 @@ pub fn strengthen_bounds_using_core_occupation(problem: &mut Problem) -> Occupat
 	loop {
 		let mut modified_interval = false;
 		for job in &mut problem.jobs {
-			let result = timeline.refine(job);
-			if result == RefineResult::Infeasible {
-				return OccupationStrengthenResult::Infeasible;
-			}
-			if result == RefineResult::ModifiedJobAndIntervals {
-				modified_interval = true;
-				modified_anything = true;
-			}
-			if result == RefineResult::ModifiedJob {
-				modified_anything = true;
+			for group_index in 0 .. timelines.len() {
+				if !job.get_affinity().is_subset_of(timelines[group_index].0) {
+					continue;
+				}
+				let old = *job;
+				let result = timelines[group_index].1.refine(job);
+				if result == RefineResult::Infeasible {
+					return OccupationStrengthenResult::Infeasible;
+				}
+				if result == RefineResult::ModifiedJobAndIntervals {
+					modified_interval = true;
+					modified_anything = true;
+				}
+				if result == RefineResult::ModifiedJob {
+					modified_anything = true;
+				}
+
+				// The other timelines that contain the job must also see its grown occupation
+				if *job != old {
+					for (other_index, (group, timeline)) in timelines.iter_mut().enumerate() {
+						if other_index == group_index || !job.get_affinity().is_subset_of(*group) {
+							continue;
+						}
+						match timeline.insert_growth(&old, job) {
+							RefineResult::Infeasible => return OccupationStrengthenResult::Infeasible,
+							RefineResult::ModifiedJobAndIntervals => modified_interval = true,
+							_ => {}
+						}
+					}
+				}
 			}
 		}
 
@@ -78,6 +107,14 @@ pub fn strengthen_bounds_using_core_occupation(problem: &mut Problem) -> Occupat
 	}
 }
 
+/// Creates a job that certainly occupies the cores of `job` from `start` until `end`, which can be
+/// inserted into an `OccupationTimeline` when the certain occupation of `job` grows
+fn get_occupation_part(job: &Job, start: Time, end: Time) -> Job {
+	let mut part = Job::release_to_deadline(job.get_index(), start, end - start, end);
+	part.set_parallelism(job.get_parallelism());
+	part
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct OccupationInterval {
 	start: Time,
@@ -106,11 +143,24 @@ impl OccupationTimeline {
 		}
 	}
 
-	/// Returns true if the problem is certainly infeasible
+	/// Adds the certain occupation of `job` to this timeline: it occupies `job.get_parallelism()`
+	/// cores from its latest start time until its earliest finish time. Returns true if the problem
+	/// is certainly infeasible.
 	pub fn insert(&mut self, job: Job) -> bool {
 		if job.get_earliest_finish() <= job.latest_start {
 			return false;
 		}
+		let parallelism = job.get_parallelism();
+
+		// No cores are occupied before the first interval, so extend it (or prepend an empty one)
+		// when the job starts earlier. Otherwise, the binary searches below would fail.
+		if job.latest_start < self.intervals[0].start {
+			if self.intervals[0].num_cores == 0 {
+				self.intervals[0].start = job.latest_start;
+			} else {
+				self.intervals.insert(0, OccupationInterval { start: job.latest_start, num_cores: 0 });
+			}
+		}
 
 		let mut end_index = match self.intervals.binary_search_by_key(
 			&job.get_earliest_finish(), |i| i.start
@@ -138,7 +188,7 @@ impl OccupationTimeline {
 					0
 				};
 				if next_start_index < self.intervals.len() &&
-					num_cores + 1 == self.intervals[next_start_index].num_cores &&
+					num_cores + parallelism == self.intervals[next_start_index].num_cores &&
 					self.intervals[next_start_index].start >= job.get_earliest_finish() {
 					self.intervals[next_start_index].start = job.latest_start;
 				} else {
@@ -151,39 +201,66 @@ impl OccupationTimeline {
 			}
 		};
 		for index in start_index ..= end_index {
-			let more_cores = self.intervals[index].num_cores + 1;
+			let more_cores = self.intervals[index].num_cores + parallelism;
 			if more_cores > self.max_num_cores {
 				return true;
 			}
 			self.intervals[index].num_cores = more_cores;
 		}
 
-		while start_index > 0 && self.intervals[start_index].num_cores == self.intervals[start_index - 1].num_cores {
-			self.intervals.remove(start_index);
-			end_index -= 1;
-		}
-		while end_index + 1 < self.intervals.len() && self.intervals[end_index].num_cores == self.intervals[end_index + 1].num_cores {
-			self.intervals.remove(end_index + 1);
+		// Merge every interval in the touched range (and the one right after it) into its predecessor
+		// when they occupy the same number of cores. Equal neighbours can occur anywhere in the range,
+		// not only at its boundaries.
+		let mut index = max(start_index, 1);
+		let mut last_index = end_index + 1;
+		while index <= last_index && index < self.intervals.len() {
+			if self.intervals[index].num_cores == self.intervals[index - 1].num_cores {
+				self.intervals.remove(index);
+				last_index -= 1;
+			} else {
+				index += 1;
+			}
 		}
+		self.audit();
 		false
 	}
 
-	fn find_interruption(&self, start: Time, bound: Time) -> Option<usize> {
+	/// Checks that the intervals have strictly increasing start times, that adjacent intervals
+	/// have a different number of cores, that no interval occupies more than `max_num_cores`
+	/// cores, and that the last interval (which never ends) occupies no cores at all.
+	///
+	/// This takes linear time, so it is only done when debug assertions are enabled. Since
+	/// `insert` gives up as soon as it detects infeasibility, the timeline is only audited while
+	/// the problem is not known to be infeasible.
+	fn audit(&self) {
+		if !cfg!(debug_assertions) {
+			return;
+		}
+		for pair in self.intervals.windows(2) {
+			assert!(pair[0].start < pair[1].start, "Intervals are not sorted: {:?}", self.intervals);
+			assert_ne!(pair[0].num_cores, pair[1].num_cores, "Intervals are not merged: {:?}", self.intervals);
+		}
+		assert!(
+			self.intervals.iter().all(|interval| interval.num_cores <= self.max_num_cores),
+			"More than {} cores are occupied: {:?}", self.max_num_cores, self.intervals
+		);
+		assert_eq!(0, self.intervals.last().unwrap().num_cores, "The last interval is occupied: {:?}", self.intervals);
+	}
+
+	/// Finds the first interval between `start` and `bound` during which fewer than `parallelism`
+	/// cores are available
+	fn find_interruption(&self, start: Time, bound: Time, parallelism: u32) -> Option<usize> {
 		let start_index = self.intervals.binary_search_by_key(
 			&start, |i| i.start
-		).unwrap_or_else(|next_start_index| next_start_index - 1);
+		).unwrap_or_else(|next_start_index| next_start_index.saturating_sub(1));
 
 		let bound_index = self.intervals.binary_search_by_key(
 			&bound, |i| i.start
 		).unwrap_or_else(|next_bound_index| next_bound_index);
 
-		for index in start_index .. bound_index {
-			if self.intervals[index].num_cores == self.max_num_cores {
-				return Some(index);
-			}
-		}
-
-		None
+		(start_index .. bound_index).find(
+			|index| self.intervals[*index].num_cores + parallelism > self.max_num_cores
+		)
 	}
 
 	pub fn refine(&mut self, job: &mut Job) -> RefineResult {
@@ -198,7 +275,7 @@ impl OccupationTimeline {
 				interruption_bound = min(interruption_bound, old.latest_start);
 			}
 			let maybe_interruption_index = self.find_interruption(
-				job.earliest_start, interruption_bound
+				job.earliest_start, interruption_bound, job.get_parallelism()
 			);
 			if let Some(interruption_index) = maybe_interruption_index {
 				debug_assert!(job.earliest_start < self.intervals[interruption_index + 1].start);
@@ -214,7 +291,7 @@ impl OccupationTimeline {
 
 		loop {
 			let maybe_interruption_index = self.find_interruption(
-				max(job.latest_start, job.get_earliest_finish()), job.get_latest_finish()
+				max(job.latest_start, job.get_earliest_finish()), job.get_latest_finish(), job.get_parallelism()
 			);
 			if let Some(interruption_index) = maybe_interruption_index {
 				debug_assert!(job.get_latest_finish() > self.intervals[interruption_index].start);
@@ -232,32 +309,40 @@ impl OccupationTimeline {
 			return RefineResult::Infeasible;
 		}
 
+		if *job == old {
+			return RefineResult::Unchanged;
+		}
+		match self.insert_growth(&old, job) {
+			RefineResult::Unchanged => RefineResult::ModifiedJob,
+			result => result,
+		}
+	}
+
+	/// Inserts the certain occupation that `job` has gained since it was `old`, when `old` was
+	/// already inserted into this timeline. Returns `ModifiedJobAndIntervals` if any intervals were
+	/// changed, and `Unchanged` otherwise.
+	pub fn insert_growth(&mut self, old: &Job, job: &Job) -> RefineResult {
 		let mut result = RefineResult::Unchanged;
-		if *job != old {
-			result = RefineResult::ModifiedJob;
-			if old.get_earliest_finish() > old.latest_start {
-				if job.latest_start < old.latest_start {
-					self.insert(Job::release_to_deadline(
-						job.get_index(), job.latest_start,
-						old.latest_start - job.latest_start,
-						old.latest_start
-					));
-					result = RefineResult::ModifiedJobAndIntervals;
+		if old.get_earliest_finish() > old.latest_start {
+			// The timeline is left half-updated when an insertion overflows the cores, so stop there
+			if job.latest_start < old.latest_start {
+				if self.insert(get_occupation_part(job, job.latest_start, old.latest_start)) {
+					return RefineResult::Infeasible;
 				}
-				if job.get_earliest_finish() > old.get_earliest_finish() {
-					self.insert(Job::release_to_deadline(
-						job.get_index(), old.get_earliest_finish(),
-						job.get_earliest_finish() - old.get_earliest_finish(),
-						job.get_earliest_finish()
-					));
-					result = RefineResult::ModifiedJobAndIntervals;
+				result = RefineResult::ModifiedJobAndIntervals;
+			}
+			if job.get_earliest_finish() > old.get_earliest_finish() {
+				if self.insert(get_occupation_part(job, old.get_earliest_finish(), job.get_earliest_finish())) {
+					return RefineResult::Infeasible;
 				}
-			} else if job.get_earliest_finish() > job.latest_start {
-				self.insert(*job);
 				result = RefineResult::ModifiedJobAndIntervals;
 			}
+		} else if job.get_earliest_finish() > job.latest_start {
+			if self.insert(*job) {
+				return RefineResult::Infeasible;
+			}
+			result = RefineResult::ModifiedJobAndIntervals;
 		}
-
 		result
 	}
 }
@@ -279,10 +364,10 @@ mod tests {
 			start: 15, num_cores: 0
 		}], timeline.intervals);
 
-		assert_eq!(Some(0), timeline.find_interruption(0, 100));
-		assert_eq!(Some(0), timeline.find_interruption(14, 100));
-		assert_eq!(None, timeline.find_interruption(15, 100));
-		assert_eq!(None, timeline.find_interruption(50, 100));
+		assert_eq!(Some(0), timeline.find_interruption(0, 100, 1));
+		assert_eq!(Some(0), timeline.find_interruption(14, 100, 1));
+		assert_eq!(None, timeline.find_interruption(15, 100, 1));
+		assert_eq!(None, timeline.find_interruption(50, 100, 1));
 	}
 
 	#[test]
@@ -385,11 +470,11 @@ mod tests {
 			start: 42, num_cores: 0
 		}], timeline.intervals);
 
-		assert_eq!(None, timeline.find_interruption(0, 21));
-		assert_eq!(Some(3), timeline.find_interruption(0, 22));
-		assert_eq!(Some(3), timeline.find_interruption(0, 100));
-		assert_eq!(Some(3), timeline.find_interruption(22, 100));
-		assert_eq!(None, timeline.find_interruption(23, 100));
+		assert_eq!(None, timeline.find_interruption(0, 21, 1));
+		assert_eq!(Some(3), timeline.find_interruption(0, 22, 1));
+		assert_eq!(Some(3), timeline.find_interruption(0, 100, 1));
+		assert_eq!(Some(3), timeline.find_interruption(22, 100, 1));
+		assert_eq!(None, timeline.find_interruption(23, 100, 1));
 	}
 
 	#[test]
@@ -415,17 +500,17 @@ mod tests {
 			start: 65, num_cores: 0
 		}], timeline.intervals);
 
-		assert_eq!(None, timeline.find_interruption(0, 15));
-		assert_eq!(Some(1), timeline.find_interruption(0, 16));
-		for start in vec![10, 15, 20] {
-			assert_eq!(Some(1), timeline.find_interruption(start, 20));
+		assert_eq!(None, timeline.find_interruption(0, 15, 1));
+		assert_eq!(Some(1), timeline.find_interruption(0, 16, 1));
+		for start in [10, 15, 20] {
+			assert_eq!(Some(1), timeline.find_interruption(start, 20, 1));
 		}
-		assert_eq!(Some(1), timeline.find_interruption(24, 35));
-		assert_eq!(None, timeline.find_interruption(25, 35));
-		assert_eq!(Some(3), timeline.find_interruption(25, 36));
-		assert_eq!(Some(3), timeline.find_interruption(25, 100));
-		assert_eq!(Some(3), timeline.find_interruption(44, 100));
-		assert_eq!(Some(5), timeline.find_interruption(45, 100));
+		assert_eq!(Some(1), timeline.find_interruption(24, 35, 1));
+		assert_eq!(None, timeline.find_interruption(25, 35, 1));
+		assert_eq!(Some(3), timeline.find_interruption(25, 36, 1));
+		assert_eq!(Some(3), timeline.find_interruption(25, 100, 1));
+		assert_eq!(Some(3), timeline.find_interruption(44, 100, 1));
+		assert_eq!(Some(5), timeline.find_interruption(45, 100, 1));
 	}
 
 	#[test]
@@ -436,7 +521,7 @@ mod tests {
 		assert!(!timeline.insert(Job::release_to_deadline(0, 50, 20, 90)));
 
 		assert_eq!(vec![OccupationInterval { start: 0, num_cores: 0 }], timeline.intervals);
-		assert_eq!(None, timeline.find_interruption(0, 12345));
+		assert_eq!(None, timeline.find_interruption(0, 12345, 1));
 	}
 
 	#[test]
@@ -459,10 +544,10 @@ mod tests {
 		// Next insertion causes deadline miss since there are 'only' 100 cores
 		assert!(timeline.insert(job));
 
-		assert_eq!(None, timeline.find_interruption(0, 30));
-		assert_eq!(Some(1), timeline.find_interruption(0, 31));
-		assert_eq!(Some(1), timeline.find_interruption(49, 100));
-		assert_eq!(None, timeline.find_interruption(50, 100));
+		assert_eq!(None, timeline.find_interruption(0, 30, 1));
+		assert_eq!(Some(1), timeline.find_interruption(0, 31, 1));
+		assert_eq!(Some(1), timeline.find_interruption(49, 100, 1));
+		assert_eq!(None, timeline.find_interruption(50, 100, 1));
 	}
 
 	#[test]
@@ -574,6 +659,30 @@ mod tests {
 		}], timeline.intervals);
 	}
 
+	#[test]
+	fn test_insert_before_zero() {
+		let mut timeline = OccupationTimeline::new(2);
+		timeline.insert(Job::release_to_deadline(0, -20, 10, -5));
+		assert_eq!(vec![OccupationInterval {
+			start: -15, num_cores: 1
+		}, OccupationInterval {
+			start: -10, num_cores: 0
+		}], timeline.intervals);
+
+		timeline.insert(Job::release_to_deadline(1, -40, 40, 10));
+		assert_eq!(vec![OccupationInterval {
+			start: -30, num_cores: 1
+		}, OccupationInterval {
+			start: -15, num_cores: 2
+		}, OccupationInterval {
+			start: -10, num_cores: 1
+		}, OccupationInterval {
+			start: 0, num_cores: 0
+		}], timeline.intervals);
+		assert_eq!(Some(1), timeline.find_interruption(-100, -12, 1));
+		assert_eq!(None, timeline.find_interruption(-100, -15, 1));
+	}
+
 	#[test]
 	fn test_simple_feasible_refinement() {
 		let mut timeline = OccupationTimeline::new(1);
@@ -640,6 +749,33 @@ mod tests {
 		assert_eq!(RefineResult::Unchanged, timeline.refine(&mut late_job));
 	}
 
+	#[test]
+	fn test_parallel_refinement() {
+		let mut timeline = OccupationTimeline::new(3);
+		let mut gang_job = Job::release_to_deadline(0, 10, 15, 30);
+		gang_job.set_parallelism(2);
+		assert!(!timeline.insert(gang_job));
+		assert_eq!(vec![OccupationInterval {
+			start: 0, num_cores: 0
+		}, OccupationInterval {
+			start: 15, num_cores: 2
+		}, OccupationInterval {
+			start: 25, num_cores: 0
+		}], timeline.intervals);
+
+		// A single core is still available while the gang job runs, but 2 cores are not
+		let mut single_job = Job::release_to_deadline(1, 12, 6, 100);
+		assert_eq!(RefineResult::Unchanged, timeline.refine(&mut single_job));
+		let mut other_gang_job = single_job;
+		other_gang_job.set_parallelism(2);
+		assert_eq!(RefineResult::ModifiedJob, timeline.refine(&mut other_gang_job));
+		assert_eq!(25, other_gang_job.earliest_start);
+
+		let mut overlapping_gang_job = Job::release_to_deadline(2, 20, 10, 30);
+		overlapping_gang_job.set_parallelism(2);
+		assert!(timeline.insert(overlapping_gang_job));
+	}
+
 	#[test]
 	fn test_refinement_shift_to_right1() {
 		let mut timeline = OccupationTimeline::new(1);
@@ -782,7 +918,8 @@ mod tests {
 				Job::release_to_deadline(2, 5, 6, 21)
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert_eq!(OccupationStrengthenResult::Modified, strengthen_bounds_using_core_occupation(&mut problem));
 		assert_eq!(0, problem.jobs[1].earliest_start);
@@ -801,7 +938,8 @@ mod tests {
 				Job::release_to_deadline(1, 7, 1, 8),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert_eq!(OccupationStrengthenResult::Infeasible, strengthen_bounds_using_core_occupation(&mut problem));
 	}
@@ -816,10 +954,50 @@ mod tests {
 				Job::release_to_deadline(4, 40, 1, 41),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		}
 	}
 
+	#[test]
+	fn test_affinity_strengthening() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 8, 10),
+				Job::release_to_deadline(1, 0, 5, 20),
+				Job::release_to_deadline(2, 0, 5, 20),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		assert_eq!(OccupationStrengthenResult::Unchanged, strengthen_bounds_using_core_occupation(&mut problem));
+
+		// Jobs 0 and 1 share core 0, so job 1 can't start before job 0 certainly finishes
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[0]));
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[0]));
+		assert_eq!(OccupationStrengthenResult::Modified, strengthen_bounds_using_core_occupation(&mut problem));
+		assert_eq!(0, problem.jobs[0].earliest_start);
+		assert_eq!(8, problem.jobs[1].earliest_start);
+		assert_eq!(0, problem.jobs[2].earliest_start);
+
+		// Job 1 must now finish before job 0 certainly starts, which is impossible
+		problem.jobs[1] = Job::release_to_deadline(1, 0, 5, 8);
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[0]));
+		assert_eq!(OccupationStrengthenResult::Infeasible, strengthen_bounds_using_core_occupation(&mut problem));
+
+		// Pinned to the other core, job 1 doesn't interfere with job 0, but both cores are certainly
+		// occupied between time 3 and 5, so job 2 can't start before time 5
+		problem.jobs[0] = Job::release_to_deadline(0, 0, 8, 10);
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[0]));
+		problem.jobs[1] = Job::release_to_deadline(1, 0, 5, 8);
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[1]));
+		assert_eq!(OccupationStrengthenResult::Modified, strengthen_bounds_using_core_occupation(&mut problem));
+		assert_eq!(0, problem.jobs[0].earliest_start);
+		assert_eq!(0, problem.jobs[1].earliest_start);
+		assert_eq!(5, problem.jobs[2].earliest_start);
+	}
+
 	#[test]
 	fn test_periodic_infeasible_strengthening() {
 		let mut problem1 = periodic_infeasible_problem();
@@ -879,4 +1057,68 @@ mod tests {
 		strengthen_bounds_using_constraints(&mut problem);
 		strengthen_bounds_using_core_occupation(&mut problem);
 	}
+
+	#[test]
+	fn test_panic_regression3() {
+		// Merging the inserted interval with both of its neighbours used to underflow
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 5, 4, 18),
+				Job::release_to_deadline(1, 3, 8, 14),
+				Job::release_to_deadline(2, 8, 1, 22),
+				Job::release_to_deadline(3, 0, 6, 17),
+			],
+			constraints: Vec::new(),
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		strengthen_bounds_using_core_occupation(&mut problem);
+	}
+
+	#[test]
+	fn test_panic_regression4() {
+		// An insertion that overflowed the core during `refine` used to leave the timeline unmerged
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 9, 2, 14),
+				Job::release_to_deadline(1, 7, 2, 15),
+				Job::release_to_deadline(2, 4, 8, 19),
+				Job::release_to_deadline(3, 0, 1, 1),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		ProblemPermutation::possible(&mut problem).unwrap();
+		strengthen_bounds_using_constraints(&mut problem);
+		assert_eq!(OccupationStrengthenResult::Infeasible, strengthen_bounds_using_core_occupation(&mut problem));
+	}
+
+	#[test]
+	fn test_panic_regression5() {
+		// Generated with `generate --seed 117 --num-jobs 7 -n 2 -u 0.8 --horizon 60 --layers 2 --max-delay 4`
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 2, 18, 27),
+				Job::release_to_deadline(1, 11, 11, 28),
+				Job::release_to_deadline(2, 37, 12, 58),
+				Job::release_to_deadline(3, 48, 3, 53),
+				Job::release_to_deadline(4, 49, 29, 90),
+				Job::release_to_deadline(5, 51, 1, 52),
+				Job::release_to_deadline(6, 31, 23, 64),
+			],
+			constraints: vec![
+				Constraint::new(0, 3, 0, ConstraintType::FinishToStart),
+				Constraint::new(0, 4, 1, ConstraintType::FinishToStart),
+				Constraint::new(2, 4, 0, ConstraintType::FinishToStart),
+				Constraint::new(2, 5, 2, ConstraintType::FinishToStart),
+				Constraint::new(0, 6, 3, ConstraintType::FinishToStart),
+			],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		ProblemPermutation::possible(&mut problem).unwrap();
+		strengthen_bounds_using_constraints(&mut problem);
+		assert_eq!(OccupationStrengthenResult::Infeasible, strengthen_bounds_using_core_occupation(&mut problem));
+	}
 }
@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+use crate::problem::*;
+
+/// Finds pairs of jobs `(i, j)` such that starting `j` before `i` would certainly cause a deadline
+/// miss, and returns an (implied) finish-to-start constraint from `i` to `j` for each such pair.
+///
+/// This is currently only done for problems with a single core, so this returns no constraints at
+/// all when `problem.num_cores > 1`. With a single core, when the earliest finish time of
+/// `j` is later than the latest start time of `i`, `j` cannot run before `i`. Since both jobs need
+/// the same core, `j` cannot start until `i` has finished. When there are multiple cores, `i` and
+/// `j` could run in parallel, so no such conclusion can be drawn.
+///
+/// Pairs whose windows don't overlap are skipped since their order is already implied by their
+/// bounds, and so are pairs that already have an equivalent finish-to-start constraint.
+///
+/// Adding these constraints to the problem allows `strengthen_bounds_using_constraints` to
+/// strengthen the bounds of the jobs further. When two jobs must precede each other, the
+/// constraints become cyclic, which means that the problem is infeasible.
+pub fn find_implied_constraints(problem: &Problem) -> Vec<Constraint> {
+	let mut implied_constraints = Vec::new();
+	if problem.num_cores != 1 {
+		return implied_constraints;
+	}
+
+	let existing_constraints: HashSet<(usize, usize)> = problem.constraints.iter().filter(
+		|c| c.get_type() == ConstraintType::FinishToStart
+	).map(|c| (c.get_before(), c.get_after())).collect();
+
+	let mut jobs_by_earliest_start: Vec<&Job> = problem.jobs.iter().collect();
+	jobs_by_earliest_start.sort_by_key(|j| j.earliest_start);
+
+	for first in &problem.jobs {
+		for second in &jobs_by_earliest_start {
+			if second.earliest_start >= first.get_latest_finish() {
+				break;
+			}
+			if first.get_index() == second.get_index() || first.earliest_start >= second.get_latest_finish() {
+				continue;
+			}
+			if second.get_earliest_finish() > first.latest_start &&
+				!existing_constraints.contains(&(first.get_index(), second.get_index())) {
+				implied_constraints.push(Constraint::new(
+					first.get_index(), second.get_index(), 0, ConstraintType::FinishToStart
+				));
+			}
+		}
+	}
+
+	implied_constraints
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::bounds::*;
+	use crate::permutation::ProblemPermutation;
+	use crate::problem::*;
+
+	#[test]
+	fn test_single_implied_constraint() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 8, 10),
+				Job::release_to_deadline(1, 1, 5, 20),
+				Job::release_to_deadline(2, 30, 5, 40),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+
+		let implied_constraints = find_implied_constraints(&problem);
+		assert_eq!(vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)], implied_constraints);
+
+		problem.constraints.extend(implied_constraints);
+		let permutation = ProblemPermutation::possible(&mut problem).unwrap();
+		assert!(strengthen_bounds_using_constraints(&mut problem));
+		permutation.transform_back(&mut problem);
+		assert_eq!(8, problem.jobs[1].earliest_start);
+		assert!(find_implied_constraints(&problem).is_empty());
+	}
+
+	#[test]
+	fn test_no_implied_constraints_with_multiple_cores() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 8, 10),
+				Job::release_to_deadline(1, 1, 5, 20),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		assert!(find_implied_constraints(&problem).is_empty());
+	}
+
+	#[test]
+	fn test_mutually_implied_constraints_are_cyclic() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 15),
+				Job::release_to_deadline(1, 5, 10, 16),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		let implied_constraints = find_implied_constraints(&problem);
+		assert_eq!(2, implied_constraints.len());
+
+		problem.constraints.extend(implied_constraints);
+		assert!(ProblemPermutation::possible(&mut problem).is_err());
+	}
+}
@@ -0,0 +1,422 @@
+use std::fmt::{Display, Formatter};
+
+use crate::permutation::ProblemPermutation;
+use crate::problem::*;
+
+/// A constraint in an infeasibility certificate: `after` must start at least `separation` time units
+/// after `before` starts. For finish-to-start constraints, the separation includes the execution
+/// time of `before`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CertifiedConstraint {
+	pub before: usize,
+	pub after: usize,
+	pub separation: Time,
+}
+
+/// The minimum amount of time during which `job` must execute within the interval of an
+/// `InfeasibilityCertificate::OverloadedInterval`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct JobDemand {
+	pub job: usize,
+	pub demand: Time,
+}
+
+/// A proof that a problem is infeasible, which contains all the numbers that are needed to check it
+/// by hand. It only relies on the original release times, execution times, deadlines, and
+/// constraints of the problem, so it can be checked without any of the analyses of this crate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InfeasibilityCertificate {
+	/// `release + execution_time > deadline`
+	EmptyWindow { job: usize, release: Time, execution_time: Time, deadline: Time },
+	/// `release + constraint.separation + execution_time > deadline`, where `release` is the release
+	/// time of `constraint.before`, and `execution_time` and `deadline` belong to `constraint.after`
+	UnsatisfiableConstraint { constraint: CertifiedConstraint, release: Time, execution_time: Time, deadline: Time },
+	/// The `after` job of each constraint is the `before` job of the next constraint (and the `after`
+	/// job of the last constraint is the `before` job of the first constraint). Since no job can be
+	/// dispatched before its predecessors, none of these jobs can be dispatched first.
+	Cycle(Vec<CertifiedConstraint>),
+	/// The jobs must execute for at least the sum of their demands between `start` and `end`, which
+	/// exceeds the `capacity` of all cores during that interval (`num_cores * (end - start)`)
+	OverloadedInterval { start: Time, end: Time, capacity: Time, demands: Vec<JobDemand> },
+}
+
+/// The reason why an `InfeasibilityCertificate` is rejected
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InfeasibilityCertificateError {
+	UnknownJob(usize),
+	DuplicateJob(usize),
+	/// The release time, execution time, deadline, or demand of this job doesn't match the problem
+	WrongJobValue(usize),
+	/// The problem has no constraint from `before` to `after` with the given separation
+	MissingConstraint { before: usize, after: usize },
+	BrokenCycle,
+	/// The `end` of an overloaded interval is not after its `start`
+	EmptyInterval,
+	WrongCapacity,
+	/// All numbers are correct, but they don't contradict each other
+	NoContradiction,
+}
+
+/// Computes the minimum amount of time during which `job` must execute between `start` and `end`,
+/// which is attained when it starts as early or as late as possible
+fn get_minimum_demand(job: &Job, start: Time, end: Time) -> Time {
+	let execution_time = job.get_execution_time();
+	// The interval of a certificate can be huge, so the (negative) demands outside it may not fit
+	let demand_when_early = Time::min(job.earliest_start + execution_time, end)
+		.saturating_sub(Time::max(job.earliest_start, start));
+	let latest_start = job.get_deadline() - execution_time;
+	let demand_when_late = Time::min(latest_start + execution_time, end).saturating_sub(Time::max(latest_start, start));
+	Time::max(0, Time::min(demand_when_early, demand_when_late))
+}
+
+fn get_separation(problem: &Problem, constraint: &Constraint) -> Time {
+	match constraint.get_type() {
+		ConstraintType::StartToStart => constraint.get_delay(),
+		ConstraintType::FinishToStart => constraint.get_delay() + problem.jobs[constraint.get_before()].get_execution_time(),
+	}
+}
+
+fn certify_constraint(problem: &Problem, constraint: &Constraint) -> CertifiedConstraint {
+	CertifiedConstraint {
+		before: constraint.get_before(),
+		after: constraint.get_after(),
+		separation: get_separation(problem, constraint),
+	}
+}
+
+/// Tries to find an `InfeasibilityCertificate` for `problem`, whose bounds must not have been
+/// strengthened. It tries the certificate kinds in the order in which they are declared, and returns
+/// the first one that it finds.
+///
+/// Note that the necessary tests can prove the infeasibility of many problems that don't have such
+/// a certificate, since they strengthen the bounds of the jobs first. In that case, this returns
+/// `None`.
+pub fn find_infeasibility_certificate(problem: &Problem) -> Option<InfeasibilityCertificate> {
+	if let Some(job) = problem.jobs.iter().find(|job| job.earliest_start + job.get_execution_time() > job.get_deadline()) {
+		return Some(InfeasibilityCertificate::EmptyWindow {
+			job: job.get_index(), release: job.earliest_start,
+			execution_time: job.get_execution_time(), deadline: job.get_deadline()
+		});
+	}
+
+	for constraint in &problem.constraints {
+		let constraint = certify_constraint(problem, constraint);
+		let (before, after) = (&problem.jobs[constraint.before], &problem.jobs[constraint.after]);
+		if before.earliest_start + constraint.separation + after.get_execution_time() > after.get_deadline() {
+			return Some(InfeasibilityCertificate::UnsatisfiableConstraint {
+				constraint, release: before.earliest_start,
+				execution_time: after.get_execution_time(), deadline: after.get_deadline()
+			});
+		}
+	}
+
+	if let Err(cycle) = ProblemPermutation::possible(&mut problem.clone()) {
+		return Some(InfeasibilityCertificate::Cycle(cycle.constraints.iter().map(
+			|index| certify_constraint(problem, &problem.constraints[*index])
+		).collect()));
+	}
+
+	find_overloaded_interval(problem)
+}
+
+/// Searches for an interval during which the jobs must execute for longer than the cores can.
+///
+/// For a fixed `start`, the demand of each job is 0 until `end` reaches some offset, after which it
+/// grows linearly until it reaches its maximum. The total demand is therefore piecewise linear in
+/// `end`, so it suffices to check the times at which its slope changes, which takes
+/// `O(n log(n))` time using a sweep. Likewise, the demand only changes slope in `start` at the
+/// release times and latest start times of the jobs, so only those are tried, which takes
+/// `O(n^2 log(n))` time in total.
+fn find_overloaded_interval(problem: &Problem) -> Option<InfeasibilityCertificate> {
+	let mut start_candidates: Vec<Time> = problem.jobs.iter().flat_map(
+		|job| [job.earliest_start, job.get_deadline() - job.get_execution_time()]
+	).collect();
+	start_candidates.sort_unstable();
+	start_candidates.dedup();
+
+	let num_cores = problem.num_cores as Time;
+	let mut slope_changes = Vec::with_capacity(2 * problem.jobs.len());
+	for start in start_candidates {
+		slope_changes.clear();
+		for job in &problem.jobs {
+			let maximum_demand = Time::min(
+				job.get_execution_time(), job.earliest_start + job.get_execution_time() - start
+			);
+			if maximum_demand > 0 {
+				let growth_start = Time::max(start, job.get_deadline() - job.get_execution_time());
+				slope_changes.push((growth_start, 1));
+				slope_changes.push((growth_start + maximum_demand, -1));
+			}
+		}
+		slope_changes.sort_unstable();
+
+		let (mut time, mut demand, mut slope) = (start, 0, 0);
+		for &(next_time, slope_change) in &slope_changes {
+			demand += slope * (next_time - time);
+			time = next_time;
+			slope += slope_change;
+			// A capacity that doesn't fit in a `Time` can't be exceeded by the demand, which does
+			if num_cores.checked_mul(time - start).is_some_and(|capacity| demand > capacity) {
+				let end = time;
+				let demands = problem.jobs.iter().map(
+					|job| JobDemand { job: job.get_index(), demand: get_minimum_demand(job, start, end) }
+				).filter(|demand| demand.demand > 0).collect();
+				return Some(InfeasibilityCertificate::OverloadedInterval {
+					start, end, capacity: num_cores * (end - start), demands
+				});
+			}
+		}
+	}
+
+	None
+}
+
+/// Checks whether `certificate` proves that `problem` is infeasible: all numbers in the certificate
+/// are recomputed from the original jobs and constraints of `problem`, after which the contradiction
+/// is checked. This shares no code with the necessary tests. The contradiction is computed using
+/// 128-bit arithmetic, so huge numbers in a certificate can't cause an overflow.
+pub fn verify_infeasibility_certificate(
+	problem: &Problem, certificate: &InfeasibilityCertificate
+) -> Result<(), InfeasibilityCertificateError> {
+	let get_job = |job: usize| problem.jobs.get(job).ok_or(InfeasibilityCertificateError::UnknownJob(job));
+	let check_constraint = |certified: &CertifiedConstraint| {
+		get_job(certified.before)?;
+		get_job(certified.after)?;
+		if problem.constraints.iter().any(|constraint| constraint.get_before() == certified.before &&
+				constraint.get_after() == certified.after &&
+				get_separation(problem, constraint) == certified.separation) {
+			Ok(())
+		} else {
+			Err(InfeasibilityCertificateError::MissingConstraint { before: certified.before, after: certified.after })
+		}
+	};
+
+	let has_contradiction = match certificate {
+		InfeasibilityCertificate::EmptyWindow { job, release, execution_time, deadline } => {
+			let actual = get_job(*job)?;
+			if (actual.earliest_start, actual.get_execution_time(), actual.get_deadline()) != (*release, *execution_time, *deadline) {
+				return Err(InfeasibilityCertificateError::WrongJobValue(*job));
+			}
+			*release as i128 + *execution_time as i128 > *deadline as i128
+		},
+		InfeasibilityCertificate::UnsatisfiableConstraint { constraint, release, execution_time, deadline } => {
+			check_constraint(constraint)?;
+			if get_job(constraint.before)?.earliest_start != *release {
+				return Err(InfeasibilityCertificateError::WrongJobValue(constraint.before));
+			}
+			let after = get_job(constraint.after)?;
+			if (after.get_execution_time(), after.get_deadline()) != (*execution_time, *deadline) {
+				return Err(InfeasibilityCertificateError::WrongJobValue(constraint.after));
+			}
+			*release as i128 + constraint.separation as i128 + *execution_time as i128 > *deadline as i128
+		},
+		InfeasibilityCertificate::Cycle(constraints) => {
+			for (position, constraint) in constraints.iter().enumerate() {
+				check_constraint(constraint)?;
+				if constraint.after != constraints[(position + 1) % constraints.len()].before {
+					return Err(InfeasibilityCertificateError::BrokenCycle);
+				}
+			}
+			!constraints.is_empty()
+		},
+		InfeasibilityCertificate::OverloadedInterval { start, end, capacity, demands } => {
+			// An empty interval would have a non-positive capacity, which even zero demand exceeds
+			if end <= start {
+				return Err(InfeasibilityCertificateError::EmptyInterval);
+			}
+			if *capacity as i128 != problem.num_cores as i128 * (*end as i128 - *start as i128) {
+				return Err(InfeasibilityCertificateError::WrongCapacity);
+			}
+			let mut is_counted = vec![false; problem.jobs.len()];
+			for demand in demands {
+				let job = get_job(demand.job)?;
+				if is_counted[demand.job] {
+					return Err(InfeasibilityCertificateError::DuplicateJob(demand.job));
+				}
+				is_counted[demand.job] = true;
+				if get_minimum_demand(job, *start, *end) != demand.demand {
+					return Err(InfeasibilityCertificateError::WrongJobValue(demand.job));
+				}
+			}
+			demands.iter().map(|demand| demand.demand as i128).sum::<i128>() > *capacity as i128
+		},
+	};
+
+	if has_contradiction {
+		Ok(())
+	} else {
+		Err(InfeasibilityCertificateError::NoContradiction)
+	}
+}
+
+impl Display for CertifiedConstraint {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Job {} must start at least {} time units after job {} starts", self.after, self.separation, self.before)
+	}
+}
+
+impl Display for InfeasibilityCertificate {
+	/// Explains the certificate, including its arithmetic, in words
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::EmptyWindow { job, release, execution_time, deadline } => writeln!(
+				f, "Job {} is released at time {} and needs {} time units, so it finishes at time {} or later, \
+				after its deadline at time {}", job, release, execution_time, *release as i128 + *execution_time as i128, deadline
+			),
+			Self::UnsatisfiableConstraint { constraint, release, execution_time, deadline } => writeln!(
+				f, "{}. Since job {} is released at time {}, job {} can't finish before {} + {} + {} = {}, \
+				which is after its deadline at time {}", constraint, constraint.before, release, constraint.after,
+				release, constraint.separation, execution_time,
+				*release as i128 + constraint.separation as i128 + *execution_time as i128, deadline
+			),
+			Self::Cycle(constraints) => {
+				writeln!(f, "The following constraints form a cycle, so none of these jobs can be dispatched first:")?;
+				for constraint in constraints {
+					writeln!(f, "{}", constraint)?;
+				}
+				Ok(())
+			},
+			Self::OverloadedInterval { start, end, capacity, demands } => {
+				let total_demand: i128 = demands.iter().map(|demand| demand.demand as i128).sum();
+				writeln!(
+					f, "Between time {} and {}, the cores can execute for at most {} time units, but these {} jobs \
+					need at least {} time units:", start, end, capacity, demands.len(), total_demand
+				)?;
+				for demand in demands {
+					writeln!(f, "Job {} needs at least {} time units", demand.job, demand.demand)?;
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::certificate::*;
+	use crate::problem::*;
+
+	fn problem(jobs: Vec<Job>, constraints: Vec<Constraint>, num_cores: u32) -> Problem {
+		Problem { jobs, constraints, num_cores, resources: Vec::new() }
+	}
+
+	#[test]
+	fn test_empty_window() {
+		let problem = problem(vec![
+			Job::release_to_deadline(0, 0, 5, 10), Job::release_to_deadline(1, 6, 5, 10)
+		], Vec::new(), 1);
+		let certificate = find_infeasibility_certificate(&problem).unwrap();
+		assert_eq!(InfeasibilityCertificate::EmptyWindow { job: 1, release: 6, execution_time: 5, deadline: 10 }, certificate);
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, &certificate));
+
+		let wrong = InfeasibilityCertificate::EmptyWindow { job: 1, release: 7, execution_time: 5, deadline: 10 };
+		assert_eq!(Err(InfeasibilityCertificateError::WrongJobValue(1)), verify_infeasibility_certificate(&problem, &wrong));
+		let feasible = InfeasibilityCertificate::EmptyWindow { job: 0, release: 0, execution_time: 5, deadline: 10 };
+		assert_eq!(Err(InfeasibilityCertificateError::NoContradiction), verify_infeasibility_certificate(&problem, &feasible));
+	}
+
+	#[test]
+	fn test_unsatisfiable_constraint() {
+		let problem = problem(vec![
+			Job::release_to_deadline(0, 0, 5, 20), Job::release_to_deadline(1, 0, 5, 15)
+		], vec![Constraint::new(0, 1, 6, ConstraintType::FinishToStart)], 2);
+		let certificate = find_infeasibility_certificate(&problem).unwrap();
+		assert_eq!(InfeasibilityCertificate::UnsatisfiableConstraint {
+			constraint: CertifiedConstraint { before: 0, after: 1, separation: 11 },
+			release: 0, execution_time: 5, deadline: 15
+		}, certificate);
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, &certificate));
+	}
+
+	#[test]
+	fn test_cycle() {
+		let problem = problem(vec![
+			Job::release_to_deadline(0, 0, 5, 100),
+			Job::release_to_deadline(1, 0, 5, 100),
+			Job::release_to_deadline(2, 0, 5, 100),
+		], vec![
+			Constraint::new(0, 1, 0, ConstraintType::StartToStart),
+			Constraint::new(1, 2, 0, ConstraintType::FinishToStart),
+			Constraint::new(2, 1, 3, ConstraintType::StartToStart),
+		], 2);
+		let certificate = find_infeasibility_certificate(&problem).unwrap();
+		let InfeasibilityCertificate::Cycle(constraints) = &certificate else { panic!("Expected a cycle") };
+		assert_eq!(2, constraints.len());
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, &certificate));
+
+		let broken = InfeasibilityCertificate::Cycle(vec![
+			CertifiedConstraint { before: 0, after: 1, separation: 0 },
+			CertifiedConstraint { before: 1, after: 2, separation: 5 },
+		]);
+		assert_eq!(Err(InfeasibilityCertificateError::BrokenCycle), verify_infeasibility_certificate(&problem, &broken));
+		let missing = InfeasibilityCertificate::Cycle(vec![
+			CertifiedConstraint { before: 1, after: 2, separation: 0 },
+			CertifiedConstraint { before: 2, after: 1, separation: 3 },
+		]);
+		assert_eq!(
+			Err(InfeasibilityCertificateError::MissingConstraint { before: 1, after: 2 }),
+			verify_infeasibility_certificate(&problem, &missing)
+		);
+		let empty = InfeasibilityCertificate::Cycle(Vec::new());
+		assert_eq!(Err(InfeasibilityCertificateError::NoContradiction), verify_infeasibility_certificate(&problem, &empty));
+	}
+
+	#[test]
+	fn test_overloaded_interval() {
+		// Jobs 0 and 1 must both run between time 10 and 15 on the only core
+		let problem = problem(vec![
+			Job::release_to_deadline(0, 5, 10, 20),
+			Job::release_to_deadline(1, 10, 5, 15),
+			Job::release_to_deadline(2, 0, 3, 12),
+		], Vec::new(), 1);
+		let certificate = find_infeasibility_certificate(&problem).unwrap();
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, &certificate));
+		let InfeasibilityCertificate::OverloadedInterval { start, end, .. } = certificate else {
+			panic!("Expected an overloaded interval")
+		};
+		assert!(start < end);
+
+		let by_hand = InfeasibilityCertificate::OverloadedInterval { start: 10, end: 15, capacity: 5, demands: vec![
+			JobDemand { job: 0, demand: 5 }, JobDemand { job: 1, demand: 5 }
+		] };
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, &by_hand));
+		let exaggerated = InfeasibilityCertificate::OverloadedInterval { start: 10, end: 15, capacity: 5, demands: vec![
+			JobDemand { job: 0, demand: 5 }, JobDemand { job: 2, demand: 2 }
+		] };
+		assert_eq!(Err(InfeasibilityCertificateError::WrongJobValue(2)), verify_infeasibility_certificate(&problem, &exaggerated));
+		let underloaded = InfeasibilityCertificate::OverloadedInterval { start: 10, end: 15, capacity: 5, demands: vec![
+			JobDemand { job: 0, demand: 5 }
+		] };
+		assert_eq!(Err(InfeasibilityCertificateError::NoContradiction), verify_infeasibility_certificate(&problem, &underloaded));
+
+		let two_cores = Problem { num_cores: 2, ..problem };
+		assert_eq!(None, find_infeasibility_certificate(&two_cores));
+	}
+
+	#[test]
+	fn test_empty_interval() {
+		// A reversed interval has a negative capacity, which would be exceeded by zero demand
+		let feasible = problem(vec![Job::release_to_deadline(0, 0, 5, 100)], Vec::new(), 1);
+		let reversed = InfeasibilityCertificate::OverloadedInterval { start: 10, end: 5, capacity: -5, demands: Vec::new() };
+		assert_eq!(Err(InfeasibilityCertificateError::EmptyInterval), verify_infeasibility_certificate(&feasible, &reversed));
+		let empty = InfeasibilityCertificate::OverloadedInterval { start: 10, end: 10, capacity: 0, demands: Vec::new() };
+		assert_eq!(Err(InfeasibilityCertificateError::EmptyInterval), verify_infeasibility_certificate(&feasible, &empty));
+	}
+
+	#[test]
+	fn test_huge_certificate_values() {
+		let feasible = problem(vec![Job::release_to_deadline(0, 0, 5, 100)], Vec::new(), 2);
+		let huge = InfeasibilityCertificate::OverloadedInterval {
+			start: Time::MIN, end: Time::MAX, capacity: -2, demands: vec![JobDemand { job: 0, demand: 5 }]
+		};
+		assert_eq!(Err(InfeasibilityCertificateError::WrongCapacity), verify_infeasibility_certificate(&feasible, &huge));
+		let late = InfeasibilityCertificate::EmptyWindow { job: 0, release: Time::MAX, execution_time: 5, deadline: 100 };
+		assert_eq!(Err(InfeasibilityCertificateError::WrongJobValue(0)), verify_infeasibility_certificate(&feasible, &late));
+
+		let overflowing = problem(vec![Job::release_to_deadline(0, Time::MAX - 4, 5, Time::MAX - 1)], Vec::new(), 1);
+		let certificate = InfeasibilityCertificate::EmptyWindow {
+			job: 0, release: Time::MAX - 4, execution_time: 5, deadline: Time::MAX - 1
+		};
+		assert_eq!(Ok(()), verify_infeasibility_certificate(&overflowing, &certificate));
+	}
+}
@@ -0,0 +1,13 @@
+mod infeasibility;
+mod schedule;
+
+pub use infeasibility::*;
+pub use schedule::*;
+
+/// A certificate of either verdict: a schedule proves that a problem is feasible, and an
+/// `InfeasibilityCertificate` proves that it is infeasible
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Certificate {
+	Schedule(Vec<CertifiedStart>),
+	Infeasibility(InfeasibilityCertificate),
+}
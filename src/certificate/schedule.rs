@@ -0,0 +1,272 @@
+use crate::problem::*;
+use crate::schedule::Schedule;
+
+/// A single line of a schedule certificate: the job, the time at which it starts, and the core on
+/// which it runs
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CertifiedStart {
+	pub job: usize,
+	pub start: Time,
+	pub core: usize,
+}
+
+/// The reason why a schedule certificate is rejected. Each variant contains the index of the job
+/// (or constraint) that is involved, except for `UnknownJob`, which contains the position of the
+/// offending line in the certificate.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CertificateViolation {
+	UnknownJob(usize),
+	DuplicateJob(usize),
+	MissingJob(usize),
+	UnknownCore(usize),
+	/// The job runs on a core that is not in its affinity
+	ViolatesAffinity(usize),
+	StartsBeforeRelease(usize),
+	FinishesAfterDeadline(usize),
+	ViolatesConstraint(usize),
+	/// `second` starts on `core` while `first` is still running there
+	CoreOccupied { core: usize, first: usize, second: usize },
+	/// `second` starts while `first`, which uses the same shared resource, is still running
+	ResourceOccupied { resource: usize, first: usize, second: usize },
+}
+
+/// Creates the certificate of `schedule`, sorted by job index
+pub fn create_schedule_certificate(schedule: &Schedule) -> Vec<CertifiedStart> {
+	let mut certificate: Vec<CertifiedStart> = schedule.get_jobs().iter().map(
+		|scheduled| CertifiedStart { job: scheduled.job, start: scheduled.start, core: scheduled.core }
+	).collect();
+	certificate.sort_by_key(|entry| entry.job);
+	certificate
+}
+
+/// Checks whether `certificate` proves that `problem` is feasible. This deliberately shares no
+/// code with the `Simulator` or `Schedule::validate`, so that it can be used to double-check their
+/// results (and those of the exact solvers). It only uses the original release times, execution
+/// times, and deadlines of the jobs, so `problem` must not have been strengthened.
+///
+/// Instead of comparing neighbouring jobs, the core and resource capacity is checked by sweeping
+/// over the start and finish times of all jobs, while tracking which job occupies each core and
+/// each resource.
+///
+/// Huge start times in the certificate can't cause an overflow: a job whose finish time doesn't
+/// fit in a `Time` finishes after its deadline, and constraints are checked using 128-bit
+/// arithmetic.
+///
+/// Returns the first violation that is encountered, or `Ok(())` if the certificate is valid.
+pub fn check_schedule_certificate(
+	problem: &Problem, certificate: &[CertifiedStart]
+) -> Result<(), CertificateViolation> {
+	let num_jobs = problem.jobs.len();
+	let mut start_times: Vec<Option<Time>> = vec![None; num_jobs];
+	let mut cores = vec![Vec::new(); num_jobs];
+	for (position, entry) in certificate.iter().enumerate() {
+		if entry.job >= num_jobs {
+			return Err(CertificateViolation::UnknownJob(position));
+		}
+
+		// A gang job has one line for each core that it occupies, all with the same start time
+		if cores[entry.job].len() >= problem.jobs[entry.job].get_parallelism() as usize ||
+				start_times[entry.job].is_some_and(|start| start != entry.start) {
+			return Err(CertificateViolation::DuplicateJob(entry.job));
+		}
+		if entry.core >= problem.num_cores as usize {
+			return Err(CertificateViolation::UnknownCore(entry.job));
+		}
+
+		let job = &problem.jobs[entry.job];
+		if !job.get_affinity().contains(entry.core) {
+			return Err(CertificateViolation::ViolatesAffinity(entry.job));
+		}
+		if entry.start < job.earliest_start {
+			return Err(CertificateViolation::StartsBeforeRelease(entry.job));
+		}
+		if entry.start.checked_add(job.get_execution_time()).is_none_or(|finish| finish > job.get_deadline()) {
+			return Err(CertificateViolation::FinishesAfterDeadline(entry.job));
+		}
+		start_times[entry.job] = Some(entry.start);
+		cores[entry.job].push(entry.core);
+	}
+
+	if let Some(missing_job) = (0 .. num_jobs).find(
+		|job| cores[*job].len() < problem.jobs[*job].get_parallelism() as usize
+	) {
+		return Err(CertificateViolation::MissingJob(missing_job));
+	}
+	let start_times: Vec<Time> = start_times.into_iter().flatten().collect();
+
+	for (index, constraint) in problem.constraints.iter().enumerate() {
+		// The finish times fit in a `Time` since they are at most the deadlines, but the separation
+		// between two jobs may not
+		let before = constraint.get_before();
+		let reference_time = match constraint.get_type() {
+			ConstraintType::StartToStart => start_times[before],
+			ConstraintType::FinishToStart => start_times[before] + problem.jobs[before].get_execution_time(),
+		};
+		let separation = start_times[constraint.get_after()] as i128 - reference_time as i128;
+		if separation < constraint.get_delay() as i128 ||
+				constraint.get_max_delay().is_some_and(|max_delay| separation > max_delay as i128) {
+			return Err(CertificateViolation::ViolatesConstraint(index));
+		}
+	}
+
+	let mut resources_of_jobs = vec![Vec::new(); num_jobs];
+	for (resource, users) in problem.resources.iter().enumerate() {
+		for &job in users {
+			resources_of_jobs[job].push(resource);
+		}
+	}
+
+	// Each event is (time, is_start, job). At equal times, finish events come before start events,
+	// since a job can start at the moment another job finishes.
+	let mut events = Vec::with_capacity(2 * num_jobs);
+	for (job, start) in start_times.iter().enumerate() {
+		events.push((*start, true, job));
+		events.push((*start + problem.jobs[job].get_execution_time(), false, job));
+	}
+	events.sort_unstable();
+
+	let mut core_occupants: Vec<Option<usize>> = vec![None; problem.num_cores as usize];
+	let mut resource_occupants: Vec<Option<usize>> = vec![None; problem.resources.len()];
+	for (_, is_start, job) in events {
+		if is_start {
+			for &core in &cores[job] {
+				if let Some(first) = core_occupants[core] {
+					return Err(CertificateViolation::CoreOccupied { core, first, second: job });
+				}
+				core_occupants[core] = Some(job);
+			}
+			for &resource in &resources_of_jobs[job] {
+				if let Some(first) = resource_occupants[resource] {
+					return Err(CertificateViolation::ResourceOccupied { resource, first, second: job });
+				}
+				resource_occupants[resource] = Some(job);
+			}
+		} else {
+			for &core in &cores[job] {
+				core_occupants[core] = None;
+			}
+			for &resource in &resources_of_jobs[job] {
+				resource_occupants[resource] = None;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::certificate::*;
+	use crate::problem::*;
+
+	fn problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 20),
+				Job::release_to_deadline(1, 5, 10, 30),
+				Job::release_to_deadline(2, 0, 5, 40),
+			],
+			constraints: vec![Constraint::new(0, 1, 3, ConstraintType::StartToStart)],
+			num_cores: 2,
+			resources: vec![vec![0, 2]],
+		}
+	}
+
+	fn certificate(starts: [(Time, usize); 3]) -> Vec<CertifiedStart> {
+		starts.iter().enumerate().map(|(job, (start, core))| CertifiedStart { job, start: *start, core: *core }).collect()
+	}
+
+	#[test]
+	fn test_valid_certificate() {
+		let problem = problem();
+		assert_eq!(Ok(()), check_schedule_certificate(&problem, &certificate([(0, 0), (5, 1), (10, 0)])));
+
+		// Jobs can use a core or resource from the moment the previous job finishes, and the order
+		// of the lines is irrelevant
+		let mut reversed = certificate([(5, 0), (10, 1), (0, 0)]);
+		reversed.reverse();
+		assert_eq!(Ok(()), check_schedule_certificate(&problem, &reversed));
+	}
+
+	#[test]
+	fn test_invalid_certificates() {
+		let problem = problem();
+		let check = |starts| check_schedule_certificate(&problem, &certificate(starts));
+		assert_eq!(Err(CertificateViolation::UnknownCore(1)), check([(0, 0), (5, 2), (10, 0)]));
+		assert_eq!(Err(CertificateViolation::StartsBeforeRelease(1)), check([(0, 0), (4, 1), (10, 0)]));
+		assert_eq!(Err(CertificateViolation::FinishesAfterDeadline(0)), check([(11, 0), (15, 1), (0, 0)]));
+		assert_eq!(Err(CertificateViolation::ViolatesConstraint(0)), check([(3, 0), (5, 1), (13, 0)]));
+		assert_eq!(
+			Err(CertificateViolation::CoreOccupied { core: 0, first: 0, second: 1 }),
+			check([(0, 0), (5, 0), (20, 1)])
+		);
+		assert_eq!(
+			Err(CertificateViolation::ResourceOccupied { resource: 0, first: 2, second: 0 }),
+			check([(4, 0), (10, 0), (0, 1)])
+		);
+
+		let mut incomplete = certificate([(0, 0), (5, 1), (10, 0)]);
+		incomplete.pop();
+		assert_eq!(Err(CertificateViolation::MissingJob(2)), check_schedule_certificate(&problem, &incomplete));
+		incomplete.push(incomplete[0]);
+		assert_eq!(Err(CertificateViolation::DuplicateJob(0)), check_schedule_certificate(&problem, &incomplete));
+		incomplete[2].job = 3;
+		assert_eq!(Err(CertificateViolation::UnknownJob(2)), check_schedule_certificate(&problem, &incomplete));
+	}
+
+	#[test]
+	fn test_huge_certificate_times() {
+		let problem = problem();
+		assert_eq!(
+			Err(CertificateViolation::FinishesAfterDeadline(0)),
+			check_schedule_certificate(&problem, &certificate([(Time::MAX - 5, 0), (5, 1), (10, 0)]))
+		);
+
+		let mut constraint = Constraint::new(0, 1, 0, ConstraintType::StartToStart);
+		constraint.set_max_delay(Some(5));
+		let far_apart = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, Time::MIN, 1, Time::MIN + 1),
+				Job::release_to_deadline(1, Time::MAX - 10, 1, Time::MAX),
+			],
+			constraints: vec![constraint],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let certificate = vec![
+			CertifiedStart { job: 0, start: Time::MIN, core: 0 },
+			CertifiedStart { job: 1, start: Time::MAX - 10, core: 0 },
+		];
+		assert_eq!(Err(CertificateViolation::ViolatesConstraint(0)), check_schedule_certificate(&far_apart, &certificate));
+	}
+
+	#[test]
+	fn test_affinity_certificate() {
+		let mut problem = problem();
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[0]));
+		assert_eq!(
+			Err(CertificateViolation::ViolatesAffinity(1)),
+			check_schedule_certificate(&problem, &certificate([(0, 0), (5, 1), (10, 0)]))
+		);
+		assert_eq!(Ok(()), check_schedule_certificate(&problem, &certificate([(0, 0), (10, 0), (20, 1)])));
+	}
+
+	#[test]
+	fn test_parallel_job_certificate() {
+		let mut problem = problem();
+		problem.jobs[2].set_parallelism(2);
+		let mut certificate = certificate([(0, 0), (5, 1), (15, 0)]);
+		assert_eq!(Err(CertificateViolation::MissingJob(2)), check_schedule_certificate(&problem, &certificate));
+
+		certificate.push(CertifiedStart { job: 2, start: 15, core: 1 });
+		assert_eq!(Ok(()), check_schedule_certificate(&problem, &certificate));
+
+		certificate[3].start = 14;
+		assert_eq!(Err(CertificateViolation::DuplicateJob(2)), check_schedule_certificate(&problem, &certificate));
+		certificate[2].start = 14;
+		assert_eq!(
+			Err(CertificateViolation::CoreOccupied { core: 1, first: 1, second: 2 }),
+			check_schedule_certificate(&problem, &certificate)
+		);
+	}
+}
@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use np_feasibility::problem::Time;
 
 const APP_NAME: &str = env!("CARGO_PKG_NAME");
 const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
@@ -9,16 +10,370 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[command(version = VERSION)]
 #[command(author = AUTHOR)]
 #[command(about = "Non-preemptive feasibility test/static schedule generator", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Args {
-	/// The CSV file containing the jobs
-	#[arg(short, long)]
-	pub jobs_file: String,
+	#[command(subcommand)]
+	pub command: Option<Command>,
+
+	/// The CSV file containing the jobs. Besides SAG job files, this can have the columns `Arrival,
+	/// Execution Time, Deadline`, optionally followed by `Parallelism`: the number of cores that a
+	/// (gang) job needs at the same time, and `Affinity`: the cores on which the job may run,
+	/// separated by spaces.
+	#[arg(short, long, required = true)]
+	pub jobs_file: Option<String>,
 
-	/// The CSV file containing the (precedence) constraints
+	/// The CSV file containing the (precedence) constraints. Each line contains the `before` job,
+	/// `after` job, delay, and type (`f-s` or `s-s`), optionally followed by a maximum delay: the
+	/// largest time that the scheduler allows between the `before` job and the start of the `after`
+	/// job.
 	#[arg(short, long)]
 	pub precedence_file: Option<String>,
 
+	/// The CSV file containing the shared resources: each line contains the name of a resource and
+	/// a job that needs exclusive access to it
+	#[arg(short, long)]
+	pub resources_file: Option<String>,
+
+	/// The CSV file containing the HI jobs of a mixed-criticality problem: each line contains a job
+	/// and its HI execution time. When given, the problem is analyzed in LO mode (with all jobs) and
+	/// in HI mode (with only the HI jobs, using their HI execution times). The `--verify`,
+	/// `--check-certificate`, and `--sensitivity` options only consider LO mode.
+	#[arg(
+		short, long, conflicts_with_all = [
+			"prefix_order", "trace", "chrome_trace", "dispatch_table", "sag_schedule", "mast", "certificate"
+		]
+	)]
+	pub criticalities_file: Option<String>,
+
+	/// The CSV file containing the preemption points of jobs: each line contains a job, followed by
+	/// the execution times of its non-preemptive segments, which must add up to its execution time.
+	/// Each segment is analyzed as a separate job that can only start after the previous segment has
+	/// finished, possibly on another core. The schedule is reported in terms of the original jobs,
+	/// but the other messages refer to the segments.
+	#[arg(
+		long, conflicts_with_all = [
+			"criticalities_file", "verify", "check_certificate", "sensitivity", "minimize", "prefix_order",
+			"sag_schedule", "mast", "certificate", "features"
+		]
+	)]
+	pub segments_file: Option<String>,
+
+	/// The length of the hyperperiod of the periodic tasks whose jobs are in the jobs file. When
+	/// given, `--num-hyperperiods` consecutive copies of the problem are analyzed, so that jobs near
+	/// the end of the hyperperiod interfere with the jobs of the next one. Job `j` of copy `k` gets
+	/// index `k * <number of jobs> + j`. If the unrolled problem is infeasible, so is the steady state.
+	/// Otherwise, the verdict is only FEASIBLE for the unrolled hyperperiods, so no schedule
+	/// certificate is written.
+	#[arg(long, conflicts_with_all = ["segments_file", "verify", "check_certificate"])]
+	pub hyperperiod: Option<Time>,
+
+	/// The number of consecutive hyperperiods that are analyzed when `--hyperperiod` is given
+	#[arg(long, default_value_t = 2, requires = "hyperperiod")]
+	pub num_hyperperiods: usize,
+
 	/// The number of jobs that the target system can run in parallel
+	#[arg(short, long, required = true)]
+	pub num_cores: Option<u32>,
+
+	/// Print some statistics of the problem, like its utilization and maximum chain depth, as well as
+	/// its fingerprint
+	#[arg(long)]
+	pub stats: bool,
+
+	/// Instead of analyzing the problem, check whether the dispatch order in this file is a feasible
+	/// solution. Each line contains a job index, and optionally a start time.
+	#[arg(long, conflicts_with_all = ["policy", "exact"])]
+	pub verify: Option<String>,
+
+	/// Instead of analyzing the problem, check whether the certificate in this file (as written by
+	/// `--certificate`) proves that it is feasible or infeasible. This check is independent of the
+	/// simulator, the exact methods, and the necessary tests.
+	#[arg(long, conflicts_with_all = ["verify", "policy", "exact", "sensitivity", "minimize"])]
+	pub check_certificate: Option<String>,
+
+	/// Instead of analyzing the problem, find the largest percentage by which the execution time of
+	/// the given job can be inflated, such that the necessary tests can't prove infeasibility. When
+	/// no job is given, this is done for each job, and for all jobs together. When `--policy` or
+	/// `--exact` is given, the inflated problem must also be solved by it.
+	#[arg(long, num_args = 0..=1, conflicts_with_all = ["verify", "optimize"])]
+	pub sensitivity: Option<Option<usize>>,
+
+	/// Print the execution window of each job after the bounds have been strengthened, as well as the
+	/// number of jobs whose start time became fixed, and the average shrinkage of the windows
+	#[arg(long)]
+	pub narrowing: bool,
+
+	/// When the necessary tests prove that the problem is infeasible, also search for a small subset
+	/// of the jobs and constraints that is already infeasible on its own
+	#[arg(long)]
+	pub shrink: bool,
+
+	/// Instead of analyzing the problem, check whether the necessary tests prove that it is
+	/// infeasible while `--policy` or `--exact` finds a feasible schedule, which indicates a bug in
+	/// one of them. If so, minimize the problem while this discrepancy persists, and write the
+	/// result to `<MINIMIZE>.csv` and `<MINIMIZE>.prec.csv`.
+	#[arg(long, conflicts_with_all = ["verify", "sensitivity", "criticalities_file"])]
+	pub minimize: Option<String>,
+
+	/// Instead of analyzing the problem, start an interactive session in which individual passes
+	/// (like the bound strengthening, the necessary tests, and the scheduling policies) can be run,
+	/// the bounds of jobs can be inspected, and hypothetical constraints can be added. Type `help` in
+	/// the session for a list of commands.
+	#[arg(long, conflicts_with_all = ["verify", "check_certificate", "sensitivity", "minimize"])]
+	pub interactive: bool,
+
+	/// Write the start, finish, and idle events of the schedule that is found (or verified) to this
+	/// CSV file. When an optimized schedule is found as well, that one is written instead.
+	#[arg(long)]
+	pub trace: Option<String>,
+
+	/// Write the schedule that is found (or verified) to this file as Chrome `trace_event` JSON, which
+	/// shows each core as a track in `chrome://tracing` or Perfetto
+	#[arg(long)]
+	pub chrome_trace: Option<String>,
+
+	/// Write the schedule that is found (or verified) to this file as a static dispatch table: a C
+	/// header when the file name ends with `.h`, and a CSV file otherwise
+	#[arg(long)]
+	pub dispatch_table: Option<String>,
+
+	/// Write the schedule that is found (or verified) to this file for SAG-based tools: as a YAML
+	/// dispatch order when the file name ends with `.yaml` or `.yml`, and otherwise as a SAG job file
+	/// in which each job arrives at its start time, and has its position in the dispatch order as
+	/// priority
+	#[arg(long)]
+	pub sag_schedule: Option<String>,
+
+	/// Write the problem to this file as a MAST model. When a schedule is found (or verified), each
+	/// job is bound to its core in that schedule, and prioritized by its dispatch order. Otherwise,
+	/// all jobs are bound to the first core, and prioritized by their deadlines.
+	#[arg(long)]
+	pub mast: Option<String>,
+
+	/// Write the problem to this file as a Cheddar XML model. Delays, start-to-start constraints,
+	/// and shared resources can't be expressed in it, so the model may be more or less constrained.
+	#[arg(long)]
+	pub cheddar: Option<String>,
+
+	/// Write the features of each job (slack, window length, in and out degree, chain depth, and local
+	/// utilization) to this CSV file, together with the verdict and the position of each job in the
+	/// dispatch order of the schedule that is found (if any). This is meant as training data for
+	/// models that predict a good dispatch order.
+	#[arg(long)]
+	pub features: Option<String>,
+
+	/// Write a certificate of the verdict to this CSV file, which can be checked independently using
+	/// `--check-certificate`. For feasible problems, this contains the start time and core of each
+	/// job. For infeasible problems, this contains the jobs and numbers of a contradiction, but such
+	/// a certificate can only be found when the proof doesn't rely on strengthened bounds.
+	#[arg(long)]
+	pub certificate: Option<String>,
+
+	/// Add the problem to the regression corpus in this directory when the analysis panics, exceeds
+	/// the `--timeout`, or finds a schedule for a problem that the necessary tests reject. The
+	/// corpus can be replayed using the `replay` subcommand.
+	#[arg(long)]
+	pub record_regressions: Option<String>,
+
+	/// Save the strengthened bounds, implied constraints, and statistics of the necessary tests to
+	/// `<fingerprint>.state.csv` in this directory, and load them when the same problem is analyzed
+	/// again, which skips the bound strengthening
+	#[arg(long)]
+	pub warm_start: Option<String>,
+
+	/// Stop the analysis when it takes more than this number of seconds
+	#[arg(long)]
+	pub timeout: Option<u64>,
+
+	/// Try to find a feasible schedule by simulating a scheduling policy when the necessary tests are
+	/// inconclusive. This is a sufficient test: it can only prove that a problem is feasible.
+	#[arg(long, value_enum)]
+	pub policy: Option<Policy>,
+
+	/// When the problem may or may not be feasible, simulate this many random dispatch orders, and
+	/// print the fraction of them that met all deadlines, as well as the smallest lateness
+	#[arg(long)]
+	pub samples: Option<usize>,
+
+	/// When no feasible schedule is found, simulate `--policy` (or EDF when it isn't given) and the
+	/// random dispatch orders of `--samples` without stopping at deadline misses, and report the
+	/// lateness of each late job, as well as the total and maximum tardiness, of the best of them
+	#[arg(long)]
+	pub tardiness: bool,
+
+	/// When none of the random dispatch orders of `--samples` met all deadlines (or only some of
+	/// them did), write to this CSV file how often each job missed its deadline, and how often each
+	/// job kept a late job waiting, in each time region. This points at the structurally problematic
+	/// part of the job set.
+	#[arg(long, requires = "samples")]
+	pub miss_heatmap: Option<String>,
+
+	/// The number of time regions of `--miss-heatmap`, which split the time until the largest
+	/// deadline
+	#[arg(long, default_value_t = 20, requires = "miss_heatmap")]
+	pub heatmap_regions: usize,
+
+	/// The seed of the random dispatch orders of `--samples`
+	#[arg(long, default_value_t = 0, requires = "samples")]
+	pub seed: u64,
+
+	/// Decide the feasibility exactly when the necessary tests are inconclusive
+	#[arg(long, value_enum)]
+	pub exact: Option<ExactMethod>,
+
+	/// Periodically save the progress of `--exact enumeration` to this file, from which a killed
+	/// search can continue using `--resume`
+	#[arg(long, requires = "exact")]
+	pub checkpoint: Option<String>,
+
+	/// The number of seconds between two saves of the `--checkpoint`
+	#[arg(long, default_value_t = 60, requires = "checkpoint")]
+	pub checkpoint_interval: u64,
+
+	/// Continue the `--exact enumeration` search from this checkpoint (see `--checkpoint`), which
+	/// must have been saved for the same problem and prefix order. When `--optimize` is given, the
+	/// checkpoint is only used by the search with the same objective.
+	#[arg(long, requires = "exact")]
+	pub resume: Option<String>,
+
+	/// Split the `--exact enumeration` search into work items in this directory, which must be
+	/// shared with the machines that run the `work` command. This process works on the items as
+	/// well, and waits until a worker found a schedule or all items are done.
+	#[arg(long, requires = "exact", conflicts_with_all = ["checkpoint", "resume", "prefix_order", "optimize"])]
+	pub distribute: Option<String>,
+
+	/// The number of jobs in the dispatch prefix of each work item of `--distribute`: each feasible
+	/// prefix becomes a work item, so a larger depth gives more and smaller items
+	#[arg(long, default_value_t = 2, requires = "distribute")]
+	pub distribute_depth: usize,
+
+	/// A file containing the indices of the first jobs that should be dispatched (in order), which
+	/// the exact method will complete
+	#[arg(long, requires = "exact")]
+	pub prefix_order: Option<String>,
+
+	/// After finding a feasible schedule, also search for a schedule that optimizes this objective
+	#[arg(long, value_enum, requires = "exact")]
+	pub optimize: Option<OptimizationGoal>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Instead of analyzing a problem, generate a random problem and write it to CSV files
+	Generate(GenerateArgs),
+	/// Instead of analyzing a problem, check that none of the problems in a regression corpus (see
+	/// `--record-regressions`) makes the analyses panic or contradict each other
+	Replay {
+		/// The directory containing the corpus
+		corpus: String,
+	},
+	/// Instead of analyzing a single problem, keep reading problems from stdin (one JSON object per
+	/// line), and write a verdict for each of them to stdout (one JSON object per line). This
+	/// requires the `daemon` feature.
+	#[cfg(feature = "daemon")]
+	Daemon,
+	/// Instead of analyzing a single problem, serve the analyses over HTTP: `POST /analyze` and
+	/// `POST /solve` expect a problem in the JSON format of the daemon, and respond with its verdict.
+	/// This requires the `http` feature.
+	#[cfg(feature = "http")]
+	Serve {
+		/// The address and port to listen on
+		#[arg(long, default_value = "127.0.0.1:8080")]
+		address: String,
+	},
+	/// Instead of analyzing a problem, work on the items of a search that was started with
+	/// `--distribute`, until all items are claimed or a verdict was reached
+	Work {
+		/// The directory that was passed to `--distribute`
+		directory: String,
+	},
+}
+
+#[derive(clap::Args)]
+pub struct GenerateArgs {
+	/// The jobs are written to `<OUTPUT>.csv`, and the constraints to `<OUTPUT>.prec.csv`
 	#[arg(short, long)]
+	pub output: String,
+
+	/// The seed of the random generator: the same seed and options always give the same problem
+	#[arg(long, default_value_t = 0)]
+	pub seed: u64,
+
+	/// The number of jobs to generate, unless `--num-tasks` is given
+	#[arg(long, default_value_t = 100)]
+	pub num_jobs: usize,
+
+	/// Instead of generating independent jobs, generate this many periodic tasks, and write all
+	/// their jobs that are released before the horizon. The utilization is distributed using
+	/// UUniFast-Discard, and each task has an implicit deadline. When the DAG has multiple layers,
+	/// the jobs of each task get constraints from the jobs of its predecessor tasks.
+	#[arg(long, conflicts_with_all = ["num_jobs", "max_window_factor"])]
+	pub num_tasks: Option<usize>,
+
+	/// The smallest period of the tasks, whose periods are chosen log-uniformly
+	#[arg(long, default_value_t = 100, requires = "num_tasks")]
+	pub min_period: Time,
+
+	/// The largest period of the tasks
+	#[arg(long, default_value_t = 10_000, requires = "num_tasks")]
+	pub max_period: Time,
+
+	/// The periods of the tasks are rounded down to a multiple of this
+	#[arg(long, default_value_t = 10, requires = "num_tasks")]
+	pub period_granularity: Time,
+
+	/// The number of cores, which is only used to scale the utilization
+	#[arg(short, long, default_value_t = 2)]
 	pub num_cores: u32,
+
+	/// The total execution time of all jobs, divided by the number of cores and the horizon
+	#[arg(short, long, default_value_t = 0.5)]
+	pub utilization: f64,
+
+	/// All jobs are released before this time
+	#[arg(long, default_value_t = 10_000)]
+	pub horizon: Time,
+
+	/// The window of each job is its execution time multiplied by a random factor between 1 and this
+	#[arg(long, default_value_t = 3.0)]
+	pub max_window_factor: f64,
+
+	/// The number of layers of the DAG: each job gets predecessors from the previous layer
+	#[arg(long, default_value_t = 1)]
+	pub layers: usize,
+
+	/// The maximum number of predecessors of each job
+	#[arg(long, default_value_t = 2)]
+	pub max_fan_in: usize,
+
+	/// The maximum delay of each constraint
+	#[arg(long, default_value_t = 0)]
+	pub max_delay: Time,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum Policy {
+	/// Global non-preemptive earliest-deadline-first
+	Edf,
+	/// Global non-preemptive fixed-priority, using the priorities of a SAG job file (lower is higher)
+	FixedPriority,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum ExactMethod {
+	/// Solve a mixed-integer linear program. This is only available when the binary is compiled
+	/// with the `milp` feature.
+	#[cfg(feature = "milp")]
+	Milp,
+	/// Try all dispatch orders, which is only suitable for small problems, but its progress can be
+	/// saved using `--checkpoint`
+	Enumeration,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum OptimizationGoal {
+	/// Minimize the time at which the last job finishes
+	Makespan,
+	/// Maximize the smallest slack (time between finish time and deadline) of all jobs
+	Slack,
 }
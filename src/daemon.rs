@@ -0,0 +1,292 @@
+use crate::batch::{analyze_problem, AnalysisOptions, AnalysisResult};
+use crate::certificate::{create_schedule_certificate, find_infeasibility_certificate};
+use crate::problem::*;
+use crate::schedule::Schedule;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+#[derive(Deserialize)]
+struct JsonJob {
+	release: Time,
+	execution_time: Time,
+	deadline: Time,
+}
+
+#[derive(Deserialize)]
+struct JsonConstraint {
+	before: usize,
+	after: usize,
+	#[serde(default)]
+	delay: Time,
+	/// Either `f-s` (the default) or `s-s`, like in constraint files
+	#[serde(default, rename = "type")]
+	constraint_type: Option<String>,
+	/// The largest time between the `before` job and the start of the `after` job, if any
+	#[serde(default)]
+	max_delay: Option<Time>,
+}
+
+/// A single line of the input of the daemon
+#[derive(Deserialize)]
+struct JsonProblem {
+	/// An arbitrary value that is copied to the verdict, so the caller can match them
+	#[serde(default)]
+	id: Value,
+	jobs: Vec<JsonJob>,
+	#[serde(default)]
+	constraints: Vec<JsonConstraint>,
+	num_cores: u32,
+	#[serde(default)]
+	resources: Vec<Vec<usize>>,
+}
+
+#[derive(Serialize)]
+struct JsonStart {
+	job: usize,
+	start: Time,
+	core: usize,
+}
+
+/// A single line of the output of the daemon
+#[derive(Serialize)]
+struct JsonVerdict {
+	#[serde(skip_serializing_if = "Value::is_null")]
+	id: Value,
+	/// `feasible`, `infeasible`, `unknown`, or `error`
+	verdict: &'static str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	schedule: Option<Vec<JsonStart>>,
+	/// Explains why the problem is infeasible, when an infeasibility certificate can be found
+	#[serde(skip_serializing_if = "Option::is_none")]
+	explanation: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<String>,
+}
+
+impl JsonVerdict {
+	fn new(id: Value, verdict: &'static str) -> Self {
+		Self { id, verdict, schedule: None, explanation: None, error: None }
+	}
+
+	fn feasible(id: Value, schedule: &Schedule) -> Self {
+		let schedule = create_schedule_certificate(schedule).into_iter().map(
+			|entry| JsonStart { job: entry.job, start: entry.start, core: entry.core }
+		).collect();
+		Self { schedule: Some(schedule), ..Self::new(id, "feasible") }
+	}
+
+	fn infeasible(id: Value, problem: &Problem) -> Self {
+		let explanation = find_infeasibility_certificate(problem).map(|certificate| certificate.to_string());
+		Self { explanation, ..Self::new(id, "infeasible") }
+	}
+
+	fn error(id: Value, error: String) -> Self {
+		Self { error: Some(error), ..Self::new(id, "error") }
+	}
+}
+
+/// How hard the analysis of a JSON problem should try to reach a verdict
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum JsonEffort {
+	/// Run the necessary tests, and simulate EDF when they are inconclusive
+	Analyze,
+	/// Like `Analyze`, but try all dispatch orders when EDF misses a deadline, unless the problem
+	/// has more than `MAX_ENUMERATED_JOBS` jobs. This decides the feasibility of such problems.
+	Solve,
+}
+
+/// The largest number of jobs for which `JsonEffort::Solve` tries all dispatch orders
+pub const MAX_ENUMERATED_JOBS: usize = 10;
+
+/// The largest number of cores that a JSON problem can have. The simulator allocates memory for
+/// each core, so larger numbers would let a single line exhaust the memory of the daemon.
+pub const MAX_CORES: u32 = 1 << 16;
+
+/// The largest total magnitude of all times in a JSON problem: the sum of the absolute releases,
+/// deadlines, execution times, and delays. This leaves enough room for the analyses to add and
+/// subtract any of these times without overflowing.
+pub const MAX_TOTAL_TIME: Time = Time::MAX / 4;
+
+/// Adds the magnitude of `time` to `total`, or returns `None` when it would exceed `MAX_TOTAL_TIME`
+fn add_magnitude(total: Time, time: Time) -> Option<Time> {
+	total.checked_add(time.checked_abs()?).filter(|&total| total <= MAX_TOTAL_TIME)
+}
+
+/// Converts `input` to a `Problem`, or returns an error when it would not pass `Problem::validate`
+fn create_problem(input: JsonProblem) -> Result<Problem, String> {
+	if input.num_cores == 0 {
+		return Err(String::from("There must be at least 1 core"));
+	}
+	if input.num_cores > MAX_CORES {
+		return Err(format!("There can be at most {} cores", MAX_CORES));
+	}
+	let mut total_time = 0;
+	let mut jobs = Vec::with_capacity(input.jobs.len());
+	for (index, job) in input.jobs.iter().enumerate() {
+		if job.execution_time <= 0 {
+			return Err(format!("The execution time of job {} must be positive", index));
+		}
+		if job.release < 0 {
+			return Err(format!("The release of job {} must not be negative", index));
+		}
+		if job.release.checked_add(job.execution_time).is_none() || job.deadline.checked_sub(job.execution_time).is_none() {
+			return Err(format!("The times of job {} are too large to be represented", index));
+		}
+		total_time = [job.release, job.execution_time, job.deadline].into_iter().try_fold(total_time, add_magnitude)
+			.ok_or_else(|| format!("The times up to job {} are too large to be analyzed", index))?;
+		jobs.push(Job::release_to_deadline(index, job.release, job.execution_time, job.deadline));
+	}
+
+	let mut constraints = Vec::with_capacity(input.constraints.len());
+	for (index, constraint) in input.constraints.iter().enumerate() {
+		if constraint.before >= jobs.len() || constraint.after >= jobs.len() {
+			return Err(format!("Constraint {} refers to a job that doesn't exist", index));
+		}
+		if constraint.delay < 0 {
+			return Err(format!("Constraint {} has a negative delay", index));
+		}
+		let constraint_type = match constraint.constraint_type.as_deref() {
+			None | Some("f-s") => ConstraintType::FinishToStart,
+			Some("s-s") => ConstraintType::StartToStart,
+			Some(other) => return Err(format!("Constraint {} has an unknown type {}", index, other)),
+		};
+		if constraint.max_delay.is_some_and(|max_delay| max_delay < constraint.delay) {
+			return Err(format!("Constraint {} has a maximum delay that is smaller than its delay", index));
+		}
+		total_time = [constraint.delay, constraint.max_delay.unwrap_or(0)].into_iter().try_fold(total_time, add_magnitude)
+			.ok_or_else(|| format!("The delays up to constraint {} are too large to be analyzed", index))?;
+		let mut parsed = Constraint::new(constraint.before, constraint.after, constraint.delay, constraint_type);
+		parsed.set_max_delay(constraint.max_delay);
+		constraints.push(parsed);
+	}
+
+	for (index, users) in input.resources.iter().enumerate() {
+		for (position, &job) in users.iter().enumerate() {
+			if job >= jobs.len() || users[.. position].contains(&job) {
+				return Err(format!("Resource {} contains an unknown or duplicate job {}", index, job));
+			}
+		}
+	}
+
+	Ok(Problem { jobs, constraints, num_cores: input.num_cores, resources: input.resources })
+}
+
+/// Analyzes `problem` using `analyze_problem`, with the options that correspond to `effort`
+fn analyze(id: Value, problem: &Problem, effort: JsonEffort) -> JsonVerdict {
+	let max_enumerated_jobs = if effort == JsonEffort::Solve { MAX_ENUMERATED_JOBS } else { 0 };
+	match analyze_problem(problem, AnalysisOptions { max_enumerated_jobs }) {
+		AnalysisResult::Feasible(schedule) => JsonVerdict::feasible(id, &schedule),
+		AnalysisResult::Infeasible => JsonVerdict::infeasible(id, problem),
+		AnalysisResult::Unknown => JsonVerdict::new(id, "unknown"),
+	}
+}
+
+/// Analyzes a problem in the JSON format of `run_daemon` with the given `effort`. Returns the verdict
+/// as JSON, or an error verdict as JSON when `text` is not a valid problem.
+pub fn analyze_json(text: &str, effort: JsonEffort) -> Result<String, String> {
+	let verdict = match serde_json::from_str::<JsonProblem>(text) {
+		Ok(mut input) => {
+			let id = std::mem::take(&mut input.id);
+			match create_problem(input) {
+				Ok(problem) => Ok(analyze(id, &problem, effort)),
+				Err(error) => Err(JsonVerdict::error(id, error)),
+			}
+		},
+		Err(error) => Err(JsonVerdict::error(Value::Null, format!("Couldn't parse problem: {}", error))),
+	};
+	let serialize = |verdict| serde_json::to_string(&verdict).expect("Couldn't serialize verdict");
+	verdict.map(serialize).map_err(serialize)
+}
+
+/// Analyzes a single line of input of the daemon, and returns the corresponding line of output
+/// (without the newline). See `run_daemon` for the format.
+pub fn analyze_json_line(line: &str) -> String {
+	analyze_json(line, JsonEffort::Analyze).unwrap_or_else(|error| error)
+}
+
+/// Reads one problem per line from `input`, and writes one verdict per line to `output`, until
+/// `input` is exhausted. Empty lines are skipped.
+///
+/// Each problem is a JSON object like
+/// `{"id": 1, "num_cores": 2, "jobs": [{"release": 0, "execution_time": 5, "deadline": 10}],
+/// "constraints": [{"before": 0, "after": 1, "delay": 2, "type": "s-s"}], "resources": [[0, 1]]}`,
+/// where `id`, `constraints`, `resources`, `delay`, `type`, and `max_delay` are optional. Each verdict contains
+/// the `id` of the problem (if any), and a `verdict` that is `feasible`, `infeasible`, `unknown`,
+/// or `error`. Feasible verdicts also contain the `schedule`: the `job`, `start`, and `core` of each
+/// job. Infeasible verdicts contain an `explanation` when an infeasibility certificate can be found.
+/// Error verdicts contain the `error` message. Problems with more than `MAX_CORES` cores, negative
+/// releases, or times whose total magnitude exceeds `MAX_TOTAL_TIME` get an error verdict.
+///
+/// Each verdict is flushed immediately, so the caller can wait for it before sending the next
+/// problem.
+pub fn run_daemon(input: impl BufRead, mut output: impl Write) {
+	for line in input.lines() {
+		let line = line.expect("Couldn't read problem from input");
+		if line.trim().is_empty() {
+			continue;
+		}
+		writeln!(output, "{}", analyze_json_line(&line)).expect("Couldn't write verdict");
+		output.flush().expect("Couldn't write verdict");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::daemon::*;
+
+	#[test]
+	fn test_run_daemon() {
+		let input = concat!(
+			r#"{"id": "a", "num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": 10}, "#,
+			r#"{"release": 0, "execution_time": 3, "deadline": 20}], "constraints": [{"before": 1, "after": 0}]}"#, "\n",
+			"\n",
+			r#"{"id": 2, "num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": 8}, "#,
+			r#"{"release": 0, "execution_time": 5, "deadline": 8}]}"#, "\n",
+			r#"{"num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": 8}], "#,
+			r#""constraints": [{"before": 0, "after": 1}]}"#, "\n",
+			"not json\n",
+		);
+		let mut output = Vec::new();
+		run_daemon(input.as_bytes(), &mut output);
+		let lines: Vec<String> = String::from_utf8(output).unwrap().lines().map(String::from).collect();
+		assert_eq!(4, lines.len());
+		assert_eq!(
+			r#"{"id":"a","verdict":"feasible","schedule":[{"job":0,"start":3,"core":0},{"job":1,"start":0,"core":0}]}"#,
+			lines[0]
+		);
+		assert!(lines[1].starts_with(r#"{"id":2,"verdict":"infeasible","explanation":"#));
+		assert_eq!(r#"{"verdict":"error","error":"Constraint 0 refers to a job that doesn't exist"}"#, lines[2]);
+		assert!(lines[3].starts_with(r#"{"verdict":"error","error":"Couldn't parse problem: "#));
+	}
+
+	#[test]
+	fn test_run_daemon_with_huge_numbers() {
+		let input = concat!(
+			r#"{"id": 1, "num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": -9223372036854775807}]}"#, "\n",
+			r#"{"id": 2, "num_cores": 1, "jobs": [{"release": 9223372036854775800, "execution_time": 100, "deadline": 10}]}"#, "\n",
+			r#"{"id": 3, "num_cores": 4294967295, "jobs": [{"release": 0, "execution_time": 5, "deadline": 10}]}"#, "\n",
+			r#"{"id": 4, "num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": 10}]}"#, "\n",
+		);
+		let mut output = Vec::new();
+		run_daemon(input.as_bytes(), &mut output);
+		let lines: Vec<String> = String::from_utf8(output).unwrap().lines().map(String::from).collect();
+		assert_eq!(4, lines.len());
+		assert_eq!(r#"{"id":1,"verdict":"error","error":"The times of job 0 are too large to be represented"}"#, lines[0]);
+		assert_eq!(r#"{"id":2,"verdict":"error","error":"The times of job 0 are too large to be represented"}"#, lines[1]);
+		assert_eq!(r#"{"id":3,"verdict":"error","error":"There can be at most 65536 cores"}"#, lines[2]);
+		assert_eq!(r#"{"id":4,"verdict":"feasible","schedule":[{"job":0,"start":0,"core":0}]}"#, lines[3]);
+	}
+
+	#[test]
+	fn test_solve_effort() {
+		// EDF runs job 2 and then job 0, so job 1 misses its deadline, while 2, 1, 0 is feasible
+		let problem = concat!(
+			r#"{"num_cores": 1, "jobs": [{"release": 1, "execution_time": 8, "deadline": 15}, "#,
+			r#"{"release": 4, "execution_time": 1, "deadline": 10}, {"release": 1, "execution_time": 2, "deadline": 7}]}"#
+		);
+		assert_eq!(Ok(String::from(r#"{"verdict":"unknown"}"#)), analyze_json(problem, JsonEffort::Analyze));
+		let verdict = analyze_json(problem, JsonEffort::Solve).unwrap();
+		assert!(verdict.starts_with(r#"{"verdict":"feasible","schedule":"#), "{}", verdict);
+	}
+}
@@ -0,0 +1,391 @@
+use crate::exact::{replay_dispatch_order, solve_by_enumeration_with_checkpoints, ExactResult, Objective};
+use crate::export::{format_constraints, format_jobs};
+use crate::fingerprint::compute_fingerprint;
+use crate::parser::parse_problem_with_resources;
+use crate::problem::*;
+use crate::simulator::Simulator;
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, remove_file, rename, write};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+// A distributed search uses a directory that is shared by all machines (for instance over NFS):
+// - `manifest.csv` contains the number of cores, the fingerprint of the problem, and the number of
+//   work items. It is written last, so workers wait until it exists.
+// - `jobs.csv`, `constraints.csv`, and `resources.csv` contain the problem.
+// - `todo/<item>.csv` contains the dispatch prefix of each work item that wasn't claimed yet. A
+//   worker claims an item by moving it to `claimed/<item>.csv`, which only one worker can do.
+// - `done/<item>.csv` marks the items whose part of the search tree has no feasible schedule.
+// - `verdict.csv` is written as soon as the verdict is known, after which all workers stop.
+const MANIFEST_FILE: &str = "manifest.csv";
+const JOBS_FILE: &str = "jobs.csv";
+const CONSTRAINTS_FILE: &str = "constraints.csv";
+const RESOURCES_FILE: &str = "resources.csv";
+const VERDICT_FILE: &str = "verdict.csv";
+const TODO_DIRECTORY: &str = "todo";
+const CLAIMED_DIRECTORY: &str = "claimed";
+const DONE_DIRECTORY: &str = "done";
+
+/// The time between two checks of the shared directory while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The verdict of a distributed search
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DistributedVerdict {
+	/// A worker found a feasible schedule, which is obtained by dispatching the jobs in this order
+	Feasible(Vec<usize>),
+	/// None of the work items has a feasible schedule
+	Infeasible,
+}
+
+/// Formats `verdict` as a single line: either `infeasible`, or `feasible` followed by the jobs of
+/// the dispatch order
+pub fn format_verdict(verdict: &DistributedVerdict) -> String {
+	match verdict {
+		DistributedVerdict::Feasible(order) => {
+			let mut line = String::from("feasible");
+			for job in order {
+				line.push_str(&format!(", {}", job));
+			}
+			line.push('\n');
+			line
+		},
+		DistributedVerdict::Infeasible => String::from("infeasible\n"),
+	}
+}
+
+/// Parses a verdict in the format of `format_verdict`
+pub fn parse_verdict_text(raw_text: &str) -> Result<DistributedVerdict, String> {
+	let line = raw_text.trim();
+	let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+	match string_values[0] {
+		"infeasible" if string_values.len() == 1 => Ok(DistributedVerdict::Infeasible),
+		"feasible" => Ok(DistributedVerdict::Feasible(string_values[1 ..].iter().map(
+			|value| value.parse::<usize>().map_err(|_| format!("Couldn't parse job {} of verdict: {}", value, line))
+		).collect::<Result<Vec<usize>, String>>()?)),
+		_ => Err(format!("Unexpected verdict: {}", line)),
+	}
+}
+
+/// Finds all dispatch prefixes of `depth` jobs (or all jobs, when the problem has fewer jobs) that
+/// respect the constraints and don't miss a deadline. Since `solve_by_enumeration_with_checkpoints`
+/// only considers these dispatch orders, the subtrees of these prefixes partition its search tree.
+pub fn find_dispatch_prefixes(problem: &Problem, depth: usize) -> Vec<Vec<usize>> {
+	let mut remaining_predecessors = vec![0usize; problem.jobs.len()];
+	for constraint in &problem.constraints {
+		remaining_predecessors[constraint.get_after()] += 1;
+	}
+	let mut prefixes = Vec::new();
+	let mut simulator = Simulator::new(problem);
+	let mut order = Vec::with_capacity(depth);
+	find_prefixes(
+		problem, depth.min(problem.jobs.len()), &mut simulator, &mut remaining_predecessors, &mut order, &mut prefixes
+	);
+	prefixes
+}
+
+fn find_prefixes(
+	problem: &Problem, depth: usize, simulator: &mut Simulator, remaining_predecessors: &mut [usize],
+	order: &mut Vec<usize>, prefixes: &mut Vec<Vec<usize>>
+) {
+	if order.len() == depth {
+		prefixes.push(order.clone());
+		return;
+	}
+
+	let checkpoint = simulator.checkpoint();
+	for job in 0 .. problem.jobs.len() {
+		if remaining_predecessors[job] != 0 {
+			continue;
+		}
+
+		simulator.schedule(problem.jobs[job]);
+		if !simulator.has_missed_deadline() {
+			// Mark the job as dispatched by pretending it has a predecessor
+			remaining_predecessors[job] = 1;
+			for constraint in &problem.constraints {
+				if constraint.get_before() == job {
+					remaining_predecessors[constraint.get_after()] -= 1;
+				}
+			}
+
+			order.push(job);
+			find_prefixes(problem, depth, simulator, remaining_predecessors, order, prefixes);
+			order.pop();
+
+			for constraint in &problem.constraints {
+				if constraint.get_before() == job {
+					remaining_predecessors[constraint.get_after()] += 1;
+				}
+			}
+			remaining_predecessors[job] = 0;
+		}
+		simulator.rollback(checkpoint);
+	}
+}
+
+/// Gets a copy of `problem` whose jobs are released at their earliest start time and whose
+/// deadline is their latest finish time, which the job format can represent exactly. The
+/// enumeration only looks at the start windows, so it treats both problems in the same way.
+fn get_publishable_problem(problem: &Problem) -> Problem {
+	let jobs = problem.jobs.iter().map(|job| {
+		let mut publishable = Job::release_to_deadline(
+			job.get_index(), job.earliest_start, job.get_execution_time(), job.get_latest_finish()
+		);
+		publishable.set_parallelism(job.get_parallelism());
+		publishable.set_affinity(job.get_affinity());
+		publishable
+	}).collect();
+	Problem { jobs, constraints: problem.constraints.clone(), num_cores: problem.num_cores, resources: problem.resources.clone() }
+}
+
+fn write_atomically(path: &Path, contents: String) {
+	let temporary_path = path.with_extension("tmp");
+	write(&temporary_path, contents).expect("Couldn't write to distribution directory");
+	rename(&temporary_path, path).expect("Couldn't write to distribution directory");
+}
+
+fn get_verdict(directory: &Path) -> Option<DistributedVerdict> {
+	let raw_text = read_to_string(directory.join(VERDICT_FILE)).ok()?;
+	Some(parse_verdict_text(&raw_text).unwrap_or_else(|error| panic!("{}", error)))
+}
+
+fn format_prefix(prefix: &[usize]) -> String {
+	let mut line = String::from("prefix");
+	for job in prefix {
+		line.push_str(&format!(", {}", job));
+	}
+	line.push('\n');
+	line
+}
+
+fn parse_prefix_text(raw_text: &str) -> Result<Vec<usize>, String> {
+	let line = raw_text.trim();
+	let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+	if string_values[0] != "prefix" {
+		return Err(format!("Unexpected work item: {}", line));
+	}
+	string_values[1 ..].iter().map(
+		|value| value.parse::<usize>().map_err(|_| format!("Couldn't parse job {} of work item: {}", value, line))
+	).collect()
+}
+
+/// Writes `problem` and its work items (the dispatch prefixes of `depth` jobs, see
+/// `find_dispatch_prefixes`) to the shared `directory`, and returns the number of work items. The
+/// state of a previous search in `directory` is removed. Mixed-criticality problems are not
+/// supported.
+pub fn publish_work(problem: &Problem, depth: usize, directory: &str) -> usize {
+	assert!(!problem.is_mixed_criticality(), "Mixed-criticality problems can't be distributed");
+	let directory = Path::new(directory);
+	create_dir_all(directory).expect("Couldn't create distribution directory");
+	for file in [MANIFEST_FILE, VERDICT_FILE] {
+		if directory.join(file).exists() {
+			remove_file(directory.join(file)).expect("Couldn't remove previous search from distribution directory");
+		}
+	}
+	for subdirectory in [TODO_DIRECTORY, CLAIMED_DIRECTORY, DONE_DIRECTORY] {
+		if directory.join(subdirectory).exists() {
+			remove_dir_all(directory.join(subdirectory)).expect("Couldn't remove previous search from distribution directory");
+		}
+		create_dir_all(directory.join(subdirectory)).expect("Couldn't create distribution directory");
+	}
+
+	let problem = get_publishable_problem(problem);
+	write(directory.join(JOBS_FILE), format_jobs(&problem)).expect("Couldn't write jobs to distribution directory");
+	write(directory.join(CONSTRAINTS_FILE), format_constraints(&problem))
+		.expect("Couldn't write constraints to distribution directory");
+	let mut resources = String::from("Resource, Job\n");
+	for (resource, users) in problem.resources.iter().enumerate() {
+		for job in users {
+			resources.push_str(&format!("r{}, {}\n", resource, job));
+		}
+	}
+	write(directory.join(RESOURCES_FILE), resources).expect("Couldn't write resources to distribution directory");
+
+	let prefixes = find_dispatch_prefixes(&problem, depth);
+	for (item, prefix) in prefixes.iter().enumerate() {
+		write(directory.join(TODO_DIRECTORY).join(format!("{}.csv", item)), format_prefix(prefix))
+			.expect("Couldn't write work item to distribution directory");
+	}
+	if prefixes.is_empty() {
+		write_atomically(&directory.join(VERDICT_FILE), format_verdict(&DistributedVerdict::Infeasible));
+	}
+	write_atomically(&directory.join(MANIFEST_FILE), format!(
+		"cores, {}\nfingerprint, {:016x}\nitems, {}\n", problem.num_cores, compute_fingerprint(&problem), prefixes.len()
+	));
+	prefixes.len()
+}
+
+/// Waits until the manifest in `directory` exists, and loads the problem that was published there.
+/// Returns the problem and the number of work items.
+fn load_published_problem(directory: &Path) -> (Problem, usize) {
+	let manifest_path = directory.join(MANIFEST_FILE);
+	while !manifest_path.exists() {
+		sleep(POLL_INTERVAL);
+	}
+
+	let raw_text = read_to_string(&manifest_path).expect("Couldn't read manifest of distribution directory");
+	let (mut num_cores, mut fingerprint, mut num_items) = (None, None, None);
+	for line in raw_text.lines() {
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		match (string_values[0], string_values.get(1)) {
+			("cores", Some(value)) => num_cores = value.parse::<u32>().ok(),
+			("fingerprint", Some(value)) => fingerprint = u64::from_str_radix(value, 16).ok(),
+			("items", Some(value)) => num_items = value.parse::<usize>().ok(),
+			_ => panic!("Unexpected line in manifest of distribution directory: {}", line),
+		}
+	}
+	let num_cores = num_cores.expect("The manifest doesn't contain the number of cores");
+	let fingerprint = fingerprint.expect("The manifest doesn't contain the fingerprint");
+	let num_items = num_items.expect("The manifest doesn't contain the number of work items");
+
+	let path = |file: &str| directory.join(file).to_string_lossy().into_owned();
+	let problem = parse_problem_with_resources(
+		&path(JOBS_FILE), Some(&path(CONSTRAINTS_FILE)), Some(&path(RESOURCES_FILE)), num_cores
+	);
+	assert_eq!(fingerprint, compute_fingerprint(&problem), "The problem in the distribution directory was modified");
+	(problem, num_items)
+}
+
+/// Moves the first unclaimed work item in `directory` to the claimed items, and returns its number
+/// and dispatch prefix. Returns `None` when all work items were claimed.
+fn claim_work_item(directory: &Path) -> Option<(usize, Vec<usize>)> {
+	let mut items: Vec<usize> = read_dir(directory.join(TODO_DIRECTORY))
+		.expect("Couldn't read work items of distribution directory")
+		.filter_map(|entry| entry.ok()?.file_name().to_str()?.strip_suffix(".csv")?.parse().ok())
+		.collect();
+	items.sort();
+
+	for item in items {
+		let claimed_path = directory.join(CLAIMED_DIRECTORY).join(format!("{}.csv", item));
+		// When the rename fails, another worker claimed this item first
+		if rename(directory.join(TODO_DIRECTORY).join(format!("{}.csv", item)), &claimed_path).is_ok() {
+			let raw_text = read_to_string(&claimed_path).expect("Couldn't read work item");
+			return Some((item, parse_prefix_text(&raw_text).unwrap_or_else(|error| panic!("{}", error))));
+		}
+	}
+	None
+}
+
+/// Keeps claiming the work items in the shared `directory` (see `publish_work`), and searches the
+/// dispatch orders that start with their prefix, until all items are claimed or the verdict is
+/// known. When a feasible schedule is found, its dispatch order is written as the verdict, which
+/// stops the other workers after their current item. Waits until the problem was published, and
+/// returns the number of items that this worker searched.
+pub fn run_worker(directory: &str) -> usize {
+	let directory = Path::new(directory);
+	let (problem, _) = load_published_problem(directory);
+
+	let mut num_searched_items = 0;
+	while get_verdict(directory).is_none() {
+		let Some((item, prefix)) = claim_work_item(directory) else { break; };
+		let result = solve_by_enumeration_with_checkpoints(
+			&problem, &prefix, Objective::EarlyStartTimes, None, Duration::MAX, |_| {}
+		);
+		if let ExactResult::Feasible(schedule) = result {
+			// Gang jobs occur once for each of their cores
+			let mut order = Vec::with_capacity(problem.jobs.len());
+			for scheduled in schedule.get_jobs() {
+				if order.last() != Some(&scheduled.job) {
+					order.push(scheduled.job);
+				}
+			}
+			write_atomically(&directory.join(VERDICT_FILE), format_verdict(&DistributedVerdict::Feasible(order)));
+		}
+		write(directory.join(DONE_DIRECTORY).join(format!("{}.csv", item)), "done\n")
+			.expect("Couldn't mark work item as done");
+		num_searched_items += 1;
+	}
+	num_searched_items
+}
+
+/// Waits until a worker found a feasible schedule, or all `num_items` work items of the shared
+/// `directory` are done, in which case `problem` is infeasible. The verdict is written to the
+/// directory, so that the remaining workers stop. `problem` must be the problem that was passed
+/// to `publish_work`. A work item that was claimed by a worker that crashed is never done, so that
+/// worker must be restarted after moving the item back from `claimed` to `todo`.
+pub fn wait_for_verdict(problem: &Problem, directory: &str, num_items: usize) -> ExactResult {
+	let directory = Path::new(directory);
+	loop {
+		if let Some(verdict) = get_verdict(directory) {
+			return match verdict {
+				DistributedVerdict::Feasible(order) => ExactResult::Feasible(
+					replay_dispatch_order(problem, &order).expect("The dispatch order of the verdict misses a deadline")
+				),
+				DistributedVerdict::Infeasible => ExactResult::Infeasible,
+			};
+		}
+
+		let num_done_items = read_dir(directory.join(DONE_DIRECTORY))
+			.expect("Couldn't read finished work items of distribution directory").count();
+		// Workers write their verdict before marking their item as done
+		if num_done_items >= num_items && get_verdict(directory).is_none() {
+			write_atomically(&directory.join(VERDICT_FILE), format_verdict(&DistributedVerdict::Infeasible));
+		} else {
+			sleep(POLL_INTERVAL);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::distributed::*;
+	use crate::exact::solve_by_enumeration;
+
+	fn create_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 20),
+				Job::release_to_deadline(1, 0, 5, 10),
+				Job::release_to_deadline(2, 3, 4, 30),
+			],
+			constraints: vec![Constraint::new(0, 2, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: vec![vec![0, 1]],
+		}
+	}
+
+	#[test]
+	fn test_find_dispatch_prefixes() {
+		let problem = create_problem();
+		assert_eq!(vec![vec![0], vec![1]], find_dispatch_prefixes(&problem, 1));
+		// Job 1 misses its deadline when it isn't dispatched first or second
+		assert_eq!(vec![vec![0, 1], vec![0, 2], vec![1, 0]], find_dispatch_prefixes(&problem, 2));
+		assert_eq!(vec![vec![0, 1, 2], vec![1, 0, 2]], find_dispatch_prefixes(&problem, 5));
+	}
+
+	#[test]
+	fn test_format_and_parse_verdict() {
+		for verdict in [DistributedVerdict::Infeasible, DistributedVerdict::Feasible(vec![2, 0, 1])] {
+			assert_eq!(Ok(verdict.clone()), parse_verdict_text(&format_verdict(&verdict)));
+		}
+		assert_eq!("feasible, 1, 0\n", format_verdict(&DistributedVerdict::Feasible(vec![1, 0])));
+		assert!(parse_verdict_text("feasible, x").is_err());
+		assert!(parse_verdict_text("maybe").is_err());
+	}
+
+	#[test]
+	fn test_distributed_search() {
+		let directory = std::env::temp_dir().join(format!("np-feasibility-distributed-{}", std::process::id()));
+		let directory = directory.to_str().unwrap();
+
+		let problem = create_problem();
+		let num_items = publish_work(&problem, 2, directory);
+		assert_eq!(3, num_items);
+		let num_searched_items = std::thread::scope(|scope| {
+			let workers: Vec<_> = (0 .. 2).map(|_| scope.spawn(|| run_worker(directory))).collect();
+			workers.into_iter().map(|worker| worker.join().unwrap()).sum::<usize>()
+		});
+		assert!(num_searched_items >= 1);
+		assert_eq!(solve_by_enumeration(&problem), wait_for_verdict(&problem, directory, num_items));
+
+		// Either job 1 or job 2 misses its deadline
+		let mut infeasible = problem.clone();
+		infeasible.jobs[2] = Job::release_to_deadline(2, 3, 4, 12);
+		let num_items = publish_work(&infeasible, 1, directory);
+		assert_eq!(2, run_worker(directory));
+		let result = wait_for_verdict(&infeasible, directory, num_items);
+		std::fs::remove_dir_all(directory).unwrap();
+		assert_eq!(ExactResult::Infeasible, result);
+	}
+}
@@ -0,0 +1,140 @@
+use crate::exact::Objective;
+use std::fs::{read_to_string, rename, write};
+
+/// The progress of an interrupted `solve_by_enumeration_with_checkpoints` search, from which it can
+/// be resumed. The search explores the dispatch orders in lexicographic order, so the search nodes
+/// that remain to be explored are determined by the node that was being visited.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EnumerationCheckpoint {
+	/// The fingerprint of the problem that is being solved (see `compute_fingerprint`)
+	pub fingerprint: u64,
+	pub objective: Objective,
+
+	/// The (partial) dispatch order of the search node at which the search continues: all dispatch
+	/// orders that are lexicographically smaller have already been explored
+	pub next_order: Vec<usize>,
+
+	/// The dispatch order of the best feasible schedule that was found so far, if any
+	pub incumbent: Option<Vec<usize>>,
+
+	/// The number of search nodes that were visited before this checkpoint was created
+	pub num_visited_nodes: u64,
+}
+
+fn get_objective_name(objective: Objective) -> &'static str {
+	match objective {
+		Objective::EarlyStartTimes => "early-start-times",
+		Objective::Makespan => "makespan",
+		Objective::MinimumSlack => "minimum-slack",
+	}
+}
+
+fn format_order(kind: &str, order: &[usize]) -> String {
+	let mut line = kind.to_string();
+	for job in order {
+		line.push_str(&format!(", {}", job));
+	}
+	line
+}
+
+/// Formats `checkpoint` as CSV, where each line starts with its kind:
+/// - `fingerprint, <fingerprint in hexadecimal>`
+/// - `objective, <early-start-times, makespan, or minimum-slack>`
+/// - `visited, <number of visited nodes>`
+/// - `next, <job>, <job>, ...`
+/// - `incumbent, <job>, <job>, ...`, which is omitted when no feasible schedule was found yet
+pub fn format_enumeration_checkpoint(checkpoint: &EnumerationCheckpoint) -> String {
+	let mut csv = format!(
+		"fingerprint, {:016x}\nobjective, {}\nvisited, {}\n{}\n", checkpoint.fingerprint,
+		get_objective_name(checkpoint.objective), checkpoint.num_visited_nodes,
+		format_order("next", &checkpoint.next_order)
+	);
+	if let Some(incumbent) = &checkpoint.incumbent {
+		csv.push_str(&format_order("incumbent", incumbent));
+		csv.push('\n');
+	}
+	csv
+}
+
+/// Parses a checkpoint in the format of `format_enumeration_checkpoint`
+pub fn parse_enumeration_checkpoint_text(raw_text: &str) -> Result<EnumerationCheckpoint, String> {
+	let mut fingerprint = None;
+	let mut objective = None;
+	let mut num_visited_nodes = 0;
+	let mut next_order = None;
+	let mut incumbent = None;
+
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		let parse_order = || string_values[1 ..].iter().map(|value| value.parse::<usize>().map_err(
+			|_| format!("Couldn't parse job {} in line of checkpoint: {}", value, line)
+		)).collect::<Result<Vec<usize>, String>>();
+
+		match (string_values[0], string_values.len()) {
+			("fingerprint", 2) => fingerprint = Some(u64::from_str_radix(string_values[1], 16).map_err(
+				|_| format!("Couldn't parse fingerprint in line of checkpoint: {}", line)
+			)?),
+			("objective", 2) => objective = Some([
+				Objective::EarlyStartTimes, Objective::Makespan, Objective::MinimumSlack
+			].into_iter().find(|objective| get_objective_name(*objective) == string_values[1]).ok_or_else(
+				|| format!("Unknown objective in line of checkpoint: {}", line)
+			)?),
+			("visited", 2) => num_visited_nodes = string_values[1].parse::<u64>().map_err(
+				|_| format!("Couldn't parse number of visited nodes in line of checkpoint: {}", line)
+			)?,
+			("next", _) => next_order = Some(parse_order()?),
+			("incumbent", _) => incumbent = Some(parse_order()?),
+			_ => return Err(format!("Unexpected line in checkpoint: {}", line)),
+		}
+	}
+
+	Ok(EnumerationCheckpoint {
+		fingerprint: fingerprint.ok_or("The checkpoint doesn't contain a fingerprint")?,
+		objective: objective.ok_or("The checkpoint doesn't contain an objective")?,
+		next_order: next_order.ok_or("The checkpoint doesn't contain the next dispatch order")?,
+		incumbent,
+		num_visited_nodes,
+	})
+}
+
+/// Writes `checkpoint` to `file_path`. The checkpoint is first written to a temporary file, which
+/// then replaces `file_path`, so the previous checkpoint survives when the process is killed while
+/// writing.
+pub fn save_enumeration_checkpoint(checkpoint: &EnumerationCheckpoint, file_path: &str) {
+	let temporary_path = format!("{}.tmp", file_path);
+	write(&temporary_path, format_enumeration_checkpoint(checkpoint)).expect("Couldn't write checkpoint file");
+	rename(&temporary_path, file_path).expect("Couldn't replace checkpoint file");
+}
+
+pub fn load_enumeration_checkpoint(file_path: &str) -> EnumerationCheckpoint {
+	let raw_text = read_to_string(file_path).expect("Couldn't read checkpoint file");
+	parse_enumeration_checkpoint_text(&raw_text).unwrap_or_else(|error| panic!("{}", error))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::exact::*;
+
+	#[test]
+	fn test_format_and_parse_checkpoint() {
+		let mut checkpoint = EnumerationCheckpoint {
+			fingerprint: 0x1234,
+			objective: Objective::Makespan,
+			next_order: vec![2, 0],
+			incumbent: Some(vec![0, 1, 2]),
+			num_visited_nodes: 17,
+		};
+		let text = format_enumeration_checkpoint(&checkpoint);
+		assert_eq!("fingerprint, 0000000000001234\nobjective, makespan\nvisited, 17\nnext, 2, 0\nincumbent, 0, 1, 2\n", text);
+		assert_eq!(Ok(checkpoint.clone()), parse_enumeration_checkpoint_text(&text));
+
+		checkpoint.incumbent = None;
+		checkpoint.next_order.clear();
+		assert_eq!(Ok(checkpoint.clone()), parse_enumeration_checkpoint_text(&format_enumeration_checkpoint(&checkpoint)));
+
+		assert!(parse_enumeration_checkpoint_text("fingerprint, 12\nobjective, makespan\n").is_err());
+		assert!(parse_enumeration_checkpoint_text("fingerprint, 12\nobjective, fast\nnext\n").is_err());
+		assert!(parse_enumeration_checkpoint_text("fingerprint, 12\nobjective, makespan\nnext, a\n").is_err());
+	}
+}
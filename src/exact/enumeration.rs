@@ -0,0 +1,325 @@
+use crate::exact::{EnumerationCheckpoint, ExactResult, Objective};
+use crate::fingerprint::compute_fingerprint;
+use crate::problem::*;
+use crate::schedule::Schedule;
+use crate::simulator::Simulator;
+use std::time::{Duration, Instant};
+
+/// Calls `on_schedule` with the schedule of every dispatch order that meets all deadlines, where
+/// each job starts as early as possible after the previous job in the order. The search backtracks
+/// as soon as a deadline is missed, but it is still exponential in the number of jobs, so this is
+/// only suitable for tiny problems, for instance as an oracle to test other analyses.
+///
+/// Every feasible schedule can be transformed into one of these schedules by dispatching its jobs
+/// in the order of their start times, which can only make them start earlier. So the problem is
+/// feasible if and only if `on_schedule` is called at least once, and the jobs of any feasible
+/// schedule start no earlier than in the corresponding schedule passed to `on_schedule`. This
+/// doesn't hold when constraints have a maximum delay (see `Problem::has_max_delays`), since
+/// starting a predecessor earlier can violate it.
+pub fn for_each_feasible_schedule(problem: &Problem, mut on_schedule: impl FnMut(&Schedule)) {
+	let mut remaining_predecessors = vec![0usize; problem.jobs.len()];
+	for constraint in &problem.constraints {
+		remaining_predecessors[constraint.get_after()] += 1;
+	}
+	let mut simulator = Simulator::new_recording(problem);
+	search(problem, &mut simulator, &mut remaining_predecessors, &mut on_schedule);
+}
+
+fn search(
+	problem: &Problem, simulator: &mut Simulator, remaining_predecessors: &mut [usize],
+	on_schedule: &mut impl FnMut(&Schedule)
+) {
+	if simulator.num_dispatched_jobs() == problem.jobs.len() {
+		on_schedule(simulator.get_schedule().unwrap());
+		return;
+	}
+
+	let checkpoint = simulator.checkpoint();
+	for job in 0 .. problem.jobs.len() {
+		if remaining_predecessors[job] != 0 {
+			continue;
+		}
+
+		simulator.schedule(problem.jobs[job]);
+		if !simulator.has_missed_deadline() {
+			// Mark the job as dispatched by pretending it has a predecessor
+			remaining_predecessors[job] = 1;
+			for constraint in &problem.constraints {
+				if constraint.get_before() == job {
+					remaining_predecessors[constraint.get_after()] -= 1;
+				}
+			}
+
+			search(problem, simulator, remaining_predecessors, on_schedule);
+
+			for constraint in &problem.constraints {
+				if constraint.get_before() == job {
+					remaining_predecessors[constraint.get_after()] += 1;
+				}
+			}
+			remaining_predecessors[job] = 0;
+		}
+		simulator.rollback(checkpoint);
+	}
+}
+
+/// The number of search nodes between two checks of the time since the last checkpoint
+const CHECKPOINT_CHECK_INTERVAL: u64 = 1024;
+
+/// The state of `solve_by_enumeration_with_checkpoints`
+struct EnumerationSearch<'a, S: FnMut(&EnumerationCheckpoint)> {
+	problem: &'a Problem,
+	prefix: &'a [usize],
+	objective: Objective,
+	fingerprint: u64,
+
+	simulator: Simulator,
+	remaining_predecessors: Vec<usize>,
+	order: Vec<usize>,
+
+	/// The dispatch order and schedule of the best feasible schedule that was found so far
+	incumbent: Option<(Vec<usize>, Schedule)>,
+	num_visited_nodes: u64,
+
+	last_checkpoint: Instant,
+	checkpoint_interval: Duration,
+	save_checkpoint: S,
+}
+
+/// Gets the value of `schedule` for `objective`, where lower is better
+fn get_objective_value(problem: &Problem, schedule: &Schedule, objective: Objective) -> Time {
+	match objective {
+		Objective::EarlyStartTimes => 0,
+		Objective::Makespan => schedule.get_makespan(),
+		Objective::MinimumSlack => -schedule.get_minimum_slack(problem).unwrap_or(0),
+	}
+}
+
+impl<S: FnMut(&EnumerationCheckpoint)> EnumerationSearch<'_, S> {
+	fn create_checkpoint(&self) -> EnumerationCheckpoint {
+		EnumerationCheckpoint {
+			fingerprint: self.fingerprint,
+			objective: self.objective,
+			next_order: self.order.clone(),
+			incumbent: self.incumbent.as_ref().map(|(order, _)| order.clone()),
+			num_visited_nodes: self.num_visited_nodes,
+		}
+	}
+
+	/// Explores the current search node and all its descendants, except the dispatch orders that
+	/// are lexicographically smaller than `resume` (when given), which is the part of the resumed
+	/// order after `self.order`. Returns `true` when the search should stop.
+	fn search(&mut self, resume: Option<&[usize]>) -> bool {
+		self.num_visited_nodes += 1;
+		if self.num_visited_nodes.is_multiple_of(CHECKPOINT_CHECK_INTERVAL) &&
+				self.last_checkpoint.elapsed() >= self.checkpoint_interval {
+			let checkpoint = self.create_checkpoint();
+			(self.save_checkpoint)(&checkpoint);
+			self.last_checkpoint = Instant::now();
+		}
+
+		if self.order.len() == self.problem.jobs.len() {
+			let schedule = self.simulator.get_schedule().unwrap();
+			let value = get_objective_value(self.problem, schedule, self.objective);
+			if self.incumbent.as_ref().is_none_or(
+				|(_, incumbent)| value < get_objective_value(self.problem, incumbent, self.objective)
+			) {
+				self.incumbent = Some((self.order.clone(), schedule.clone()));
+			}
+			return self.objective == Objective::EarlyStartTimes;
+		}
+
+		let depth = self.order.len();
+		let first_job = resume.and_then(|resume| resume.first().copied()).unwrap_or(0);
+		let checkpoint = self.simulator.checkpoint();
+		for job in first_job .. self.problem.jobs.len() {
+			if self.remaining_predecessors[job] != 0 || self.prefix.get(depth).is_some_and(|prefix_job| *prefix_job != job) {
+				continue;
+			}
+
+			self.simulator.schedule(self.problem.jobs[job]);
+			let mut should_stop = false;
+			if !self.simulator.has_missed_deadline() {
+				// Mark the job as dispatched by pretending it has a predecessor
+				self.remaining_predecessors[job] = 1;
+				for constraint in &self.problem.constraints {
+					if constraint.get_before() == job {
+						self.remaining_predecessors[constraint.get_after()] -= 1;
+					}
+				}
+
+				self.order.push(job);
+				let child_resume = resume.filter(|resume| resume.first() == Some(&job)).map(|resume| &resume[1 ..]);
+				should_stop = self.search(child_resume);
+				self.order.pop();
+
+				for constraint in &self.problem.constraints {
+					if constraint.get_before() == job {
+						self.remaining_predecessors[constraint.get_after()] += 1;
+					}
+				}
+				self.remaining_predecessors[job] = 0;
+			}
+			self.simulator.rollback(checkpoint);
+			if should_stop {
+				return true;
+			}
+		}
+		false
+	}
+}
+
+/// Gets the schedule of dispatching the jobs of `problem` in the given `order`, or `None` when it
+/// misses a deadline
+pub fn replay_dispatch_order(problem: &Problem, order: &[usize]) -> Option<Schedule> {
+	if order.len() != problem.jobs.len() || order.iter().any(|job| *job >= problem.jobs.len()) {
+		return None;
+	}
+	let mut simulator = Simulator::new_recording(problem);
+	for job in order {
+		simulator.schedule(problem.jobs[*job]);
+	}
+	(!simulator.has_missed_deadline()).then(|| simulator.get_schedule().unwrap().clone())
+}
+
+/// Decides the feasibility of `problem` by trying the dispatch orders (see
+/// `for_each_feasible_schedule`) that start with `prefix`, in lexicographic order. For the
+/// `EarlyStartTimes` objective, the search stops at the first feasible schedule. For the other
+/// objectives, all dispatch orders are tried, and the best schedule is returned.
+///
+/// Since this search can take very long, `save_checkpoint` is called with its progress about every
+/// `checkpoint_interval`. When `resume` is given, the search continues from that checkpoint, which
+/// must have been created for the same problem, prefix, and objective.
+pub fn solve_by_enumeration_with_checkpoints(
+	problem: &Problem, prefix: &[usize], objective: Objective, resume: Option<&EnumerationCheckpoint>,
+	checkpoint_interval: Duration, save_checkpoint: impl FnMut(&EnumerationCheckpoint)
+) -> ExactResult {
+	let mut remaining_predecessors = vec![0usize; problem.jobs.len()];
+	for constraint in &problem.constraints {
+		remaining_predecessors[constraint.get_after()] += 1;
+	}
+	let fingerprint = compute_fingerprint(problem);
+	let mut search = EnumerationSearch {
+		problem, prefix, objective, fingerprint,
+		simulator: Simulator::new_recording(problem),
+		remaining_predecessors,
+		order: Vec::with_capacity(problem.jobs.len()),
+		incumbent: None,
+		num_visited_nodes: 0,
+		last_checkpoint: Instant::now(),
+		checkpoint_interval,
+		save_checkpoint,
+	};
+	if let Some(resume) = resume {
+		assert_eq!(fingerprint, resume.fingerprint, "The checkpoint belongs to another problem");
+		assert_eq!(objective, resume.objective, "The checkpoint belongs to another objective");
+		search.num_visited_nodes = resume.num_visited_nodes;
+		search.incumbent = resume.incumbent.as_ref().and_then(
+			|order| replay_dispatch_order(problem, order).map(|schedule| (order.clone(), schedule))
+		);
+	}
+
+	search.search(resume.map(|resume| resume.next_order.as_slice()));
+	match search.incumbent {
+		Some((_, schedule)) => ExactResult::Feasible(schedule),
+		None => ExactResult::Infeasible,
+	}
+}
+
+/// Decides the feasibility of `problem` by trying all dispatch orders (see
+/// `for_each_feasible_schedule`). This is only suitable for tiny problems.
+pub fn solve_by_enumeration(problem: &Problem) -> ExactResult {
+	solve_by_enumeration_with_checkpoints(problem, &[], Objective::EarlyStartTimes, None, Duration::MAX, |_| {})
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::exact::*;
+	use crate::fingerprint::compute_fingerprint;
+	use crate::problem::*;
+	use std::time::Duration;
+
+	#[test]
+	fn test_needs_idle_time() {
+		// Job 1 must wait for job 0, even though it is released earlier
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 2, 5, 7),
+				Job::release_to_deadline(1, 0, 5, 12),
+			],
+			constraints: Vec::new(),
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let ExactResult::Feasible(schedule) = solve_by_enumeration(&problem) else { panic!("Should be feasible") };
+		assert_eq!(Ok(()), schedule.validate(&problem));
+
+		let mut num_schedules = 0;
+		for_each_feasible_schedule(&problem, |_| num_schedules += 1);
+		assert_eq!(1, num_schedules);
+	}
+
+	#[test]
+	fn test_infeasible_constraint() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 20),
+				Job::release_to_deadline(1, 0, 5, 20),
+			],
+			constraints: vec![Constraint::new(0, 1, 11, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		assert_eq!(ExactResult::Infeasible, solve_by_enumeration(&problem));
+	}
+
+	#[test]
+	fn test_checkpoints() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 40),
+				Job::release_to_deadline(1, 0, 3, 40),
+				Job::release_to_deadline(2, 2, 4, 40),
+				Job::release_to_deadline(3, 0, 6, 40),
+			],
+			constraints: vec![Constraint::new(1, 2, 0, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+
+		let mut checkpoints = Vec::new();
+		let expected = solve_by_enumeration_with_checkpoints(
+			&problem, &[], Objective::Makespan, None, Duration::ZERO, |checkpoint| checkpoints.push(checkpoint.clone())
+		);
+		let ExactResult::Feasible(schedule) = &expected else { panic!("Should be feasible") };
+		assert_eq!(9, schedule.get_makespan());
+		assert_eq!(Ok(()), schedule.validate(&problem));
+
+		// Too few nodes are visited to create a checkpoint, so create one halfway manually
+		assert!(checkpoints.is_empty());
+		let resume = EnumerationCheckpoint {
+			fingerprint: compute_fingerprint(&problem),
+			objective: Objective::Makespan,
+			next_order: vec![1, 3],
+			incumbent: Some(vec![0, 1, 3, 2]),
+			num_visited_nodes: 10,
+		};
+		let resumed = solve_by_enumeration_with_checkpoints(
+			&problem, &[], Objective::Makespan, Some(&resume), Duration::MAX, |_| {}
+		);
+		assert_eq!(expected, resumed);
+
+		// When all orders have been explored, the incumbent is the answer
+		let resume = EnumerationCheckpoint { next_order: vec![4], incumbent: Some(vec![0, 1, 2, 3]), ..resume };
+		let ExactResult::Feasible(schedule) = solve_by_enumeration_with_checkpoints(
+			&problem, &[], Objective::Makespan, Some(&resume), Duration::MAX, |_| {}
+		) else { panic!("Should be feasible") };
+		assert_eq!(11, schedule.get_makespan());
+
+		let ExactResult::Feasible(schedule) = solve_by_enumeration_with_checkpoints(
+			&problem, &[3, 1], Objective::EarlyStartTimes, None, Duration::MAX, |_| {}
+		) else { panic!("Should be feasible") };
+		let order: Vec<usize> = schedule.get_jobs().iter().map(|scheduled| scheduled.job).collect();
+		assert_eq!(vec![3, 1, 0, 2], order);
+	}
+}
@@ -0,0 +1,335 @@
+use crate::exact::{ExactResult, Objective};
+use crate::problem::*;
+use crate::schedule::{Schedule, ScheduledJob};
+use good_lp::*;
+use std::collections::HashSet;
+
+/// The variables that decide the relative order of 2 jobs whose windows overlap
+struct JobPair {
+	first: usize,
+	second: usize,
+
+	/// 1 when `first` runs before `second` (if they run on the same core)
+	first_runs_before_second: Variable,
+
+	/// 1 when both jobs run on the same core. This is `None` when there is only 1 core, or when
+	/// both jobs use the same shared resource (since they can't overlap anyway).
+	same_core: Option<Variable>,
+}
+
+/// Decides exactly whether `problem` is feasible, by formulating it as a mixed-integer linear
+/// program and solving it using the default `good_lp` solver (HiGHS).
+///
+/// The formulation has an integer start time variable per job, a binary core assignment variable
+/// per job per core, and for each pair of jobs whose windows overlap:
+/// - a binary variable that indicates whether the two jobs run on the same core
+/// - a binary variable that decides which of the two jobs runs first *if* they run on the same core
+///   (or use the same shared resource)
+///
+/// Since the number of variables grows quadratically in the number of jobs, this is only practical
+/// for small and medium-sized problems. The returned schedule is always validated against
+/// `problem` before it is returned.
+///
+/// When the problem is feasible, the returned schedule is optimal w.r.t. `objective`.
+///
+/// The first jobs to be dispatched can be pinned using `prefix`: the jobs in `prefix` must start
+/// in the given order, and no other job can start before the last job in `prefix`.
+///
+/// Returns an error when `prefix` doesn't satisfy `problem.check_dispatch_prefix(prefix)`, when
+/// `problem` has gang jobs (with a parallelism larger than 1), which this formulation doesn't
+/// support, when the solver fails, or when it produces a schedule that is invalid (for instance
+/// due to rounding errors).
+pub fn solve_using_milp(problem: &Problem, prefix: &[usize], objective: Objective) -> Result<ExactResult, String> {
+	if let Err(position) = problem.check_dispatch_prefix(prefix) {
+		return Err(format!(
+			"Job {} at position {} of the prefix is duplicated, unknown, or dispatched before one of its predecessors",
+			prefix[position], position
+		));
+	}
+	if problem.has_parallel_jobs() {
+		return Err(String::from("The MILP formulation doesn't support gang jobs"));
+	}
+	if problem.is_certainly_infeasible() {
+		return Ok(ExactResult::Infeasible);
+	}
+	if problem.jobs.is_empty() {
+		return Ok(ExactResult::Feasible(Schedule::new()));
+	}
+
+	let horizon_start = problem.jobs.iter().map(|j| j.earliest_start).min().unwrap();
+	let horizon_end = problem.jobs.iter().map(|j| j.get_latest_finish()).max().unwrap();
+	let big_m = (horizon_end - horizon_start) as f64;
+	let num_cores = problem.num_cores as usize;
+
+	let mut variables = variables!();
+	let start_times: Vec<Variable> = problem.jobs.iter().map(|job| variables.add(
+		variable().integer().min(job.earliest_start as f64).max(job.latest_start as f64)
+	)).collect();
+
+	let mut core_assignments: Vec<Vec<Variable>> = Vec::new();
+	if num_cores > 1 {
+		for _ in &problem.jobs {
+			core_assignments.push((0 .. num_cores).map(|_| variables.add(variable().binary())).collect());
+		}
+	}
+
+	let mut shared_resource_pairs = HashSet::new();
+	for users in &problem.resources {
+		for (index, &first) in users.iter().enumerate() {
+			for &second in &users[index + 1 ..] {
+				shared_resource_pairs.insert((usize::min(first, second), usize::max(first, second)));
+			}
+		}
+	}
+
+	let mut pairs = Vec::new();
+	for first in 0 .. problem.jobs.len() {
+		for second in first + 1 .. problem.jobs.len() {
+			let first_job = &problem.jobs[first];
+			let second_job = &problem.jobs[second];
+			if first_job.get_latest_finish() <= second_job.earliest_start ||
+				second_job.get_latest_finish() <= first_job.earliest_start {
+				continue;
+			}
+			pairs.push(JobPair {
+				first, second,
+				first_runs_before_second: variables.add(variable().binary()),
+				same_core: if num_cores > 1 && !shared_resource_pairs.contains(&(first, second)) {
+					Some(variables.add(variable().binary()))
+				} else {
+					None
+				},
+			});
+		}
+	}
+
+	// The makespan or minimum slack, depending on the objective
+	let bound = variables.add(variable().integer());
+	let objective_expression: Expression = match objective {
+		Objective::EarlyStartTimes => start_times.iter().sum(),
+		Objective::Makespan => bound.into(),
+		Objective::MinimumSlack => -bound,
+	};
+	let mut model = variables.minimise(objective_expression).using(default_solver);
+
+	for job in &problem.jobs {
+		let start_time = start_times[job.get_index()];
+		model = match objective {
+			Objective::EarlyStartTimes => model,
+			Objective::Makespan => model.with(constraint!(
+				bound - start_time >= job.get_execution_time() as f64
+			)),
+			Objective::MinimumSlack => model.with(constraint!(
+				bound + start_time <= (job.get_deadline() - job.get_execution_time()) as f64
+			)),
+		};
+	}
+
+	for constraint in &problem.constraints {
+		let mut minimum_distance = constraint.get_delay();
+		if constraint.get_type() == ConstraintType::FinishToStart {
+			minimum_distance += problem.jobs[constraint.get_before()].get_execution_time();
+		}
+		model = model.with(constraint!(
+			start_times[constraint.get_after()] - start_times[constraint.get_before()] >= minimum_distance as f64
+		));
+		if let Some(maximum_distance) = constraint.get_max_separation(problem.jobs[constraint.get_before()].get_execution_time()) {
+			model = model.with(constraint!(
+				start_times[constraint.get_after()] - start_times[constraint.get_before()] <= maximum_distance as f64
+			));
+		}
+	}
+
+	for pair in prefix.windows(2) {
+		model = model.with(constraint!(start_times[pair[1]] - start_times[pair[0]] >= 0));
+	}
+	if let Some(&last_prefix_job) = prefix.last() {
+		let mut is_in_prefix = vec![false; problem.jobs.len()];
+		for &job in prefix {
+			is_in_prefix[job] = true;
+		}
+		for (&start_time, is_in_prefix) in start_times.iter().zip(is_in_prefix) {
+			if !is_in_prefix {
+				model = model.with(constraint!(start_time - start_times[last_prefix_job] >= 0));
+			}
+		}
+	}
+
+	for (job, cores) in problem.jobs.iter().zip(&core_assignments) {
+		let num_assigned_cores: Expression = cores.iter().sum();
+		model = model.with(constraint!(num_assigned_cores == 1));
+		for (core, &assigned) in cores.iter().enumerate() {
+			if !job.get_affinity().contains(core) {
+				model = model.with(constraint!(assigned == 0));
+			}
+		}
+	}
+
+	for pair in &pairs {
+		let mut different_core_slack = Expression::from(0.0);
+		if let Some(same_core) = pair.same_core {
+			let first_cores = &core_assignments[pair.first];
+			let second_cores = &core_assignments[pair.second];
+			for (first_core, second_core) in first_cores.iter().zip(second_cores) {
+				model = model.with(constraint!(same_core >= *first_core + *second_core - 1.0));
+			}
+			different_core_slack = big_m - big_m * same_core;
+		}
+
+		let first_execution_time = problem.jobs[pair.first].get_execution_time() as f64;
+		let second_execution_time = problem.jobs[pair.second].get_execution_time() as f64;
+		let first_start = start_times[pair.first];
+		let second_start = start_times[pair.second];
+		let order = pair.first_runs_before_second;
+		model = model.with(constraint!(
+			second_start - first_start - first_execution_time + big_m - big_m * order +
+				different_core_slack.clone() >= 0.0
+		));
+		model = model.with(constraint!(
+			first_start - second_start - second_execution_time + big_m * order +
+				different_core_slack >= 0.0
+		));
+	}
+
+	let solution = match model.solve() {
+		Ok(solution) => solution,
+		Err(ResolutionError::Infeasible) => return Ok(ExactResult::Infeasible),
+		Err(error) => return Err(format!("The MILP solver failed: {}", error)),
+	};
+
+	let mut schedule = Schedule::new();
+	for job in &problem.jobs {
+		let start = solution.value(start_times[job.get_index()]).round() as Time;
+		let mut core = 0;
+		if num_cores > 1 {
+			let cores = &core_assignments[job.get_index()];
+			core = (0 .. num_cores).max_by(
+				|a, b| solution.value(cores[*a]).total_cmp(&solution.value(cores[*b]))
+			).unwrap();
+		}
+		schedule.push(ScheduledJob {
+			job: job.get_index(), start, finish: start + job.get_execution_time(), core
+		});
+	}
+
+	if let Err(violation) = schedule.validate(problem) {
+		return Err(format!("The MILP solver produced an invalid schedule: {:?}", violation));
+	}
+	Ok(ExactResult::Feasible(schedule))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::exact::*;
+	use crate::problem::*;
+
+	#[test]
+	fn test_feasible_with_one_core() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 50),
+				Job::release_to_deadline(1, 10, 30, 50)
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		match solve_using_milp(&problem, &[], Objective::EarlyStartTimes).unwrap() {
+			ExactResult::Feasible(schedule) => assert_eq!(Ok(()), schedule.validate(&problem)),
+			ExactResult::Infeasible => panic!("Problem should be feasible"),
+		}
+	}
+
+	#[test]
+	fn test_feasible_with_two_cores_and_constraints() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 20),
+				Job::release_to_deadline(1, 0, 30, 32),
+				Job::release_to_deadline(2, 0, 30, 100)
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 2, ConstraintType::StartToStart),
+				Constraint::new(0, 2, 10, ConstraintType::FinishToStart)
+			],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		assert_ne!(ExactResult::Infeasible, solve_using_milp(&problem, &[], Objective::EarlyStartTimes).unwrap());
+	}
+
+	#[test]
+	fn test_infeasible_overload() {
+		// Both jobs must run during [5, 10) on the only core
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 15),
+				Job::release_to_deadline(1, 5, 10, 16)
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		assert_eq!(ExactResult::Infeasible, solve_using_milp(&problem, &[], Objective::EarlyStartTimes).unwrap());
+	}
+
+	#[test]
+	fn test_prefix_order() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 50),
+				Job::release_to_deadline(1, 10, 30, 50)
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		assert_ne!(ExactResult::Infeasible, solve_using_milp(&problem, &[0], Objective::EarlyStartTimes).unwrap());
+		assert_eq!(ExactResult::Infeasible, solve_using_milp(&problem, &[1], Objective::EarlyStartTimes).unwrap());
+		assert!(solve_using_milp(&problem, &[0, 0], Objective::EarlyStartTimes).is_err());
+
+		problem.num_cores = 2;
+		problem.jobs[1].set_parallelism(2);
+		assert!(solve_using_milp(&problem, &[], Objective::EarlyStartTimes).is_err());
+	}
+
+	#[test]
+	fn test_objectives() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 5, 10, 100)
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+
+		match solve_using_milp(&problem, &[], Objective::Makespan).unwrap() {
+			ExactResult::Feasible(schedule) => assert_eq!(20, schedule.get_makespan()),
+			ExactResult::Infeasible => panic!("Problem should be feasible"),
+		}
+		match solve_using_milp(&problem, &[], Objective::MinimumSlack).unwrap() {
+			ExactResult::Feasible(schedule) => assert_eq!(Some(80), schedule.get_minimum_slack(&problem)),
+			ExactResult::Infeasible => panic!("Problem should be feasible"),
+		}
+	}
+
+	#[test]
+	fn test_shared_resource() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 15),
+				Job::release_to_deadline(1, 5, 10, 16)
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		assert_ne!(ExactResult::Infeasible, solve_using_milp(&problem, &[], Objective::EarlyStartTimes).unwrap());
+
+		problem.resources.push(vec![0, 1]);
+		assert_eq!(ExactResult::Infeasible, solve_using_milp(&problem, &[], Objective::EarlyStartTimes).unwrap());
+	}
+}
@@ -0,0 +1,33 @@
+mod checkpoint;
+mod enumeration;
+#[cfg(feature = "milp")]
+mod milp;
+
+pub use checkpoint::*;
+pub use enumeration::{
+	for_each_feasible_schedule, replay_dispatch_order, solve_by_enumeration, solve_by_enumeration_with_checkpoints
+};
+#[cfg(feature = "milp")]
+pub use milp::solve_using_milp;
+
+use crate::schedule::Schedule;
+
+/// The quantity that an exact method should optimize when it finds a feasible schedule
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Objective {
+	/// Start all jobs as early as possible: minimize the sum of all start times
+	EarlyStartTimes,
+	/// Minimize the time at which the last job finishes
+	Makespan,
+	/// Maximize the smallest slack (time between finish time and deadline) of all jobs
+	MinimumSlack,
+}
+
+/// The outcome of an exact feasibility analysis
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ExactResult {
+	/// The problem is feasible, and this is a valid schedule for it
+	Feasible(Schedule),
+	/// The problem is certainly infeasible
+	Infeasible,
+}
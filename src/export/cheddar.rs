@@ -0,0 +1,132 @@
+use crate::problem::Problem;
+use std::fs::write;
+
+/// Formats the scheduling parameters of a non-preemptive EDF scheduler in Cheddar XML
+fn format_cheddar_scheduler(indentation: &str) -> String {
+	format!(
+		"{0}<scheduling>\n{0}\t<scheduling_parameters>\n\
+		{0}\t\t<scheduler_type>EARLIEST_DEADLINE_FIRST_PROTOCOL</scheduler_type>\n\
+		{0}\t\t<quantum>0</quantum>\n{0}\t\t<preemptive_type>NOT_PREEMPTIVE</preemptive_type>\n\
+		{0}\t\t<capacity>0</capacity>\n{0}\t\t<period>0</period>\n{0}\t\t<priority>0</priority>\n\
+		{0}\t\t<start_time>0</start_time>\n{0}\t</scheduling_parameters>\n{0}</scheduling>\n",
+		indentation
+	)
+}
+
+/// Formats `problem` as a Cheddar XML model, such that it can be simulated and analyzed by Cheddar.
+/// The cores are identical cores of a single processor with a non-preemptive EDF scheduler, and each
+/// job is an aperiodic task that is released at its release time, and has a deadline relative to
+/// it. Each job may run on any core, like in the schedules of this crate.
+///
+/// Each constraint becomes a precedence dependency. Since these don't have a delay or type, the
+/// delays are dropped, and start-to-start constraints become finish-to-start constraints. Shared
+/// resources are not written.
+pub fn format_cheddar_model(problem: &Problem) -> String {
+	let mut xml = String::from("<?xml version=\"1.0\" standalone=\"yes\"?>\n<cheddar>\n\t<core_units>\n");
+	for core in 0 .. problem.num_cores {
+		xml.push_str(&format!(
+			"\t\t<core_unit id=\"core_{0}\">\n\t\t\t<object_type>CORE_OBJECT_TYPE</object_type>\n\
+			\t\t\t<name>core_{0}</name>\n{1}\t\t\t<speed>1</speed>\n\t\t</core_unit>\n",
+			core, format_cheddar_scheduler("\t\t\t")
+		));
+	}
+	xml.push_str("\t</core_units>\n\t<processors>\n");
+
+	let (processor_tag, processor_type, migration_type) = if problem.num_cores == 1 {
+		("mono_core_processor", "MONOCORE_TYPE", "NO_MIGRATION_TYPE")
+	} else {
+		("multi_cores_processor", "IDENTICAL_MULTICORES_TYPE", "JOB_LEVEL_MIGRATION_TYPE")
+	};
+	xml.push_str(&format!(
+		"\t\t<{0} id=\"processor\">\n\t\t\t<object_type>PROCESSOR_OBJECT_TYPE</object_type>\n\
+		\t\t\t<name>processor</name>\n\t\t\t<processor_type>{1}</processor_type>\n\
+		\t\t\t<migration_type>{2}</migration_type>\n",
+		processor_tag, processor_type, migration_type
+	));
+	if problem.num_cores == 1 {
+		xml.push_str("\t\t\t<core ref=\"core_0\"></core>\n");
+	} else {
+		xml.push_str("\t\t\t<cores>\n");
+		for core in 0 .. problem.num_cores {
+			xml.push_str(&format!("\t\t\t\t<core_unit ref=\"core_{}\"></core_unit>\n", core));
+		}
+		xml.push_str("\t\t\t</cores>\n");
+	}
+	xml.push_str(&format!("\t\t</{}>\n\t</processors>\n", processor_tag));
+
+	xml.push_str(
+		"\t<address_spaces>\n\t\t<address_space id=\"address_space\">\n\
+		\t\t\t<object_type>ADDRESS_SPACE_OBJECT_TYPE</object_type>\n\t\t\t<name>address_space</name>\n\
+		\t\t\t<cpu_name>processor</cpu_name>\n\t\t\t<text_memory_size>0</text_memory_size>\n\
+		\t\t\t<stack_memory_size>0</stack_memory_size>\n\t\t\t<data_memory_size>0</data_memory_size>\n\
+		\t\t\t<heap_memory_size>0</heap_memory_size>\n\t\t</address_space>\n\t</address_spaces>\n\t<tasks>\n"
+	);
+	for (index, job) in problem.jobs.iter().enumerate() {
+		xml.push_str(&format!(
+			"\t\t<aperiodic_task id=\"job_{0}\">\n\t\t\t<object_type>TASK_OBJECT_TYPE</object_type>\n\
+			\t\t\t<name>job_{0}</name>\n\t\t\t<task_type>APERIODIC_TYPE</task_type>\n\
+			\t\t\t<cpu_name>processor</cpu_name>\n\t\t\t<address_space_name>address_space</address_space_name>\n\
+			\t\t\t<capacity>{1}</capacity>\n\t\t\t<deadline>{2}</deadline>\n\t\t\t<start_time>{3}</start_time>\n\
+			\t\t\t<priority>1</priority>\n\t\t\t<blocking_time>0</blocking_time>\n\t\t\t<policy>SCHED_FIFO</policy>\n\
+			\t\t</aperiodic_task>\n",
+			index, job.get_execution_time(), job.get_latest_finish() - job.earliest_start, job.earliest_start
+		));
+	}
+	xml.push_str("\t</tasks>\n");
+
+	if !problem.constraints.is_empty() {
+		xml.push_str("\t<dependencies>\n");
+		for constraint in &problem.constraints {
+			xml.push_str(&format!(
+				"\t\t<dependency>\n\t\t\t<type_of_dependency>PRECEDENCE_DEPENDENCY</type_of_dependency>\n\
+				\t\t\t<precedence_sink ref=\"job_{}\"></precedence_sink>\n\
+				\t\t\t<precedence_source ref=\"job_{}\"></precedence_source>\n\t\t</dependency>\n",
+				constraint.get_after(), constraint.get_before()
+			));
+		}
+		xml.push_str("\t</dependencies>\n");
+	}
+	xml.push_str("</cheddar>\n");
+	xml
+}
+
+/// Writes `problem` to `file_path` as a Cheddar XML model. See `format_cheddar_model`.
+pub fn write_cheddar_model(problem: &Problem, file_path: &str) {
+	write(file_path, format_cheddar_model(problem)).expect("Couldn't write Cheddar model file");
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::export::*;
+	use crate::problem::*;
+
+	#[test]
+	fn test_format_cheddar_model() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 20),
+				Job::release_to_deadline(1, 2, 3, 10),
+			],
+			constraints: vec![Constraint::new(0, 1, 2, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: vec![],
+		};
+		let model = format_cheddar_model(&problem);
+		assert!(model.starts_with("<?xml version=\"1.0\" standalone=\"yes\"?>\n<cheddar>\n"));
+		assert!(model.ends_with("</cheddar>\n"));
+		assert!(model.contains("<mono_core_processor id=\"processor\">"));
+		assert!(model.contains("<core ref=\"core_0\"></core>"));
+		assert!(model.contains("<name>job_1</name>"));
+		assert!(model.contains("<capacity>3</capacity>\n\t\t\t<deadline>8</deadline>\n\t\t\t<start_time>2</start_time>"));
+		assert!(model.contains("<precedence_sink ref=\"job_1\"></precedence_sink>\n\
+			\t\t\t<precedence_source ref=\"job_0\"></precedence_source>"));
+
+		problem.num_cores = 3;
+		problem.constraints.clear();
+		let model = format_cheddar_model(&problem);
+		assert_eq!(3, model.matches("<core_unit id=").count());
+		assert!(model.contains("<multi_cores_processor id=\"processor\">"));
+		assert!(model.contains("<core_unit ref=\"core_2\"></core_unit>"));
+		assert!(!model.contains("<dependencies>"));
+	}
+}
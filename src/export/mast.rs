@@ -0,0 +1,279 @@
+use crate::export::get_dispatch_order;
+use crate::problem::{ConstraintType, Problem};
+use crate::schedule::Schedule;
+use std::fs::write;
+
+/// Gets the MAST priority of each job, where a larger value means a higher priority: the jobs that
+/// are dispatched earlier in `schedule` get a higher priority, or the jobs with an earlier deadline
+/// when there is no schedule
+fn get_priorities(problem: &Problem, schedule: Option<&Schedule>) -> Vec<usize> {
+	let order: Vec<usize> = match schedule {
+		Some(schedule) => get_dispatch_order(schedule).iter().map(|scheduled| scheduled.job).collect(),
+		None => {
+			let mut order: Vec<usize> = (0 .. problem.jobs.len()).collect();
+			order.sort_by_key(|&job| problem.jobs[job].get_latest_finish());
+			order
+		},
+	};
+	assert_eq!(problem.jobs.len(), order.len(), "The schedule must contain all jobs");
+	let mut priorities = vec![0; problem.jobs.len()];
+	for (position, job) in order.into_iter().enumerate() {
+		priorities[job] = problem.jobs.len() - position;
+	}
+	priorities
+}
+
+/// Groups the jobs into the (weakly) connected components of the constraint graph, since each of
+/// them becomes a MAST transaction. The jobs of each component are sorted by index.
+fn get_components(problem: &Problem) -> Vec<Vec<usize>> {
+	let mut neighbours = vec![Vec::new(); problem.jobs.len()];
+	for constraint in &problem.constraints {
+		neighbours[constraint.get_before()].push(constraint.get_after());
+		neighbours[constraint.get_after()].push(constraint.get_before());
+	}
+
+	let mut visited = vec![false; problem.jobs.len()];
+	let mut components = Vec::new();
+	for root in 0 .. problem.jobs.len() {
+		if visited[root] {
+			continue;
+		}
+		visited[root] = true;
+		let mut component = vec![root];
+		let mut next = 0;
+		while next < component.len() {
+			for &neighbour in &neighbours[component[next]] {
+				if !visited[neighbour] {
+					visited[neighbour] = true;
+					component.push(neighbour);
+				}
+			}
+			next += 1;
+		}
+		component.sort();
+		components.push(component);
+	}
+	components
+}
+
+/// Formats the transaction of the jobs in `component`, whose servers and operations are named
+/// `server_<job>` and `job_<job>`
+fn format_transaction(problem: &Problem, index: usize, component: &[usize]) -> String {
+	let mut num_outgoing_finish = vec![0; problem.jobs.len()];
+	for constraint in &problem.constraints {
+		if constraint.get_type() == ConstraintType::FinishToStart {
+			num_outgoing_finish[constraint.get_before()] += 1;
+		}
+	}
+	// The event that satisfies constraint `k` before its delay. A finish event with a single
+	// successor is used directly, and all other events are copied by a multicast.
+	let get_edge_event = |k: usize| {
+		let before = problem.constraints[k].get_before();
+		if problem.constraints[k].get_type() == ConstraintType::FinishToStart && num_outgoing_finish[before] == 1 {
+			format!("finish_{}", before)
+		} else {
+			format!("edge_{}", k)
+		}
+	};
+
+	let mut external_events = Vec::new();
+	let mut internal_events = Vec::new();
+	let mut handlers = Vec::new();
+	for &job in component {
+		let earliest_start = problem.jobs[job].earliest_start;
+		external_events.push(format!("(Type => Singular, Name => release_{}, Phase => {})", job, earliest_start));
+
+		let mut inputs = vec![format!("release_{}", job)];
+		for (k, constraint) in problem.constraints.iter().enumerate() {
+			if constraint.get_after() == job {
+				inputs.push(if constraint.get_delay() > 0 { format!("delayed_{}", k) } else { get_edge_event(k) });
+			}
+		}
+		let mut activation = inputs[0].clone();
+		if inputs.len() > 1 {
+			activation = format!("ready_{}", job);
+			internal_events.push(format!("(Type => Regular, Name => {})", activation));
+			handlers.push(format!(
+				"(Type => Barrier, Input_Events_List => ({}), Output_Event => {})", inputs.join(", "), activation
+			));
+		}
+
+		let mut start_edges = Vec::new();
+		let mut finish_edges = Vec::new();
+		for (k, constraint) in problem.constraints.iter().enumerate() {
+			if constraint.get_before() != job {
+				continue;
+			}
+			let edge_event = get_edge_event(k);
+			if edge_event.starts_with("edge_") {
+				internal_events.push(format!("(Type => Regular, Name => {})", edge_event));
+			}
+			if constraint.get_delay() > 0 {
+				internal_events.push(format!("(Type => Regular, Name => delayed_{})", k));
+				handlers.push(format!(
+					"(Type => Delay, Input_Event => {}, Output_Event => delayed_{}, Delay_Max_Interval => {}, \
+					Delay_Min_Interval => {})", edge_event, k, constraint.get_delay(), constraint.get_delay()
+				));
+			}
+			match constraint.get_type() {
+				ConstraintType::FinishToStart => finish_edges.push(edge_event),
+				ConstraintType::StartToStart => start_edges.push(edge_event),
+			}
+		}
+
+		if !start_edges.is_empty() {
+			let activity_input = format!("activate_{}", job);
+			internal_events.push(format!("(Type => Regular, Name => {})", activity_input));
+			start_edges.insert(0, activity_input.clone());
+			handlers.push(format!(
+				"(Type => Multicast, Input_Event => {}, Output_Events_List => ({}))", activation, start_edges.join(", ")
+			));
+			activation = activity_input;
+		}
+
+		internal_events.push(format!(
+			"(Type => Regular, Name => finish_{}, Timing_Requirements => (Type => Hard_Global_Deadline, \
+			Deadline => {}, Referenced_Event => release_{}))",
+			job, problem.jobs[job].get_latest_finish() - earliest_start, job
+		));
+		handlers.push(format!(
+			"(Type => Activity, Input_Event => {}, Output_Event => finish_{}, Activity_Operation => job_{}, \
+			Activity_Server => server_{})", activation, job, job, job
+		));
+		if finish_edges.len() > 1 {
+			handlers.push(format!(
+				"(Type => Multicast, Input_Event => finish_{}, Output_Events_List => ({}))", job, finish_edges.join(", ")
+			));
+		}
+	}
+
+	format!(
+		"Transaction (\n\tType => Regular,\n\tName => transaction_{},\n\tExternal_Events => (\n\t\t{}),\n\t\
+		Internal_Events => (\n\t\t{}),\n\tEvent_Handlers => (\n\t\t{}));\n\n",
+		index, external_events.join(",\n\t\t"), internal_events.join(",\n\t\t"), handlers.join(",\n\t\t")
+	)
+}
+
+/// Formats `problem` as a MAST model, such that it can be analyzed by the MAST tools. Each core is a
+/// processor with a non-preemptive fixed-priority scheduler, each job is an operation that is
+/// released once by an external event and has a hard deadline, and each shared resource is an
+/// immediate ceiling resource. The constraints are modelled by event handlers: a barrier waits until
+/// all predecessors of a job are done, and a delay handler waits for the delay of a constraint. A
+/// start-to-start constraint is satisfied when its `before` job is ready to start, which is weaker
+/// than the original constraint.
+///
+/// MAST doesn't support global multiprocessor scheduling, so each job is bound to a single core.
+/// When `schedule` is given, each job is bound to the core on which it runs in `schedule`, and jobs
+/// that are dispatched earlier get higher priorities. Otherwise, all jobs are bound to the first
+/// core and get deadline-monotonic priorities, which is only a faithful model when there is 1 core.
+pub fn format_mast_model(problem: &Problem, schedule: Option<&Schedule>) -> String {
+	let priorities = get_priorities(problem, schedule);
+	let mut cores = vec![0; problem.jobs.len()];
+	if let Some(schedule) = schedule {
+		for scheduled in schedule.get_jobs() {
+			cores[scheduled.job] = scheduled.core;
+		}
+	}
+
+	let mut model = String::from("-- Generated by np-feasibility\n\nModel (\n\tModel_Name => np_feasibility);\n\n");
+	for core in 0 .. problem.num_cores {
+		model.push_str(&format!(
+			"Processing_Resource (\n\tType => Regular_Processor,\n\tName => core_{});\n\n\
+			Scheduler (\n\tType => Primary_Scheduler,\n\tName => scheduler_{},\n\tHost => core_{},\n\t\
+			Policy => (Type => Fixed_Priority, Max_Priority => {}, Min_Priority => 1));\n\n",
+			core, core, core, usize::max(1, problem.jobs.len())
+		));
+	}
+	for resource in 0 .. problem.resources.len() {
+		model.push_str(&format!(
+			"Shared_Resource (\n\tType => Immediate_Ceiling_Resource,\n\tName => resource_{});\n\n", resource
+		));
+	}
+
+	for (index, job) in problem.jobs.iter().enumerate() {
+		model.push_str(&format!(
+			"Scheduling_Server (\n\tType => Regular,\n\tName => server_{},\n\tServer_Sched_Parameters => \
+			(Type => Non_Preemptible_FP_Policy, The_Priority => {}),\n\tScheduler => scheduler_{});\n\n",
+			index, priorities[index], cores[index]
+		));
+		let resources: Vec<String> = problem.resources.iter().enumerate().filter(
+			|(_, users)| users.contains(&index)
+		).map(|(resource, _)| format!("resource_{}", resource)).collect();
+		let resources = if resources.is_empty() {
+			String::new()
+		} else {
+			format!(",\n\tShared_Resources_List => ({})", resources.join(", "))
+		};
+		model.push_str(&format!(
+			"Operation (\n\tType => Simple,\n\tName => job_{},\n\tWorst_Case_Execution_Time => {},\n\t\
+			Best_Case_Execution_Time => {}{});\n\n",
+			index, job.get_execution_time(), job.get_min_execution_time(), resources
+		));
+	}
+
+	for (index, component) in get_components(problem).iter().enumerate() {
+		model.push_str(&format_transaction(problem, index, component));
+	}
+	model
+}
+
+/// Writes `problem` to `file_path` as a MAST model. See `format_mast_model`.
+pub fn write_mast_model(problem: &Problem, schedule: Option<&Schedule>, file_path: &str) {
+	write(file_path, format_mast_model(problem, schedule)).expect("Couldn't write MAST model file");
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::export::*;
+	use crate::problem::*;
+	use crate::schedule::*;
+
+	#[test]
+	fn test_format_mast_model() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 20),
+				Job::release_to_deadline(1, 2, 3, 10),
+				Job::release_to_deadline(2, 1, 4, 30),
+				Job::release_to_deadline(3, 0, 1, 40),
+			],
+			constraints: vec![
+				Constraint::new(0, 2, 2, ConstraintType::FinishToStart),
+				Constraint::new(1, 2, 0, ConstraintType::StartToStart),
+			],
+			num_cores: 2,
+			resources: vec![vec![0, 3]],
+		};
+		let model = format_mast_model(&problem, None);
+		assert_eq!(2, model.matches("Type => Regular_Processor").count());
+		assert!(model.contains("Name => job_3,\n\tWorst_Case_Execution_Time => 1,\n\t\
+			Best_Case_Execution_Time => 1,\n\tShared_Resources_List => (resource_0));"));
+		// Deadline-monotonic priorities, and all jobs on the first core
+		assert!(model.contains("Name => server_1,\n\tServer_Sched_Parameters => \
+			(Type => Non_Preemptible_FP_Policy, The_Priority => 4),\n\tScheduler => scheduler_0);"));
+		assert!(model.contains("Name => server_3,\n\tServer_Sched_Parameters => \
+			(Type => Non_Preemptible_FP_Policy, The_Priority => 1),\n\tScheduler => scheduler_0);"));
+
+		// Jobs 0, 1, and 2 form a transaction, and job 3 forms its own
+		assert_eq!(2, model.matches("Transaction (").count());
+		assert!(model.contains("(Type => Delay, Input_Event => finish_0, Output_Event => delayed_0, \
+			Delay_Max_Interval => 2, Delay_Min_Interval => 2)"));
+		assert!(model.contains("(Type => Multicast, Input_Event => release_1, Output_Events_List => (activate_1, edge_1))"));
+		assert!(model.contains("(Type => Barrier, Input_Events_List => (release_2, delayed_0, edge_1), Output_Event => ready_2)"));
+		assert!(model.contains("(Type => Activity, Input_Event => ready_2, Output_Event => finish_2, \
+			Activity_Operation => job_2, Activity_Server => server_2)"));
+		assert!(model.contains("(Type => Regular, Name => finish_2, Timing_Requirements => \
+			(Type => Hard_Global_Deadline, Deadline => 29, Referenced_Event => release_2))"));
+
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 0, start: 0, finish: 5, core: 0 });
+		schedule.push(ScheduledJob { job: 3, start: 0, finish: 1, core: 1 });
+		schedule.push(ScheduledJob { job: 1, start: 2, finish: 5, core: 1 });
+		schedule.push(ScheduledJob { job: 2, start: 7, finish: 11, core: 0 });
+		let model = format_mast_model(&problem, Some(&schedule));
+		assert!(model.contains("Name => server_3,\n\tServer_Sched_Parameters => \
+			(Type => Non_Preemptible_FP_Policy, The_Priority => 3),\n\tScheduler => scheduler_1);"));
+		assert!(model.contains("Name => server_2,\n\tServer_Sched_Parameters => \
+			(Type => Non_Preemptible_FP_Policy, The_Priority => 1),\n\tScheduler => scheduler_0);"));
+	}
+}
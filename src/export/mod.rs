@@ -0,0 +1,585 @@
+mod cheddar;
+mod mast;
+
+use crate::certificate::*;
+use crate::problem::{ConstraintType, Problem, Time};
+use crate::schedule::{Schedule, ScheduledJob};
+use crate::simulator::DeadlineMissHeatmap;
+use crate::stats::{JobFeatures, Verdict};
+use std::fs::write;
+use std::mem::replace;
+
+pub use cheddar::*;
+pub use mast::*;
+
+/// Formats the events of `schedule` as CSV with the columns `Time, Core, Event, Job`, where the
+/// event is `start`, `finish`, or `idle`. The `Job` column is empty for `idle` events.
+pub fn format_event_trace(schedule: &Schedule) -> String {
+	let mut csv = String::from("Time, Core, Event, Job\n");
+	for event in schedule.get_events() {
+		let job = event.job.map(|job| job.to_string()).unwrap_or_default();
+		csv.push_str(&format!("{}, {}, {}, {}\n", event.time, event.core, event.kind.get_name(), job));
+	}
+	csv
+}
+
+/// Writes the event trace of `schedule` to `file_path`. See `format_event_trace`.
+pub fn write_event_trace(schedule: &Schedule, file_path: &str) {
+	write(file_path, format_event_trace(schedule)).expect("Couldn't write event trace file");
+}
+
+/// Formats `schedule` as Chrome `trace_event` JSON, which can be opened in `chrome://tracing` or
+/// Perfetto. Each core is a thread named `core <core>`, and each job is a complete event named
+/// `job <job>` with its slack as argument. One time unit of `schedule` is shown as 1 microsecond.
+pub fn format_chrome_trace(problem: &Problem, schedule: &Schedule) -> String {
+	let mut events = Vec::new();
+	for core in 0 .. problem.num_cores {
+		events.push(format!(
+			"{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":{0},\"args\":{{\"name\":\"core {0}\"}}}}", core
+		));
+	}
+	for scheduled in get_dispatch_order(schedule) {
+		events.push(format!(
+			"{{\"name\":\"job {0}\",\"cat\":\"job\",\"ph\":\"X\",\"ts\":{1},\"dur\":{2},\"pid\":0,\"tid\":{3},\
+			\"args\":{{\"job\":{0},\"slack\":{4}}}}}",
+			scheduled.job, scheduled.start, scheduled.finish - scheduled.start, scheduled.core,
+			scheduled.get_slack(problem)
+		));
+	}
+	format!("{{\"traceEvents\":[\n{}\n]}}\n", events.join(",\n"))
+}
+
+/// Writes the Chrome trace of `schedule` to `file_path`. See `format_chrome_trace`.
+pub fn write_chrome_trace(problem: &Problem, schedule: &Schedule, file_path: &str) {
+	write(file_path, format_chrome_trace(problem, schedule)).expect("Couldn't write Chrome trace file");
+}
+
+/// Groups the jobs of `schedule` per core, sorted by start time
+fn get_core_tables(schedule: &Schedule, num_cores: usize) -> Vec<Vec<ScheduledJob>> {
+	let mut tables = vec![Vec::new(); num_cores];
+	for scheduled in schedule.get_jobs() {
+		tables[scheduled.core].push(*scheduled);
+	}
+	for table in &mut tables {
+		table.sort_by_key(|scheduled| scheduled.start);
+	}
+	tables
+}
+
+/// Formats `schedule` as a static dispatch table: a CSV file with the columns
+/// `Core, Start, Job, Slack`, grouped by core and sorted by start time. The slack is relative to
+/// the original deadline of the job.
+pub fn format_dispatch_table(problem: &Problem, schedule: &Schedule) -> String {
+	let mut csv = String::from("Core, Start, Job, Slack\n");
+	for (core, table) in get_core_tables(schedule, problem.num_cores as usize).iter().enumerate() {
+		for scheduled in table {
+			let slack = scheduled.get_slack(problem);
+			csv.push_str(&format!("{}, {}, {}, {}\n", core, scheduled.start, scheduled.job, slack));
+		}
+	}
+	csv
+}
+
+/// Formats `schedule` as a C header that can be used by a table-driven cyclic executive. The
+/// entries of core `c` are `dispatch_table[core_offsets[c]]` up to (but excluding)
+/// `dispatch_table[core_offsets[c + 1]]`, sorted by start time.
+pub fn format_c_header(schedule: &Schedule, num_cores: usize) -> String {
+	let tables = get_core_tables(schedule, num_cores);
+	let mut header = String::from(
+		"#ifndef DISPATCH_TABLE_H\n#define DISPATCH_TABLE_H\n\n#include <stdint.h>\n\n\
+		typedef struct {\n\tint64_t start;\n\tuint32_t job;\n} dispatch_entry_t;\n\n"
+	);
+	header.push_str(&format!("#define NUM_CORES {}\n", num_cores));
+	header.push_str(&format!("#define NUM_DISPATCH_ENTRIES {}\n\n", schedule.get_jobs().len()));
+
+	let mut offsets = vec![0];
+	if !schedule.get_jobs().is_empty() {
+		header.push_str("static const dispatch_entry_t dispatch_table[NUM_DISPATCH_ENTRIES] = {\n");
+		for table in &tables {
+			for scheduled in table {
+				header.push_str(&format!("\t{{ {}, {} }},\n", scheduled.start, scheduled.job));
+			}
+		}
+		header.push_str("};\n\n");
+	}
+	for table in &tables {
+		offsets.push(offsets.last().unwrap() + table.len());
+	}
+
+	let offsets: Vec<String> = offsets.iter().map(|offset| offset.to_string()).collect();
+	header.push_str(&format!(
+		"static const uint32_t core_offsets[NUM_CORES + 1] = {{ {} }};\n\n#endif\n", offsets.join(", ")
+	));
+	header
+}
+
+/// Writes the dispatch table of `schedule` to `file_path`. When the path ends with `.h`, it is
+/// written as a C header (see `format_c_header`), and otherwise as CSV (see `format_dispatch_table`).
+pub fn write_dispatch_table(problem: &Problem, schedule: &Schedule, file_path: &str) {
+	let content = if file_path.ends_with(".h") {
+		format_c_header(schedule, problem.num_cores as usize)
+	} else {
+		format_dispatch_table(problem, schedule)
+	};
+	write(file_path, content).expect("Couldn't write dispatch table file");
+}
+
+/// Gets the jobs of `schedule` in the order in which they are dispatched: sorted by start time, and
+/// then by core
+fn get_dispatch_order(schedule: &Schedule) -> Vec<ScheduledJob> {
+	let mut order = schedule.get_jobs().to_vec();
+	order.sort_by_key(|scheduled| (scheduled.start, scheduled.core));
+	order
+}
+
+/// Like `get_dispatch_order`, but each job is included only once: a gang job is represented by
+/// the entry of the lowest core that it occupies
+fn get_unique_dispatch_order(schedule: &Schedule) -> Vec<ScheduledJob> {
+	let mut order = get_dispatch_order(schedule);
+	let mut is_included = vec![false; order.iter().map(|scheduled| scheduled.job + 1).max().unwrap_or(0)];
+	order.retain(|scheduled| !replace(&mut is_included[scheduled.job], true));
+	order
+}
+
+/// Formats `schedule` as a SAG job file with the columns `Task ID, Job ID, Arrival min, Arrival max,
+/// Cost min, Cost max, Deadline, Priority`, such that SAG-based tools can analyze the time-triggered
+/// dispatching of this schedule: each job arrives exactly at its start time, and its priority is its
+/// position in the dispatch order (lower is higher). All jobs belong to task 1, and the job ID is the
+/// index of the job. Gang jobs are listed only once.
+pub fn format_sag_jobs(problem: &Problem, schedule: &Schedule) -> String {
+	let mut csv = String::from("Task ID, Job ID, Arrival min, Arrival max, Cost min, Cost max, Deadline, Priority\n");
+	for (priority, scheduled) in get_unique_dispatch_order(schedule).iter().enumerate() {
+		let job = &problem.jobs[scheduled.job];
+		csv.push_str(&format!(
+			"1, {}, {}, {}, {}, {}, {}, {}\n", scheduled.job, scheduled.start, scheduled.start,
+			job.get_min_execution_time(), job.get_execution_time(), job.get_deadline(), priority
+		));
+	}
+	csv
+}
+
+/// Formats the dispatch order of `schedule` as YAML: a list of the jobs in the order in which they
+/// are dispatched, each with its index, start time, and core. Gang jobs are listed only once, with
+/// the lowest core that they occupy.
+pub fn format_dispatch_order_yaml(schedule: &Schedule) -> String {
+	if schedule.get_jobs().is_empty() {
+		return String::from("dispatch_order: []\n");
+	}
+	let mut yaml = String::from("dispatch_order:\n");
+	for scheduled in get_unique_dispatch_order(schedule) {
+		yaml.push_str(&format!(
+			"  - {{ job: {}, start: {}, core: {} }}\n", scheduled.job, scheduled.start, scheduled.core
+		));
+	}
+	yaml
+}
+
+/// Writes `schedule` to `file_path` for SAG-based tools: as YAML when the path ends with `.yaml` or
+/// `.yml` (see `format_dispatch_order_yaml`), and otherwise as a SAG job file (see `format_sag_jobs`)
+pub fn write_sag_schedule(problem: &Problem, schedule: &Schedule, file_path: &str) {
+	let content = if file_path.ends_with(".yaml") || file_path.ends_with(".yml") {
+		format_dispatch_order_yaml(schedule)
+	} else {
+		format_sag_jobs(problem, schedule)
+	};
+	write(file_path, content).expect("Couldn't write SAG schedule file");
+}
+
+/// Formats the certificate of `schedule` (see `create_schedule_certificate`) as CSV with the
+/// columns `Job, Start, Core`, which the parser can read back
+pub fn format_schedule_certificate(schedule: &Schedule) -> String {
+	let mut csv = String::from("Job, Start, Core\n");
+	for entry in create_schedule_certificate(schedule) {
+		csv.push_str(&format!("{}, {}, {}\n", entry.job, entry.start, entry.core));
+	}
+	csv
+}
+
+/// Writes the certificate of `schedule` to `file_path`. See `format_schedule_certificate`.
+pub fn write_schedule_certificate(schedule: &Schedule, file_path: &str) {
+	write(file_path, format_schedule_certificate(schedule)).expect("Couldn't write schedule certificate file");
+}
+
+/// Formats `certificate` as CSV, which the parser can read back. Each line starts with its kind:
+/// - `empty-window, job, release, execution time, deadline`
+/// - `unsatisfiable-constraint, before, after, separation, release, execution time, deadline`
+/// - `cycle, before, after, separation` for each constraint in the cycle
+/// - `overloaded-interval, start, end, capacity`, followed by `demand, job, demand` for each job
+pub fn format_infeasibility_certificate(certificate: &InfeasibilityCertificate) -> String {
+	match certificate {
+		InfeasibilityCertificate::EmptyWindow { job, release, execution_time, deadline } => format!(
+			"empty-window, {}, {}, {}, {}\n", job, release, execution_time, deadline
+		),
+		InfeasibilityCertificate::UnsatisfiableConstraint { constraint, release, execution_time, deadline } => format!(
+			"unsatisfiable-constraint, {}, {}, {}, {}, {}, {}\n", constraint.before, constraint.after,
+			constraint.separation, release, execution_time, deadline
+		),
+		InfeasibilityCertificate::Cycle(constraints) => constraints.iter().map(
+			|constraint| format!("cycle, {}, {}, {}\n", constraint.before, constraint.after, constraint.separation)
+		).collect(),
+		InfeasibilityCertificate::OverloadedInterval { start, end, capacity, demands } => {
+			let mut csv = format!("overloaded-interval, {}, {}, {}\n", start, end, capacity);
+			for demand in demands {
+				csv.push_str(&format!("demand, {}, {}\n", demand.job, demand.demand));
+			}
+			csv
+		},
+	}
+}
+
+/// Writes `certificate` to `file_path`. See `format_infeasibility_certificate`.
+pub fn write_infeasibility_certificate(certificate: &InfeasibilityCertificate, file_path: &str) {
+	write(file_path, format_infeasibility_certificate(certificate)).expect("Couldn't write infeasibility certificate file");
+}
+
+/// Formats the jobs of `problem` as CSV with the columns `Arrival, Execution Time, Deadline`, which
+/// the parser can read back. The original deadlines are written, even when the latest finish times
+/// have been strengthened. When the problem has gang jobs, the `Parallelism` column is added. When
+/// it has affinities, the `Parallelism` and `Affinity` columns are added, where the affinity is a
+/// list of cores separated by spaces, which is empty for jobs that can run on all cores.
+pub fn format_jobs(problem: &Problem) -> String {
+	let has_affinities = problem.has_affinities();
+	let has_parallel_jobs = problem.has_parallel_jobs() || has_affinities;
+	let mut csv = String::from("Arrival, Execution Time, Deadline");
+	csv.push_str(if has_parallel_jobs { ", Parallelism" } else { "" });
+	csv.push_str(if has_affinities { ", Affinity\n" } else { "\n" });
+	for job in &problem.jobs {
+		csv.push_str(&format!("{}, {}, {}", job.earliest_start, job.get_execution_time(), job.get_deadline()));
+		if has_parallel_jobs {
+			csv.push_str(&format!(", {}", job.get_parallelism()));
+		}
+		if has_affinities {
+			let cores: Vec<String> = if job.get_affinity().is_all() {
+				Vec::new()
+			} else {
+				job.get_affinity().get_cores(problem.num_cores).map(|core| core.to_string()).collect()
+			};
+			csv.push_str(&format!(", {}", cores.join(" ")));
+		}
+		csv.push('\n');
+	}
+	csv
+}
+
+/// Formats the constraints of `problem` as CSV with the columns `Before Index, After Index, Delay,
+/// Type`, which the parser can read back. When any constraint has a maximum delay, the
+/// `Max Delay` column is added, which is empty for the other constraints.
+pub fn format_constraints(problem: &Problem) -> String {
+	let has_max_delays = problem.constraints.iter().any(|constraint| constraint.get_max_delay().is_some());
+	let mut csv = String::from("Before Index, After Index, Delay, Type");
+	csv.push_str(if has_max_delays { ", Max Delay\n" } else { "\n" });
+	for constraint in &problem.constraints {
+		let constraint_type = match constraint.get_type() {
+			ConstraintType::FinishToStart => "f-s",
+			ConstraintType::StartToStart => "s-s",
+		};
+		csv.push_str(&format!(
+			"{}, {}, {}, {}", constraint.get_before(), constraint.get_after(),
+			constraint.get_delay(), constraint_type
+		));
+		if has_max_delays {
+			csv.push_str(&format!(", {}", constraint.get_max_delay().map(|delay| delay.to_string()).unwrap_or_default()));
+		}
+		csv.push('\n');
+	}
+	csv
+}
+
+/// Writes the jobs of `problem` to `jobs_path` (see `format_jobs`), and its constraints to
+/// `precedence_path` (see `format_constraints`). Resources and criticalities are not written.
+pub fn write_problem(problem: &Problem, jobs_path: &str, precedence_path: &str) {
+	write(jobs_path, format_jobs(problem)).expect("Couldn't write jobs file");
+	write(precedence_path, format_constraints(problem)).expect("Couldn't write precedence file");
+}
+
+/// Formats the features of the jobs of `problem` (see `JobFeatures`) as CSV with the columns
+/// `Job, Slack, Window Length, In Degree, Out Degree, Chain Depth, Local Utilization, Verdict,
+/// Dispatch Position`, which can be used to train models that predict a good dispatch order. The
+/// `Chain Depth` is empty when the constraints are cyclic. The dispatch position of a job is its
+/// position in `schedule` (when a feasible schedule was found), sorted by start time.
+pub fn format_job_features(problem: &Problem, verdict: Verdict, schedule: Option<&Schedule>) -> String {
+	let mut dispatch_positions = vec![None; problem.jobs.len()];
+	if let Some(schedule) = schedule {
+		let mut jobs: Vec<&ScheduledJob> = schedule.get_jobs().iter().collect();
+		jobs.sort_by_key(|scheduled| scheduled.start);
+		let mut next_position = 0;
+		for scheduled in jobs {
+			// Gang jobs occur once for each of their cores
+			if dispatch_positions[scheduled.job].is_none() {
+				dispatch_positions[scheduled.job] = Some(next_position);
+				next_position += 1;
+			}
+		}
+	}
+
+	let mut csv = String::from(
+		"Job, Slack, Window Length, In Degree, Out Degree, Chain Depth, Local Utilization, Verdict, Dispatch Position\n"
+	);
+	for (job, features) in JobFeatures::extract(problem).iter().enumerate() {
+		let format_optional = |value: Option<usize>| value.map(|value| value.to_string()).unwrap_or_default();
+		csv.push_str(&format!(
+			"{}, {}, {}, {}, {}, {}, {:.4}, {}, {}\n", job, features.slack, features.window_length,
+			features.in_degree, features.out_degree, format_optional(features.chain_depth),
+			features.local_utilization, verdict.get_name(), format_optional(dispatch_positions[job])
+		));
+	}
+	csv
+}
+
+pub fn write_job_features(problem: &Problem, verdict: Verdict, schedule: Option<&Schedule>, file_path: &str) {
+	write(file_path, format_job_features(problem, verdict, schedule)).expect("Couldn't write job features file");
+}
+
+/// Formats `heatmap` as CSV with the columns `Job, Region Start, Region End, Misses, Blocking`, which
+/// contains a line for each job and time region with a non-zero count, and can be pivoted into a
+/// heatmap for plotting. The misses and blocking counts are explained in `DeadlineMissHeatmap`.
+pub fn format_deadline_miss_heatmap(heatmap: &DeadlineMissHeatmap) -> String {
+	let mut csv = String::from("Job, Region Start, Region End, Misses, Blocking\n");
+	for (job, (misses, blocking)) in heatmap.misses.iter().zip(&heatmap.blocking).enumerate() {
+		for (region, (num_misses, num_blocking)) in misses.iter().zip(blocking).enumerate() {
+			if *num_misses > 0 || *num_blocking > 0 {
+				let region_start = region as Time * heatmap.region_length;
+				csv.push_str(&format!(
+					"{}, {}, {}, {}, {}\n", job, region_start, region_start + heatmap.region_length,
+					num_misses, num_blocking
+				));
+			}
+		}
+	}
+	csv
+}
+
+pub fn write_deadline_miss_heatmap(heatmap: &DeadlineMissHeatmap, file_path: &str) {
+	write(file_path, format_deadline_miss_heatmap(heatmap)).expect("Couldn't write deadline miss heatmap file");
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::export::*;
+	use crate::problem::*;
+	use crate::schedule::*;
+	use crate::stats::Verdict;
+
+	#[test]
+	fn test_format_event_trace() {
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 0, start: 0, finish: 5, core: 0 });
+		schedule.push(ScheduledJob { job: 1, start: 2, finish: 4, core: 1 });
+		schedule.push(ScheduledJob { job: 2, start: 5, finish: 6, core: 0 });
+		assert_eq!("Time, Core, Event, Job\n\
+			0, 0, start, 0\n\
+			2, 1, start, 1\n\
+			4, 1, finish, 1\n\
+			4, 1, idle, \n\
+			5, 0, finish, 0\n\
+			5, 0, start, 2\n\
+			6, 0, finish, 2\n\
+			6, 0, idle, \n", format_event_trace(&schedule));
+	}
+
+	fn two_core_schedule() -> Schedule {
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 2, start: 5, finish: 6, core: 0 });
+		schedule.push(ScheduledJob { job: 1, start: 2, finish: 4, core: 2 });
+		schedule.push(ScheduledJob { job: 0, start: 0, finish: 5, core: 0 });
+		schedule
+	}
+
+	#[test]
+	fn test_format_chrome_trace() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 0, 2, 10),
+				Job::release_to_deadline(2, 0, 1, 10),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: vec![],
+		};
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 2, start: 5, finish: 6, core: 0 });
+		schedule.push(ScheduledJob { job: 1, start: 2, finish: 4, core: 1 });
+		schedule.push(ScheduledJob { job: 0, start: 0, finish: 5, core: 0 });
+		assert_eq!(
+			"{\"traceEvents\":[\n\
+			{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":0,\"args\":{\"name\":\"core 0\"}},\n\
+			{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":1,\"args\":{\"name\":\"core 1\"}},\n\
+			{\"name\":\"job 0\",\"cat\":\"job\",\"ph\":\"X\",\"ts\":0,\"dur\":5,\"pid\":0,\"tid\":0,\"args\":{\"job\":0,\"slack\":5}},\n\
+			{\"name\":\"job 1\",\"cat\":\"job\",\"ph\":\"X\",\"ts\":2,\"dur\":2,\"pid\":0,\"tid\":1,\"args\":{\"job\":1,\"slack\":6}},\n\
+			{\"name\":\"job 2\",\"cat\":\"job\",\"ph\":\"X\",\"ts\":5,\"dur\":1,\"pid\":0,\"tid\":0,\"args\":{\"job\":2,\"slack\":4}}\n]}\n",
+			format_chrome_trace(&problem, &schedule)
+		);
+	}
+
+	#[test]
+	fn test_format_dispatch_table() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 0, 2, 10),
+				Job::release_to_deadline(2, 0, 1, 10),
+			],
+			constraints: vec![],
+			num_cores: 3,
+			resources: vec![],
+		};
+		assert_eq!(
+			"Core, Start, Job, Slack\n0, 0, 0, 5\n0, 5, 2, 4\n2, 2, 1, 6\n",
+			format_dispatch_table(&problem, &two_core_schedule())
+		);
+	}
+
+	#[test]
+	fn test_format_job_features() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 0, 2, 10),
+				Job::release_to_deadline(2, 0, 1, 10),
+			],
+			constraints: vec![Constraint::new(0, 2, 0, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: vec![],
+		};
+		assert_eq!(
+			"Job, Slack, Window Length, In Degree, Out Degree, Chain Depth, Local Utilization, Verdict, Dispatch Position\n\
+			0, 5, 10, 0, 1, 1, 0.4000, feasible, 0\n\
+			1, 8, 10, 0, 0, 1, 0.4000, feasible, 1\n\
+			2, 9, 10, 1, 0, 2, 0.4000, feasible, 2\n",
+			format_job_features(&problem, Verdict::Feasible, Some(&two_core_schedule()))
+		);
+		assert!(format_job_features(&problem, Verdict::Unknown, None).ends_with("0.4000, unknown, \n"));
+	}
+
+	#[test]
+	fn test_format_deadline_miss_heatmap() {
+		let heatmap = DeadlineMissHeatmap {
+			num_samples: 10,
+			num_failed_samples: 4,
+			region_length: 5,
+			misses: vec![vec![0, 0, 0], vec![0, 4, 0]],
+			blocking: vec![vec![4, 1, 0], vec![0, 0, 0]],
+		};
+		assert_eq!(
+			"Job, Region Start, Region End, Misses, Blocking\n0, 0, 5, 0, 4\n0, 5, 10, 0, 1\n1, 5, 10, 4, 0\n",
+			format_deadline_miss_heatmap(&heatmap)
+		);
+	}
+
+	#[test]
+	fn test_format_schedule_certificate() {
+		assert_eq!(
+			"Job, Start, Core\n0, 0, 0\n1, 2, 2\n2, 5, 0\n",
+			format_schedule_certificate(&two_core_schedule())
+		);
+	}
+
+	#[test]
+	fn test_format_sag_schedule() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 0, 2, 10),
+				Job::release_to_deadline(2, 0, 1, 10),
+			],
+			constraints: vec![],
+			num_cores: 3,
+			resources: vec![],
+		};
+		problem.jobs[1].set_sag_details(0, 1, 0);
+		assert_eq!(
+			"Task ID, Job ID, Arrival min, Arrival max, Cost min, Cost max, Deadline, Priority\n\
+			1, 0, 0, 0, 5, 5, 10, 0\n1, 1, 2, 2, 1, 2, 10, 1\n1, 2, 5, 5, 1, 1, 10, 2\n",
+			format_sag_jobs(&problem, &two_core_schedule())
+		);
+		assert_eq!(
+			"dispatch_order:\n  - { job: 0, start: 0, core: 0 }\n  - { job: 1, start: 2, core: 2 }\n  \
+			- { job: 2, start: 5, core: 0 }\n",
+			format_dispatch_order_yaml(&two_core_schedule())
+		);
+		assert_eq!("dispatch_order: []\n", format_dispatch_order_yaml(&Schedule::new()));
+	}
+
+	#[test]
+	fn test_format_sag_schedule_with_gang_job() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 0, 2, 10),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: vec![],
+		};
+		problem.jobs[0].set_parallelism(2);
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 0, start: 0, finish: 5, core: 1 });
+		schedule.push(ScheduledJob { job: 0, start: 0, finish: 5, core: 0 });
+		schedule.push(ScheduledJob { job: 1, start: 5, finish: 7, core: 1 });
+		assert_eq!(
+			"Task ID, Job ID, Arrival min, Arrival max, Cost min, Cost max, Deadline, Priority\n\
+			1, 0, 0, 0, 5, 5, 10, 0\n1, 1, 5, 5, 2, 2, 10, 1\n",
+			format_sag_jobs(&problem, &schedule)
+		);
+		assert_eq!(
+			"dispatch_order:\n  - { job: 0, start: 0, core: 0 }\n  - { job: 1, start: 5, core: 1 }\n",
+			format_dispatch_order_yaml(&schedule)
+		);
+	}
+
+	#[test]
+	fn test_format_c_header() {
+		let header = format_c_header(&two_core_schedule(), 3);
+		assert!(header.starts_with("#ifndef DISPATCH_TABLE_H\n"));
+		assert!(header.contains("#define NUM_CORES 3\n#define NUM_DISPATCH_ENTRIES 3\n"));
+		assert!(header.contains("= {\n\t{ 0, 0 },\n\t{ 5, 2 },\n\t{ 2, 1 },\n};\n"));
+		assert!(header.contains("core_offsets[NUM_CORES + 1] = { 0, 2, 2, 3 };\n"));
+		assert!(header.ends_with("#endif\n"));
+
+		let empty_header = format_c_header(&Schedule::new(), 1);
+		assert!(!empty_header.contains("dispatch_table"));
+		assert!(empty_header.contains("core_offsets[NUM_CORES + 1] = { 0, 0 };\n"));
+	}
+
+	#[test]
+	fn test_format_problem() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 4, 2, 10),
+				Job::release_to_deadline(1, 10, 3, 30),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 5, ConstraintType::FinishToStart),
+				Constraint::new(1, 0, 0, ConstraintType::StartToStart),
+			],
+			num_cores: 1,
+			resources: vec![],
+		};
+		assert_eq!("Arrival, Execution Time, Deadline\n4, 2, 10\n10, 3, 30\n", format_jobs(&problem));
+
+		// A strengthened latest finish time must not be written as the deadline
+		let mut strengthened = problem.clone();
+		strengthened.jobs[0].set_latest_finish(8);
+		assert_eq!(format_jobs(&problem), format_jobs(&strengthened));
+
+		let mut pinned = problem.clone();
+		pinned.num_cores = 3;
+		pinned.jobs[1].set_affinity(CoreSet::from_cores(&[0, 2]));
+		assert_eq!(
+			"Arrival, Execution Time, Deadline, Parallelism, Affinity\n4, 2, 10, 1, \n10, 3, 30, 1, 0 2\n",
+			format_jobs(&pinned)
+		);
+		assert_eq!(
+			"Before Index, After Index, Delay, Type\n0, 1, 5, f-s\n1, 0, 0, s-s\n",
+			format_constraints(&problem)
+		);
+
+		let mut bounded = problem.clone();
+		bounded.constraints[0].set_max_delay(Some(8));
+		assert_eq!(
+			"Before Index, After Index, Delay, Type, Max Delay\n0, 1, 5, f-s, 8\n1, 0, 0, s-s, \n",
+			format_constraints(&bounded)
+		);
+	}
+}
@@ -0,0 +1,179 @@
+use crate::problem::*;
+
+/// A 64-bit FNV-1a hasher. Unlike `std::hash::DefaultHasher`, its output is guaranteed to be the
+/// same on every platform and with every Rust version, so it can be stored in files.
+struct Fnv1a {
+	state: u64,
+}
+
+impl Fnv1a {
+	fn new() -> Self {
+		Self { state: 0xcbf29ce484222325 }
+	}
+
+	fn write_u64(&mut self, value: u64) {
+		for byte in value.to_le_bytes() {
+			self.state ^= byte as u64;
+			self.state = self.state.wrapping_mul(0x100000001b3);
+		}
+	}
+
+	fn write_i64(&mut self, value: i64) {
+		self.write_u64(value as u64);
+	}
+}
+
+/// The properties of a job that don't depend on its index. The fifth element is the HI execution
+/// time of HI jobs, and -1 for LO jobs. The last elements are the parallelism and the affinity.
+type JobSignature = (Time, Time, Time, Time, Time, u32, CoreSet);
+
+fn get_signature(job: &Job) -> JobSignature {
+	let hi_execution_time = match job.get_criticality() {
+		Criticality::Lo => -1,
+		Criticality::Hi => job.get_hi_execution_time(),
+	};
+	(
+		job.earliest_start, job.latest_start, job.get_execution_time(), job.get_deadline(), hi_execution_time,
+		job.get_parallelism(), job.get_affinity()
+	)
+}
+
+/// Computes a stable fingerprint of `problem`, which can be used to recognize the same problem in
+/// caches and experiment results. The fingerprint depends on the number of cores, the windows,
+/// execution times, and criticalities of the jobs, the constraints, and the shared resources, but
+/// *not* on the order of the jobs, constraints, or resources: permuting them doesn't change the
+/// fingerprint.
+///
+/// To achieve this, jobs are identified by their properties rather than their index. As a
+/// consequence, two problems that differ only in the constraints between jobs with identical
+/// properties may get the same fingerprint.
+pub fn compute_fingerprint(problem: &Problem) -> u64 {
+	let signatures: Vec<JobSignature> = problem.jobs.iter().map(get_signature).collect();
+
+	let mut sorted_signatures = signatures.clone();
+	sorted_signatures.sort();
+
+	let mut constraints: Vec<(JobSignature, JobSignature, Time, bool, Option<Time>)> = problem.constraints.iter().map(
+		|c| (
+			signatures[c.get_before()], signatures[c.get_after()], c.get_delay(),
+			c.get_type() == ConstraintType::FinishToStart, c.get_max_delay()
+		)
+	).collect();
+	constraints.sort();
+
+	let mut resources: Vec<Vec<JobSignature>> = problem.resources.iter().map(|users| {
+		let mut user_signatures: Vec<JobSignature> = users.iter().map(|job| signatures[*job]).collect();
+		user_signatures.sort();
+		user_signatures
+	}).collect();
+	resources.sort();
+
+	let mut hasher = Fnv1a::new();
+	let write_signature = |hasher: &mut Fnv1a, signature: &JobSignature| {
+		for value in [signature.0, signature.1, signature.2, signature.3, signature.4] {
+			hasher.write_i64(value);
+		}
+		// The parallelism of ordinary jobs is not hashed, so that their fingerprints stay the same
+		if signature.5 > 1 {
+			hasher.write_u64(signature.5 as u64);
+		}
+		// Likewise, the affinity is only hashed when it doesn't contain all cores
+		if !signature.6.is_all() {
+			let cores: Vec<usize> = signature.6.get_cores(problem.num_cores).collect();
+			hasher.write_u64(cores.len() as u64);
+			for core in cores {
+				hasher.write_u64(core as u64);
+			}
+		}
+	};
+
+	hasher.write_u64(problem.num_cores as u64);
+	hasher.write_u64(sorted_signatures.len() as u64);
+	for signature in &sorted_signatures {
+		write_signature(&mut hasher, signature);
+	}
+	hasher.write_u64(constraints.len() as u64);
+	for (before, after, delay, is_finish_to_start, max_delay) in &constraints {
+		write_signature(&mut hasher, before);
+		write_signature(&mut hasher, after);
+		hasher.write_i64(*delay);
+		hasher.write_u64(*is_finish_to_start as u64);
+		// Like the parallelism, the maximum delay is only hashed when it's used
+		if let Some(max_delay) = max_delay {
+			hasher.write_i64(*max_delay);
+		}
+	}
+	hasher.write_u64(resources.len() as u64);
+	for users in &resources {
+		hasher.write_u64(users.len() as u64);
+		for signature in users {
+			write_signature(&mut hasher, signature);
+		}
+	}
+	hasher.state
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::fingerprint::compute_fingerprint;
+	use crate::parser::parse_problem;
+	use crate::permutation::ProblemPermutation;
+	use crate::problem::*;
+
+	#[test]
+	fn test_fingerprint_is_independent_of_permutation() {
+		let mut problem = parse_problem(
+			"./test-problems/feasible/1core/case1.csv", Some("./test-problems/feasible/1core/case1.prec.csv"), 1
+		);
+		let fingerprint = compute_fingerprint(&problem);
+
+		ProblemPermutation::possible(&mut problem).unwrap();
+		assert_eq!(fingerprint, compute_fingerprint(&problem));
+
+		problem.constraints.reverse();
+		assert_eq!(fingerprint, compute_fingerprint(&problem));
+	}
+
+	#[test]
+	fn test_fingerprint_depends_on_problem() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 20, 100),
+			],
+			constraints: vec![Constraint::new(0, 1, 5, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		let fingerprint = compute_fingerprint(&problem);
+		assert_eq!(fingerprint, compute_fingerprint(&problem.clone()));
+
+		problem.num_cores = 3;
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+		problem.num_cores = 2;
+
+		problem.constraints[0] = Constraint::new(1, 0, 5, ConstraintType::FinishToStart);
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+
+		problem.constraints[0] = Constraint::new(0, 1, 5, ConstraintType::StartToStart);
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+
+		problem.constraints[0] = Constraint::new(0, 1, 5, ConstraintType::FinishToStart);
+		problem.resources.push(vec![0, 1]);
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+		problem.resources.clear();
+
+		problem.jobs[0].set_parallelism(2);
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+		problem.jobs[0].set_parallelism(1);
+		assert_eq!(fingerprint, compute_fingerprint(&problem));
+
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[1]));
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+		problem.jobs[0].set_affinity(CoreSet::all());
+		assert_eq!(fingerprint, compute_fingerprint(&problem));
+
+		problem.jobs[0].set_hi_criticality(10);
+		assert_ne!(fingerprint, compute_fingerprint(&problem));
+	}
+}
@@ -0,0 +1,406 @@
+use crate::problem::*;
+
+/// The parameters of a randomly generated problem. The same configuration (including the seed)
+/// always generates the same problem.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GeneratorConfig {
+	pub seed: u64,
+	pub num_jobs: usize,
+	pub num_cores: u32,
+
+	/// The total execution time of all jobs, divided by `num_cores * horizon`. It is distributed
+	/// over the jobs using UUniFast.
+	pub utilization: f64,
+
+	/// All jobs are released before `horizon`. Deadlines can exceed it when the constraints push
+	/// jobs to the end.
+	pub horizon: Time,
+
+	/// The window of each job (from its release to its deadline) is its execution time multiplied by
+	/// a random factor between 1 and `max_window_factor`
+	pub max_window_factor: f64,
+
+	/// The jobs are divided over this many layers. Every job in a layer (except the first) gets
+	/// between 1 and `max_fan_in` predecessors from the previous layer, if the DAG may contain
+	/// constraints at all. A single layer means that there are no constraints.
+	pub num_layers: usize,
+	pub max_fan_in: usize,
+
+	/// The delay of each constraint is chosen uniformly between 0 and `max_delay`
+	pub max_delay: Time,
+}
+
+impl Default for GeneratorConfig {
+	fn default() -> Self {
+		Self {
+			seed: 0,
+			num_jobs: 100,
+			num_cores: 2,
+			utilization: 0.5,
+			horizon: 10_000,
+			max_window_factor: 3.0,
+			num_layers: 1,
+			max_fan_in: 2,
+			max_delay: 0,
+		}
+	}
+}
+
+/// The parameters of a randomly generated periodic task set, which is unrolled into the jobs that
+/// are released before `horizon`. The same configuration (including the seed) always generates the
+/// same problem.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TaskSetConfig {
+	pub seed: u64,
+	pub num_tasks: usize,
+	pub num_cores: u32,
+
+	/// The total utilization of all tasks, divided by `num_cores`. It is distributed over the tasks
+	/// using UUniFast-Discard, so no task has a utilization larger than 1.
+	pub utilization: f64,
+
+	/// The period of each task is chosen log-uniformly between `min_period` and `max_period`, and
+	/// rounded down to a multiple of `period_granularity`. Each task has an implicit deadline.
+	pub min_period: Time,
+	pub max_period: Time,
+	pub period_granularity: Time,
+
+	/// All jobs are released before `horizon`
+	pub horizon: Time,
+
+	/// The tasks are divided over layers like the jobs of `GeneratorConfig`. When task `a` is a
+	/// predecessor of task `b`, each job of `b` gets a constraint from the last job of `a` that was
+	/// released at the same time or before it.
+	pub num_layers: usize,
+	pub max_fan_in: usize,
+
+	/// The delay of each constraint between tasks is chosen uniformly between 0 and `max_delay`
+	pub max_delay: Time,
+}
+
+impl Default for TaskSetConfig {
+	fn default() -> Self {
+		Self {
+			seed: 0,
+			num_tasks: 10,
+			num_cores: 2,
+			utilization: 0.5,
+			min_period: 100,
+			max_period: 10_000,
+			period_granularity: 10,
+			horizon: 10_000,
+			num_layers: 1,
+			max_fan_in: 2,
+			max_delay: 0,
+		}
+	}
+}
+
+/// A small SplitMix64 generator, which is good enough for generating test problems, and keeps the
+/// generated problems stable across platforms and versions
+pub(crate) struct Random {
+	pub(crate) state: u64,
+}
+
+impl Random {
+	pub(crate) fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+		z ^ (z >> 31)
+	}
+
+	/// Returns a uniformly distributed number in the range [0, 1)
+	pub(crate) fn next_f64(&mut self) -> f64 {
+		(self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+	}
+
+	/// Returns a number in the range [0, bound), or 0 when `bound` is 0
+	pub(crate) fn next_below(&mut self, bound: u64) -> u64 {
+		if bound == 0 { 0 } else { self.next_u64() % bound }
+	}
+}
+
+/// Distributes `total_utilization` over `num_jobs` jobs using the UUniFast algorithm of
+/// Bini and Buttazzo, which gives a uniform distribution over all possible splits
+fn uunifast(random: &mut Random, num_jobs: usize, total_utilization: f64) -> Vec<f64> {
+	let mut utilizations = Vec::with_capacity(num_jobs);
+	let mut remaining = total_utilization;
+	for index in 1 .. num_jobs {
+		let next_remaining = remaining * random.next_f64().powf(1.0 / (num_jobs - index) as f64);
+		utilizations.push(remaining - next_remaining);
+		remaining = next_remaining;
+	}
+	if num_jobs > 0 {
+		utilizations.push(remaining);
+	}
+	utilizations
+}
+
+/// Distributes `total_utilization` over `num_tasks` tasks like `uunifast`, but discards and redraws
+/// the distribution until no task has a utilization larger than 1, as proposed by Davis and Burns
+/// for multiprocessor task sets. `total_utilization` can't exceed `num_tasks`.
+fn uunifast_discard(random: &mut Random, num_tasks: usize, total_utilization: f64) -> Vec<f64> {
+	assert!(total_utilization <= num_tasks as f64, "The utilization of a task can't exceed 1");
+	loop {
+		let utilizations = uunifast(random, num_tasks, total_utilization);
+		if utilizations.iter().all(|utilization| *utilization <= 1.0) {
+			return utilizations;
+		}
+	}
+}
+
+/// Returns a number that is log-uniformly distributed between `min` and `max`, and rounded down to
+/// a multiple of `granularity` (but at least `granularity`)
+fn next_log_uniform(random: &mut Random, min: Time, max: Time, granularity: Time) -> Time {
+	let value = ((min as f64).ln() + random.next_f64() * ((max as f64).ln() - (min as f64).ln())).exp();
+	Time::max(granularity, (value as Time) / granularity * granularity)
+}
+
+/// Generates the constraints of a layered DAG of `num_nodes` nodes, which are divided over
+/// `num_layers` layers. Every node in a layer (except the first) gets between 1 and `max_fan_in`
+/// predecessors from the previous layer. The constraints are sorted by their `after` node.
+fn generate_layered_dag(
+	random: &mut Random, num_nodes: usize, num_layers: usize, max_fan_in: usize, max_delay: Time
+) -> Vec<Constraint> {
+	let layer_start = |layer: usize| layer * num_nodes / num_layers;
+	let mut constraints = Vec::new();
+	for layer in 1 .. num_layers {
+		let previous_layer = layer_start(layer - 1) .. layer_start(layer);
+		if previous_layer.is_empty() {
+			continue;
+		}
+		for after in layer_start(layer) .. layer_start(layer + 1) {
+			let fan_in = 1 + random.next_below(max_fan_in as u64) as usize;
+			let mut predecessors = Vec::new();
+			for _ in 0 .. fan_in {
+				let before = previous_layer.start + random.next_below(previous_layer.len() as u64) as usize;
+				if !predecessors.contains(&before) {
+					predecessors.push(before);
+				}
+			}
+			predecessors.sort();
+			for before in predecessors {
+				let delay = random.next_below(max_delay as u64 + 1) as Time;
+				constraints.push(Constraint::new(before, after, delay, ConstraintType::FinishToStart));
+			}
+		}
+	}
+	constraints
+}
+
+/// Generates a random problem with the given configuration. The release time of each job is at
+/// least the earliest time at which all its predecessors could have finished (including the
+/// delays), so the windows are consistent with the DAG, but the problem may still be infeasible.
+pub fn generate_problem(config: &GeneratorConfig) -> Problem {
+	assert!(config.num_layers > 0, "There must be at least 1 layer");
+	assert!(config.max_window_factor >= 1.0, "The window of a job can't be smaller than its execution time");
+	let mut random = Random { state: config.seed };
+
+	let total_utilization = config.utilization * config.num_cores as f64;
+	let execution_times: Vec<Time> = uunifast(&mut random, config.num_jobs, total_utilization).into_iter().map(
+		|utilization| Time::max(1, (utilization * config.horizon as f64).round() as Time)
+	).collect();
+
+	let constraints = generate_layered_dag(
+		&mut random, config.num_jobs, config.num_layers, config.max_fan_in, config.max_delay
+	);
+
+	let mut jobs = Vec::with_capacity(config.num_jobs);
+	let mut constraint_index = 0;
+	for (index, &execution_time) in execution_times.iter().enumerate() {
+		let window_factor = 1.0 + random.next_f64() * (config.max_window_factor - 1.0);
+		let window = (execution_time as f64 * window_factor).round() as Time;
+
+		// The constraints are sorted by their 'after' job, and all predecessors are already generated
+		let mut release = random.next_below(u64::max(1, config.horizon as u64)) as Time;
+		while constraint_index < constraints.len() && constraints[constraint_index].get_after() == index {
+			let constraint = constraints[constraint_index];
+			let before: &Job = &jobs[constraint.get_before()];
+			release = Time::max(release, before.get_earliest_finish() + constraint.get_delay());
+			constraint_index += 1;
+		}
+		jobs.push(Job::release_to_deadline(index, release, execution_time, release + window));
+	}
+
+	let problem = Problem { jobs, constraints, num_cores: config.num_cores, resources: Vec::new() };
+	problem.validate();
+	problem
+}
+
+/// Generates a random periodic task set with the given configuration, and unrolls it into a
+/// problem. The jobs of each task are consecutive, and sorted by release time. Unlike
+/// `generate_problem`, the release times are strictly periodic, so the constraints between tasks
+/// can make the problem infeasible.
+pub fn generate_task_set(config: &TaskSetConfig) -> Problem {
+	assert!(config.num_layers > 0, "There must be at least 1 layer");
+	assert!(config.period_granularity > 0, "The period granularity must be positive");
+	assert!(
+		config.period_granularity <= config.min_period && config.min_period <= config.max_period,
+		"The periods must satisfy granularity <= min period <= max period"
+	);
+	let mut random = Random { state: config.seed };
+
+	let total_utilization = config.utilization * config.num_cores as f64;
+	let utilizations = uunifast_discard(&mut random, config.num_tasks, total_utilization);
+	let periods: Vec<Time> = (0 .. config.num_tasks).map(
+		|_| next_log_uniform(&mut random, config.min_period, config.max_period, config.period_granularity)
+	).collect();
+	let task_constraints = generate_layered_dag(
+		&mut random, config.num_tasks, config.num_layers, config.max_fan_in, config.max_delay
+	);
+
+	let mut jobs = Vec::new();
+	let mut first_jobs = Vec::with_capacity(config.num_tasks);
+	for (&utilization, &period) in utilizations.iter().zip(&periods) {
+		first_jobs.push(jobs.len());
+		let execution_time = Time::max(1, (utilization * period as f64).round() as Time);
+		let execution_time = Time::min(execution_time, period);
+		let mut release = 0;
+		while release < config.horizon {
+			jobs.push(Job::release_to_deadline(jobs.len(), release, execution_time, release + period));
+			release += period;
+		}
+	}
+
+	let mut constraints = Vec::new();
+	for constraint in &task_constraints {
+		let (before, after) = (constraint.get_before(), constraint.get_after());
+		let num_after_jobs = ((config.horizon + periods[after] - 1) / periods[after]) as usize;
+		for instance in 0 .. num_after_jobs {
+			let release = instance as Time * periods[after];
+			let before_instance = (release / periods[before]) as usize;
+			constraints.push(Constraint::new(
+				first_jobs[before] + before_instance, first_jobs[after] + instance,
+				constraint.get_delay(), ConstraintType::FinishToStart
+			));
+		}
+	}
+
+	let problem = Problem { jobs, constraints, num_cores: config.num_cores, resources: Vec::new() };
+	problem.validate();
+	problem
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::generator::*;
+
+	#[test]
+	fn test_uunifast() {
+		let mut random = Random { state: 12 };
+		let utilizations = uunifast(&mut random, 10, 1.5);
+		assert_eq!(10, utilizations.len());
+		assert!(utilizations.iter().all(|utilization| *utilization >= 0.0));
+		assert!((utilizations.iter().sum::<f64>() - 1.5).abs() < 1e-9);
+	}
+
+	#[test]
+	fn test_generate_problem() {
+		let config = GeneratorConfig {
+			seed: 1234,
+			num_jobs: 50,
+			num_cores: 4,
+			utilization: 0.3,
+			num_layers: 5,
+			max_fan_in: 3,
+			max_delay: 20,
+			..Default::default()
+		};
+		let problem = generate_problem(&config);
+		assert_eq!(problem, generate_problem(&config));
+		assert_ne!(problem, generate_problem(&GeneratorConfig { seed: 1235, ..config }));
+
+		assert_eq!(50, problem.jobs.len());
+		assert_eq!(4, problem.num_cores);
+		assert!(problem.constraints.len() >= 40);
+		assert!(problem.is_job_order_possible());
+		for constraint in &problem.constraints {
+			assert_eq!(constraint.get_before() / 10 + 1, constraint.get_after() / 10);
+			assert!(constraint.get_delay() <= 20);
+			let before = &problem.jobs[constraint.get_before()];
+			let after = &problem.jobs[constraint.get_after()];
+			assert!(after.earliest_start >= before.get_earliest_finish() + constraint.get_delay());
+		}
+		for job in &problem.jobs {
+			let window = job.get_latest_finish() - job.earliest_start;
+			assert!(window >= job.get_execution_time());
+			assert!(window as f64 <= 3.0 * job.get_execution_time() as f64 + 0.5);
+		}
+
+		let total_execution_time: Time = problem.jobs.iter().map(|job| job.get_execution_time()).sum();
+		assert!((total_execution_time - 12_000).abs() <= 50);
+	}
+
+	#[test]
+	fn test_uunifast_discard() {
+		let mut random = Random { state: 5 };
+		for _ in 0 .. 20 {
+			let utilizations = uunifast_discard(&mut random, 4, 3.2);
+			assert!(utilizations.iter().all(|utilization| *utilization >= 0.0 && *utilization <= 1.0));
+			assert!((utilizations.iter().sum::<f64>() - 3.2).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_next_log_uniform() {
+		let mut random = Random { state: 8 };
+		let values: Vec<Time> = (0 .. 1000).map(|_| next_log_uniform(&mut random, 10, 10_000, 10)).collect();
+		assert!(values.iter().all(|value| *value >= 10 && *value <= 10_000 && *value % 10 == 0));
+		// Log-uniform means that each decade gets roughly the same number of values
+		let num_small = values.iter().filter(|value| **value < 100).count();
+		let num_large = values.iter().filter(|value| **value >= 1000).count();
+		assert!(num_small > 250 && num_small < 420, "{}", num_small);
+		assert!(num_large > 250 && num_large < 420, "{}", num_large);
+	}
+
+	#[test]
+	fn test_generate_task_set() {
+		let config = TaskSetConfig {
+			seed: 77,
+			num_tasks: 8,
+			num_cores: 2,
+			utilization: 0.6,
+			min_period: 100,
+			max_period: 1000,
+			period_granularity: 50,
+			horizon: 2000,
+			num_layers: 2,
+			max_fan_in: 2,
+			max_delay: 5,
+		};
+		let problem = generate_task_set(&config);
+		assert_eq!(problem, generate_task_set(&config));
+		assert!(problem.is_job_order_possible());
+		assert!(!problem.constraints.is_empty());
+
+		for job in &problem.jobs {
+			let period = job.get_latest_finish() - job.earliest_start;
+			assert!((100 .. 1000).contains(&period) && period % 50 == 0);
+			assert_eq!(0, job.earliest_start % period);
+			assert!(job.earliest_start < 2000);
+		}
+		for constraint in &problem.constraints {
+			let before = &problem.jobs[constraint.get_before()];
+			let after = &problem.jobs[constraint.get_after()];
+			assert!(before.earliest_start <= after.earliest_start);
+			assert!(constraint.get_delay() <= 5);
+		}
+
+		// Each task has ceil(horizon / period) jobs, and the total utilization is 0.6 * 2 cores
+		let utilization: f64 = problem.jobs.iter().map(|job| {
+			let period = job.get_latest_finish() - job.earliest_start;
+			job.get_execution_time() as f64 / period as f64 / ((2000 + period - 1) / period) as f64
+		}).sum();
+		assert!((utilization - 1.2).abs() < 0.05, "{}", utilization);
+	}
+
+	#[test]
+	fn test_generate_problem_without_layers() {
+		let problem = generate_problem(&GeneratorConfig::default());
+		assert_eq!(100, problem.jobs.len());
+		assert!(problem.constraints.is_empty());
+		assert!(problem.jobs.iter().all(|job| job.earliest_start < 10_000));
+	}
+}
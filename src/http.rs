@@ -0,0 +1,150 @@
+use crate::daemon::{analyze_json, JsonEffort};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::spawn;
+
+/// Requests with a larger body are rejected
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Request {
+	method: String,
+	path: String,
+	body: String,
+}
+
+/// Reads an HTTP/1.1 request from `reader`. Only the `Content-Length` header is used.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line).map_err(|error| error.to_string())?;
+	let mut parts = request_line.split_whitespace();
+	let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+		return Err(format!("Malformed request line: {}", request_line.trim()));
+	};
+
+	let mut content_length = 0;
+	loop {
+		let mut header = String::new();
+		if reader.read_line(&mut header).map_err(|error| error.to_string())? == 0 {
+			return Err(String::from("The request ended before its body"));
+		}
+		let header = header.trim();
+		if header.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = header.split_once(':') && name.trim().eq_ignore_ascii_case("content-length") {
+			content_length = value.trim().parse().map_err(|_| format!("Malformed Content-Length: {}", value))?;
+		}
+	}
+	if content_length > MAX_BODY_SIZE {
+		return Err(format!("The body can't be larger than {} bytes", MAX_BODY_SIZE));
+	}
+
+	let mut body = vec![0; content_length];
+	reader.read_exact(&mut body).map_err(|error| error.to_string())?;
+	let body = String::from_utf8(body).map_err(|_| String::from("The body is not valid UTF-8"))?;
+	Ok(Request { method: method.to_string(), path: path.to_string(), body })
+}
+
+/// Computes the status code and JSON body of the response to `request`
+fn respond(request: &Request) -> (u16, String) {
+	let effort = match request.path.as_str() {
+		"/analyze" => JsonEffort::Analyze,
+		"/solve" => JsonEffort::Solve,
+		_ => return (404, String::from(r#"{"error":"Unknown path, use /analyze or /solve"}"#)),
+	};
+	match request.method.as_str() {
+		"POST" => match analyze_json(&request.body, effort) {
+			Ok(verdict) => (200, verdict),
+			Err(error) => (400, error),
+		},
+		// The preflight request of browsers, since the front-end may be served from another origin
+		"OPTIONS" => (204, String::new()),
+		_ => (405, String::from(r#"{"error":"Only POST is allowed"}"#)),
+	}
+}
+
+fn get_reason_phrase(status: u16) -> &'static str {
+	match status {
+		200 => "OK",
+		204 => "No Content",
+		400 => "Bad Request",
+		404 => "Not Found",
+		405 => "Method Not Allowed",
+		_ => "Unknown",
+	}
+}
+
+fn write_response(writer: &mut impl Write, status: u16, body: &str) -> std::io::Result<()> {
+	write!(
+		writer, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+		Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\n\
+		Access-Control-Allow-Headers: Content-Type\r\nConnection: close\r\n\r\n{}",
+		status, get_reason_phrase(status), body.len(), body
+	)?;
+	writer.flush()
+}
+
+fn handle_connection(stream: TcpStream) {
+	let mut reader = BufReader::new(&stream);
+	let (status, body) = match read_request(&mut reader) {
+		Ok(request) => respond(&request),
+		Err(error) => (400, serde_json::json!({ "error": error }).to_string()),
+	};
+	// The client may have disconnected already, in which case there is nobody to tell
+	let _ = write_response(&mut &stream, status, &body);
+}
+
+/// Serves the analyses over HTTP on `address` (for instance `127.0.0.1:8080`), until the process
+/// is stopped. Each connection is handled on its own thread, and carries a single request:
+/// - `POST /analyze` expects a problem in the JSON format of `run_daemon` as body, and responds
+///   with its verdict, including a schedule or explanation when possible
+/// - `POST /solve` is like `/analyze`, but tries harder (see `JsonEffort::Solve`)
+///
+/// Error verdicts get status 400. All responses allow cross-origin requests, so a web front-end can
+/// use this server directly.
+pub fn serve(address: &str) {
+	let listener = TcpListener::bind(address).expect("Couldn't bind HTTP server");
+	for stream in listener.incoming() {
+		match stream {
+			Ok(stream) => {
+				spawn(move || handle_connection(stream));
+			},
+			Err(error) => eprintln!("Couldn't accept connection: {}", error),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::http::*;
+
+	#[test]
+	fn test_read_request() {
+		let body = r#"{"num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": 10}]}"#;
+		let raw = format!("POST /solve HTTP/1.1\r\nHost: localhost\r\ncontent-length: {}\r\n\r\n{}", body.len(), body);
+		let request = read_request(&mut raw.as_bytes()).unwrap();
+		assert_eq!(Request { method: String::from("POST"), path: String::from("/solve"), body: String::from(body) }, request);
+		assert_eq!((200, String::from(r#"{"verdict":"feasible","schedule":[{"job":0,"start":0,"core":0}]}"#)), respond(&request));
+
+		assert!(read_request(&mut "POST /analyze HTTP/1.1\r\nContent-Length: 10\r\n\r\n{}".as_bytes()).is_err());
+		assert!(read_request(&mut "\r\n".as_bytes()).is_err());
+	}
+
+	#[test]
+	fn test_respond() {
+		let request = |method: &str, path: &str, body: &str| Request {
+			method: method.to_string(), path: path.to_string(), body: body.to_string()
+		};
+		assert_eq!(404, respond(&request("POST", "/", "")).0);
+		assert_eq!(405, respond(&request("GET", "/analyze", "")).0);
+		assert_eq!(204, respond(&request("OPTIONS", "/analyze", "")).0);
+		assert_eq!(400, respond(&request("POST", "/analyze", "{}")).0);
+
+		let (status, body) = respond(&request(
+			"POST", "/analyze", r#"{"num_cores": 1, "jobs": [{"release": 0, "execution_time": 5, "deadline": 4}]}"#
+		));
+		assert_eq!(200, status);
+		assert!(body.starts_with(r#"{"verdict":"infeasible","explanation":"#), "{}", body);
+	}
+}
@@ -0,0 +1,566 @@
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+/// A set of indices in the range `0 .. capacity`, which is stored as a bitset. Membership tests,
+/// insertions, and removals take constant time, and iterating over the indices skips empty words.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DenseIndexSet {
+	words: Vec<u64>,
+	len: usize,
+	capacity: usize,
+}
+
+impl DenseIndexSet {
+	/// Creates an empty set that can hold the indices `0 .. capacity`
+	pub fn new(capacity: usize) -> Self {
+		Self { words: vec![0; capacity.div_ceil(BITS_PER_WORD)], len: 0, capacity }
+	}
+
+	/// Gets the number of indices that this set can hold, which is independent of `len`
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Adds `index` to this set, and returns `true` if it wasn't in this set yet
+	pub fn insert(&mut self, index: usize) -> bool {
+		let word = &mut self.words[index / BITS_PER_WORD];
+		let mask = 1u64 << (index % BITS_PER_WORD);
+		let is_new = *word & mask == 0;
+		*word |= mask;
+		if is_new {
+			self.len += 1;
+		}
+		is_new
+	}
+
+	/// Removes `index` from this set, and returns `true` if it was in this set
+	pub fn remove(&mut self, index: usize) -> bool {
+		let word = &mut self.words[index / BITS_PER_WORD];
+		let mask = 1u64 << (index % BITS_PER_WORD);
+		let was_present = *word & mask != 0;
+		*word &= !mask;
+		if was_present {
+			self.len -= 1;
+		}
+		was_present
+	}
+
+	pub fn contains(&self, index: usize) -> bool {
+		self.words[index / BITS_PER_WORD] & (1u64 << (index % BITS_PER_WORD)) != 0
+	}
+
+	/// Gets the number of indices in this set
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Removes all indices from this set, without changing its capacity
+	pub fn clear(&mut self) {
+		self.words.fill(0);
+		self.len = 0;
+	}
+
+	/// Adds all indices of `other` to this set. Both sets must have the same capacity.
+	pub fn union(&mut self, other: &DenseIndexSet) {
+		assert_eq!(self.words.len(), other.words.len());
+		for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+			*word |= *other_word;
+		}
+		self.update_len();
+	}
+
+	/// Removes all indices that are not in `other` from this set. Both sets must have the same
+	/// capacity.
+	pub fn intersect(&mut self, other: &DenseIndexSet) {
+		assert_eq!(self.words.len(), other.words.len());
+		for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+			*word &= *other_word;
+		}
+		self.update_len();
+	}
+
+	/// Finds the smallest index `i >= from` in `word_index`, or in a later word, such that bit `i` of
+	/// `words` XOR `flip` is set. This scans the words linearly.
+	fn find_next(words: &[u64], from: usize, flip: u64) -> Option<usize> {
+		let mut word_index = from / BITS_PER_WORD;
+		if word_index >= words.len() {
+			return None;
+		}
+		let mut bits = (words[word_index] ^ flip) & (!0u64 << (from % BITS_PER_WORD));
+		while bits == 0 {
+			word_index += 1;
+			if word_index >= words.len() {
+				return None;
+			}
+			bits = words[word_index] ^ flip;
+		}
+		Some(word_index * BITS_PER_WORD + bits.trailing_zeros() as usize)
+	}
+
+	/// Finds the smallest index `i >= from` that is in this set. This scans the words linearly, so
+	/// use a `HierarchicalIndexSet` when this is a hot loop.
+	pub fn next_set(&self, from: usize) -> Option<usize> {
+		Self::find_next(&self.words, from, 0)
+	}
+
+	/// Finds the smallest index `i >= from` (and `i < capacity`) that is *not* in this set. This
+	/// scans the words linearly, so use a `HierarchicalIndexSet` when this is a hot loop.
+	pub fn next_unset(&self, from: usize) -> Option<usize> {
+		Self::find_next(&self.words, from, !0).filter(|index| *index < self.capacity)
+	}
+
+	fn update_len(&mut self) {
+		self.len = self.words.iter().map(|word| word.count_ones() as usize).sum();
+	}
+
+	/// Iterates over the indices in this set, in increasing order
+	pub fn iter(&self) -> DenseIndexSetIterator<'_> {
+		DenseIndexSetIterator {
+			words: &self.words,
+			word_index: 0,
+			remaining_bits: self.words.first().copied().unwrap_or(0),
+		}
+	}
+}
+
+/// A `DenseIndexSet` with a summary of its words, which speeds up `next_set` and `next_unset` when
+/// most words are empty or full: it only needs to scan 1 summary bit per word, so it skips 4096
+/// indices per summary word. This is useful for finding the next job that hasn't been dispatched
+/// yet, when most jobs with a small index have already been dispatched.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HierarchicalIndexSet {
+	set: DenseIndexSet,
+
+	/// Contains word index `w` if and only if `set.words[w]` is not 0
+	non_empty_words: DenseIndexSet,
+
+	/// Contains word index `w` if and only if all bits of `set.words[w]` are set
+	full_words: DenseIndexSet,
+}
+
+impl HierarchicalIndexSet {
+	/// Creates an empty set that can hold the indices `0 .. capacity`
+	pub fn new(capacity: usize) -> Self {
+		let set = DenseIndexSet::new(capacity);
+		let num_words = set.words.len();
+		Self { set, non_empty_words: DenseIndexSet::new(num_words), full_words: DenseIndexSet::new(num_words) }
+	}
+
+	/// Adds `index` to this set, and returns `true` if it wasn't in this set yet
+	pub fn insert(&mut self, index: usize) -> bool {
+		let is_new = self.set.insert(index);
+		let word_index = index / BITS_PER_WORD;
+		self.non_empty_words.insert(word_index);
+		if self.set.words[word_index] == !0 {
+			self.full_words.insert(word_index);
+		}
+		is_new
+	}
+
+	/// Removes `index` from this set, and returns `true` if it was in this set
+	pub fn remove(&mut self, index: usize) -> bool {
+		let was_present = self.set.remove(index);
+		let word_index = index / BITS_PER_WORD;
+		self.full_words.remove(word_index);
+		if self.set.words[word_index] == 0 {
+			self.non_empty_words.remove(word_index);
+		}
+		was_present
+	}
+
+	pub fn contains(&self, index: usize) -> bool {
+		self.set.contains(index)
+	}
+
+	/// Gets the number of indices in this set
+	pub fn len(&self) -> usize {
+		self.set.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.set.is_empty()
+	}
+
+	/// Finds the smallest index `i >= from` that is in this set
+	pub fn next_set(&self, from: usize) -> Option<usize> {
+		let word_index = from / BITS_PER_WORD;
+		if word_index >= self.set.words.len() {
+			return None;
+		}
+		let bits = self.set.words[word_index] & (!0u64 << (from % BITS_PER_WORD));
+		if bits != 0 {
+			return Some(word_index * BITS_PER_WORD + bits.trailing_zeros() as usize);
+		}
+		let next_word = self.non_empty_words.next_set(word_index + 1)?;
+		Some(next_word * BITS_PER_WORD + self.set.words[next_word].trailing_zeros() as usize)
+	}
+
+	/// Finds the smallest index `i >= from` (and `i < capacity`) that is *not* in this set
+	pub fn next_unset(&self, from: usize) -> Option<usize> {
+		let word_index = from / BITS_PER_WORD;
+		if word_index >= self.set.words.len() {
+			return None;
+		}
+		let bits = !self.set.words[word_index] & (!0u64 << (from % BITS_PER_WORD));
+		let index = if bits != 0 {
+			word_index * BITS_PER_WORD + bits.trailing_zeros() as usize
+		} else {
+			let next_word = self.full_words.next_unset(word_index + 1)?;
+			next_word * BITS_PER_WORD + (!self.set.words[next_word]).trailing_zeros() as usize
+		};
+		if index < self.set.capacity { Some(index) } else { None }
+	}
+
+	/// Gets the underlying `DenseIndexSet`
+	pub fn get_set(&self) -> &DenseIndexSet {
+		&self.set
+	}
+}
+
+/// A set of indices in the range `0 .. capacity`, which stores its members in an array, and the
+/// position of each index in that array. Unlike a `DenseIndexSet`, clearing takes constant time and
+/// iterating takes time proportional to `len` (rather than `capacity`), which makes it better for
+/// sets that contain only a tiny fraction of the possible indices. The indices are iterated in an
+/// unspecified order.
+#[derive(Debug, Clone)]
+pub struct SparseIndexSet {
+	members: Vec<usize>,
+
+	/// When `i` is in this set, `members[positions[i]] == i`. Otherwise, `positions[i]` is garbage.
+	positions: Vec<usize>,
+}
+
+impl SparseIndexSet {
+	/// Creates an empty set that can hold the indices `0 .. capacity`
+	pub fn new(capacity: usize) -> Self {
+		Self { members: Vec::new(), positions: vec![0; capacity] }
+	}
+
+	/// Adds `index` to this set, and returns `true` if it wasn't in this set yet
+	pub fn insert(&mut self, index: usize) -> bool {
+		if self.contains(index) {
+			return false;
+		}
+		self.positions[index] = self.members.len();
+		self.members.push(index);
+		true
+	}
+
+	/// Removes `index` from this set, and returns `true` if it was in this set
+	pub fn remove(&mut self, index: usize) -> bool {
+		if !self.contains(index) {
+			return false;
+		}
+		let position = self.positions[index];
+		let last = *self.members.last().unwrap();
+		self.members.swap_remove(position);
+		self.positions[last] = position;
+		true
+	}
+
+	pub fn contains(&self, index: usize) -> bool {
+		let position = self.positions[index];
+		position < self.members.len() && self.members[position] == index
+	}
+
+	/// Gets the number of indices in this set
+	pub fn len(&self) -> usize {
+		self.members.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.members.is_empty()
+	}
+
+	/// Gets the number of indices that this set can hold, which is independent of `len`
+	pub fn capacity(&self) -> usize {
+		self.positions.len()
+	}
+
+	/// Removes all indices from this set, in constant time
+	pub fn clear(&mut self) {
+		self.members.clear();
+	}
+
+	/// Iterates over the indices in this set, in an unspecified order
+	pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		self.members.iter().copied()
+	}
+}
+
+/// A set of indices in the range `0 .. capacity` that starts as a `SparseIndexSet`, and switches to
+/// a `DenseIndexSet` once it contains more than 1 index per 64 possible indices. From that point,
+/// the `DenseIndexSet` is both smaller and faster to iterate over. This is convenient for sets
+/// whose size is not known in advance, but that are usually small.
+#[derive(Debug, Clone)]
+pub enum AdaptiveIndexSet {
+	Sparse(SparseIndexSet),
+	Dense(DenseIndexSet),
+}
+
+impl AdaptiveIndexSet {
+	/// Creates an empty (sparse) set that can hold the indices `0 .. capacity`
+	pub fn new(capacity: usize) -> Self {
+		Self::Sparse(SparseIndexSet::new(capacity))
+	}
+
+	/// Adds `index` to this set, and returns `true` if it wasn't in this set yet
+	pub fn insert(&mut self, index: usize) -> bool {
+		match self {
+			Self::Sparse(sparse) => {
+				let is_new = sparse.insert(index);
+				if sparse.len() > sparse.capacity() / BITS_PER_WORD {
+					let mut dense = DenseIndexSet::new(sparse.capacity());
+					for member in sparse.iter() {
+						dense.insert(member);
+					}
+					*self = Self::Dense(dense);
+				}
+				is_new
+			},
+			Self::Dense(dense) => dense.insert(index),
+		}
+	}
+
+	/// Removes `index` from this set, and returns `true` if it was in this set
+	pub fn remove(&mut self, index: usize) -> bool {
+		match self {
+			Self::Sparse(sparse) => sparse.remove(index),
+			Self::Dense(dense) => dense.remove(index),
+		}
+	}
+
+	pub fn contains(&self, index: usize) -> bool {
+		match self {
+			Self::Sparse(sparse) => sparse.contains(index),
+			Self::Dense(dense) => dense.contains(index),
+		}
+	}
+
+	/// Gets the number of indices in this set
+	pub fn len(&self) -> usize {
+		match self {
+			Self::Sparse(sparse) => sparse.len(),
+			Self::Dense(dense) => dense.len(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Removes all indices from this set, without changing its representation
+	pub fn clear(&mut self) {
+		match self {
+			Self::Sparse(sparse) => sparse.clear(),
+			Self::Dense(dense) => dense.clear(),
+		}
+	}
+
+	/// Iterates over the indices in this set, in an unspecified order
+	pub fn iter(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+		match self {
+			Self::Sparse(sparse) => Box::new(sparse.iter()),
+			Self::Dense(dense) => Box::new(dense.iter()),
+		}
+	}
+}
+
+pub struct DenseIndexSetIterator<'a> {
+	words: &'a [u64],
+	word_index: usize,
+
+	/// The bits of `words[word_index]` that haven't been returned yet
+	remaining_bits: u64,
+}
+
+impl Iterator for DenseIndexSetIterator<'_> {
+	type Item = usize;
+
+	fn next(&mut self) -> Option<usize> {
+		while self.remaining_bits == 0 {
+			self.word_index += 1;
+			if self.word_index >= self.words.len() {
+				return None;
+			}
+			self.remaining_bits = self.words[self.word_index];
+		}
+
+		let bit = self.remaining_bits.trailing_zeros() as usize;
+		self.remaining_bits &= self.remaining_bits - 1;
+		Some(self.word_index * BITS_PER_WORD + bit)
+	}
+}
+
+impl<'a> IntoIterator for &'a DenseIndexSet {
+	type Item = usize;
+	type IntoIter = DenseIndexSetIterator<'a>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::index_set::*;
+
+	#[test]
+	fn test_insert_remove_contains() {
+		let mut set = DenseIndexSet::new(200);
+		assert!(set.is_empty());
+		assert!(set.insert(3));
+		assert!(set.insert(64));
+		assert!(set.insert(199));
+		assert!(!set.insert(64));
+		assert_eq!(3, set.len());
+		assert!(set.contains(3));
+		assert!(set.contains(64));
+		assert!(!set.contains(63));
+		assert!(!set.contains(65));
+
+		assert!(set.remove(64));
+		assert!(!set.remove(64));
+		assert!(!set.contains(64));
+		assert_eq!(2, set.len());
+
+		set.clear();
+		assert!(set.is_empty());
+		assert!(!set.contains(3));
+		assert_eq!(None, set.iter().next());
+	}
+
+	#[test]
+	fn test_iterator() {
+		let mut set = DenseIndexSet::new(300);
+		let indices = vec![0, 1, 63, 64, 127, 250, 299];
+		for &index in &indices {
+			set.insert(index);
+		}
+		assert_eq!(indices, set.iter().collect::<Vec<_>>());
+		assert_eq!(0, DenseIndexSet::new(0).iter().count());
+	}
+
+	#[test]
+	fn test_union_and_intersect() {
+		let mut first = DenseIndexSet::new(100);
+		let mut second = DenseIndexSet::new(100);
+		for index in [1, 5, 70] {
+			first.insert(index);
+		}
+		for index in [5, 70, 99] {
+			second.insert(index);
+		}
+
+		let mut union = first.clone();
+		union.union(&second);
+		assert_eq!(vec![1, 5, 70, 99], union.iter().collect::<Vec<_>>());
+		assert_eq!(4, union.len());
+
+		first.intersect(&second);
+		assert_eq!(vec![5, 70], first.iter().collect::<Vec<_>>());
+		assert_eq!(2, first.len());
+	}
+
+	#[test]
+	fn test_next_set_and_unset() {
+		let mut set = DenseIndexSet::new(130);
+		assert_eq!(None, set.next_set(0));
+		assert_eq!(Some(0), set.next_unset(0));
+		assert_eq!(Some(129), set.next_unset(129));
+		assert_eq!(None, set.next_unset(130));
+
+		for index in 0 .. 128 {
+			set.insert(index);
+		}
+		assert_eq!(Some(5), set.next_set(5));
+		assert_eq!(None, set.next_set(128));
+		assert_eq!(Some(128), set.next_unset(3));
+		set.insert(129);
+		assert_eq!(Some(128), set.next_unset(3));
+		set.insert(128);
+		assert_eq!(None, set.next_unset(3));
+	}
+
+	#[test]
+	fn test_hierarchical_index_set() {
+		let capacity = 10_000;
+		let mut set = HierarchicalIndexSet::new(capacity);
+		let mut reference = DenseIndexSet::new(capacity);
+		for index in (0 .. 9000).chain([9100, 9101, 9999]) {
+			assert!(set.insert(index));
+			reference.insert(index);
+		}
+		set.remove(4321);
+		reference.remove(4321);
+		set.remove(9101);
+		reference.remove(9101);
+
+		assert_eq!(&reference, set.get_set());
+		assert_eq!(reference.len(), set.len());
+		for from in (0 .. capacity + 100).step_by(7) {
+			assert_eq!(reference.next_set(from), set.next_set(from), "from = {}", from);
+			assert_eq!(reference.next_unset(from), set.next_unset(from), "from = {}", from);
+		}
+		assert_eq!(Some(4321), set.next_unset(0));
+		assert_eq!(Some(9000), set.next_unset(4322));
+		assert_eq!(Some(9100), set.next_set(9000));
+
+		let mut full = HierarchicalIndexSet::new(128);
+		for index in 0 .. 128 {
+			full.insert(index);
+		}
+		assert_eq!(None, full.next_unset(0));
+		assert!(full.contains(127));
+	}
+
+	#[test]
+	fn test_sparse_index_set() {
+		let mut set = SparseIndexSet::new(1000);
+		assert!(set.insert(500));
+		assert!(set.insert(3));
+		assert!(set.insert(999));
+		assert!(!set.insert(3));
+		assert_eq!(3, set.len());
+		assert_eq!(vec![500, 3, 999], set.iter().collect::<Vec<_>>());
+
+		assert!(set.remove(500));
+		assert!(!set.remove(500));
+		assert!(!set.contains(500));
+		assert!(set.contains(3));
+		assert!(set.contains(999));
+		assert_eq!(2, set.len());
+
+		set.clear();
+		assert!(set.is_empty());
+		assert!(!set.contains(3));
+		assert!(set.insert(999));
+		assert!(!set.contains(3));
+	}
+
+	#[test]
+	fn test_adaptive_index_set() {
+		let mut set = AdaptiveIndexSet::new(640);
+		for index in 0 .. 10 {
+			assert!(set.insert(index * 3));
+		}
+		assert!(matches!(set, AdaptiveIndexSet::Sparse(_)));
+		assert!(set.insert(1));
+		assert!(matches!(set, AdaptiveIndexSet::Dense(_)));
+		assert!(!set.insert(1));
+		assert_eq!(11, set.len());
+		assert!(set.contains(27));
+		assert!(!set.contains(28));
+
+		let mut members: Vec<usize> = set.iter().collect();
+		members.sort();
+		assert_eq!(vec![0, 1, 3, 6, 9, 12, 15, 18, 21, 24, 27], members);
+
+		assert!(set.remove(27));
+		set.clear();
+		assert!(set.is_empty());
+	}
+}
@@ -0,0 +1,226 @@
+use crate::problem::*;
+
+/// A subset of the jobs and constraints of a problem that is infeasible on its own
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfeasibleSubset {
+	/// The indices of the jobs in the subset, in increasing order
+	pub jobs: Vec<usize>,
+
+	/// The indices of the constraints in the subset, in increasing order
+	pub constraints: Vec<usize>,
+}
+
+/// Removes as many of the elements for which `keep[i]` is `true` as possible, such that
+/// `is_infeasible` still returns `true`.
+///
+/// It starts by binary-searching the shortest prefix of the kept elements that is still
+/// infeasible, which quickly removes the irrelevant tail of large problems. Then, it tries to remove
+/// chunks of the remaining elements, and keeps every chunk removed for which `is_infeasible` still
+/// returns `true`. The chunk size starts at half of the number of kept elements, and is halved
+/// until it is 1.
+fn shrink(keep: &mut [bool], mut is_infeasible: impl FnMut(&[bool]) -> bool) {
+	let kept_elements: Vec<usize> = (0 .. keep.len()).filter(|index| keep[*index]).collect();
+	let mut min_prefix_length = 0;
+	let mut max_prefix_length = kept_elements.len();
+	while min_prefix_length < max_prefix_length {
+		let prefix_length = (min_prefix_length + max_prefix_length) / 2;
+		for (position, &index) in kept_elements.iter().enumerate() {
+			keep[index] = position < prefix_length;
+		}
+		if is_infeasible(keep) {
+			max_prefix_length = prefix_length;
+		} else {
+			min_prefix_length = prefix_length + 1;
+		}
+	}
+	for (position, &index) in kept_elements.iter().enumerate() {
+		keep[index] = position < max_prefix_length;
+	}
+
+	let mut chunk_size = max_prefix_length / 2;
+	loop {
+		let chunk_size_now = usize::max(1, chunk_size);
+		let kept_elements: Vec<usize> = (0 .. keep.len()).filter(|index| keep[*index]).collect();
+		for chunk in kept_elements.chunks(chunk_size_now) {
+			for &index in chunk {
+				keep[index] = false;
+			}
+			if !is_infeasible(keep) {
+				for &index in chunk {
+					keep[index] = true;
+				}
+			}
+		}
+		if chunk_size_now == 1 {
+			break;
+		}
+		chunk_size /= 2;
+	}
+}
+
+/// Computes an approximately minimal subset of the jobs and constraints of `problem` for which
+/// `is_infeasible` returns `true`, using deletion-based shrinking: it first removes as many jobs as
+/// possible, and then as many of the remaining constraints as possible. The result is minimal in
+/// the sense that removing any single job or constraint would make `is_infeasible` return `false`,
+/// provided that `is_infeasible` is monotone.
+///
+/// `is_infeasible` should be cheap, since it is called many times. Typically, it is
+/// `is_proven_infeasible`. It must return `true` for `problem` itself.
+pub fn find_infeasible_subset(
+	problem: &Problem, mut is_infeasible: impl FnMut(&Problem) -> bool
+) -> InfeasibleSubset {
+	let mut keep_jobs = vec![true; problem.jobs.len()];
+	let mut keep_constraints = vec![true; problem.constraints.len()];
+	assert!(is_infeasible(problem), "The problem must be infeasible");
+
+	shrink(&mut keep_jobs, |keep_jobs| is_infeasible(&problem.get_subproblem(keep_jobs, &keep_constraints)));
+	for (keep, constraint) in keep_constraints.iter_mut().zip(&problem.constraints) {
+		*keep = keep_jobs[constraint.get_before()] && keep_jobs[constraint.get_after()];
+	}
+	shrink(&mut keep_constraints, |keep_constraints| is_infeasible(&problem.get_subproblem(&keep_jobs, keep_constraints)));
+
+	InfeasibleSubset {
+		jobs: (0 .. problem.jobs.len()).filter(|job| keep_jobs[*job]).collect(),
+		constraints: (0 .. problem.constraints.len()).filter(|index| keep_constraints[*index]).collect(),
+	}
+}
+
+/// Minimizes a counterexample: a problem for which `has_discrepancy` returns `true`, for instance
+/// because a necessary test claims that it is infeasible, while a scheduling policy finds a
+/// feasible schedule. While the discrepancy persists, it:
+/// 1. removes as many jobs and constraints as possible (see `find_infeasible_subset`),
+/// 2. removes as many cores as possible,
+/// 3. reduces the execution time of each job as much as possible, while keeping its latest finish
+///    time, and
+/// 4. divides all times by their greatest common divisor.
+///
+/// Since `has_discrepancy` is generally not monotone, the result is not necessarily minimal, but
+/// it is usually much easier to debug. `has_discrepancy` must return `true` for `problem` itself.
+pub fn minimize_counterexample(
+	problem: &Problem, mut has_discrepancy: impl FnMut(&Problem) -> bool
+) -> Problem {
+	let subset = find_infeasible_subset(problem, &mut has_discrepancy);
+	let mut keep_jobs = vec![false; problem.jobs.len()];
+	for job in subset.jobs {
+		keep_jobs[job] = true;
+	}
+	let mut keep_constraints = vec![false; problem.constraints.len()];
+	for constraint in subset.constraints {
+		keep_constraints[constraint] = true;
+	}
+	let mut minimized = problem.get_subproblem(&keep_jobs, &keep_constraints);
+
+	while minimized.num_cores > 1 {
+		let fewer_cores = Problem { num_cores: minimized.num_cores - 1, ..minimized.clone() };
+		if !has_discrepancy(&fewer_cores) {
+			break;
+		}
+		minimized = fewer_cores;
+	}
+
+	for index in 0 .. minimized.jobs.len() {
+		let mut reduction = minimized.jobs[index].get_execution_time() / 2;
+		while reduction > 0 {
+			let mut shorter = minimized.clone();
+			let execution_time = shorter.jobs[index].get_execution_time();
+			shorter.jobs[index].set_execution_time(execution_time - reduction);
+			if has_discrepancy(&shorter) {
+				minimized = shorter;
+				reduction = Time::min(reduction, (execution_time - reduction) / 2);
+			} else {
+				reduction /= 2;
+			}
+		}
+	}
+
+	let mut divided = minimized.clone();
+	divided.divide_times(minimized.get_time_gcd());
+	if has_discrepancy(&divided) { divided } else { minimized }
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::infeasible_subset::{find_infeasible_subset, minimize_counterexample};
+	use crate::necessary::is_proven_infeasible;
+	use crate::problem::*;
+
+	#[test]
+	fn test_find_infeasible_subset() {
+		let mut jobs = Vec::new();
+		let mut constraints = Vec::new();
+		for index in 0 .. 20 {
+			jobs.push(Job::release_to_deadline(index, 0, 10, 1000));
+			if index > 0 && index < 5 {
+				constraints.push(Constraint::new(index - 1, index, 0, ConstraintType::FinishToStart));
+			}
+		}
+
+		// Jobs 5 and 12 can't both run during [20, 30) on a single core
+		jobs[5] = Job::release_to_deadline(5, 20, 10, 30);
+		jobs[12] = Job::release_to_deadline(12, 20, 10, 30);
+
+		let problem = Problem { jobs, constraints, num_cores: 1, resources: Vec::new() };
+		problem.validate();
+
+		let subset = find_infeasible_subset(&problem, is_proven_infeasible);
+		assert_eq!(vec![5, 12], subset.jobs);
+		assert!(subset.constraints.is_empty());
+	}
+
+	#[test]
+	fn test_find_infeasible_subset_with_constraints() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 15),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![
+				Constraint::new(2, 0, 0, ConstraintType::StartToStart),
+				Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+			],
+			num_cores: 3,
+			resources: Vec::new(),
+		};
+
+		let subset = find_infeasible_subset(&problem, is_proven_infeasible);
+		assert_eq!(vec![0, 1], subset.jobs);
+		assert_eq!(vec![1], subset.constraints);
+	}
+
+	#[test]
+	fn test_minimize_counterexample() {
+		let mut jobs = Vec::new();
+		for index in 0 .. 10 {
+			jobs.push(Job::release_to_deadline(index, 0, 40, 1000));
+		}
+		jobs[3] = Job::release_to_deadline(3, 100, 80, 300);
+		jobs[7] = Job::release_to_deadline(7, 100, 60, 300);
+		let problem = Problem {
+			jobs,
+			constraints: vec![Constraint::new(3, 7, 20, ConstraintType::FinishToStart)],
+			num_cores: 3,
+			resources: Vec::new(),
+		};
+
+		// A fake discrepancy that persists as long as there are at least 2 cores, and a constraint
+		// whose jobs need at least 30 and 20 time units
+		let has_discrepancy = |problem: &Problem| problem.num_cores >= 2 && problem.constraints.iter().any(
+			|constraint| problem.jobs[constraint.get_before()].get_execution_time() >= 30 &&
+				problem.jobs[constraint.get_after()].get_execution_time() >= 20
+		);
+		assert!(has_discrepancy(&problem));
+
+		let minimized = minimize_counterexample(&problem, has_discrepancy);
+		assert!(has_discrepancy(&minimized));
+		assert_eq!(2, minimized.jobs.len());
+		assert_eq!(2, minimized.num_cores);
+		assert_eq!(1, minimized.constraints.len());
+		assert_eq!(30, minimized.jobs[0].get_execution_time());
+		assert_eq!(20, minimized.jobs[1].get_execution_time());
+		assert_eq!(300, minimized.jobs[1].get_latest_finish());
+
+		// Dividing all times by 10 would remove the discrepancy
+		assert_eq!(100, minimized.jobs[1].earliest_start);
+	}
+}
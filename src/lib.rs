@@ -0,0 +1,28 @@
+pub mod batch;
+pub mod bounds;
+pub mod certificate;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod distributed;
+pub mod exact;
+pub mod export;
+pub mod fingerprint;
+pub mod generator;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod index_set;
+pub mod infeasible_subset;
+pub mod necessary;
+pub mod parser;
+pub mod permutation;
+pub mod problem;
+pub mod regression;
+pub mod repl;
+pub mod schedule;
+pub mod segments;
+pub mod sensitivity;
+pub mod simulator;
+pub mod stats;
+pub mod warm_start;
+pub mod what_if;
+mod sorted_job_iterator;
@@ -1,38 +1,877 @@
-mod bounds;
 mod cli;
-mod necessary;
-mod parser;
-mod permutation;
-mod problem;
-mod simulator;
-mod sorted_job_iterator;
-
-use bounds::*;
+
 use clap::Parser;
-use cli::Args;
-use parser::parse_problem;
-use permutation::ProblemPermutation;
-use necessary::*;
+use cli::{Args, Command, ExactMethod, GenerateArgs, OptimizationGoal, Policy};
+use np_feasibility::bounds::*;
+use np_feasibility::certificate::*;
+use np_feasibility::distributed::{publish_work, run_worker, wait_for_verdict};
+use np_feasibility::exact::*;
+use np_feasibility::export::{
+	write_cheddar_model, write_chrome_trace, write_deadline_miss_heatmap, write_dispatch_table, write_event_trace,
+	write_infeasibility_certificate, write_job_features, write_mast_model, write_problem, write_sag_schedule,
+	write_schedule_certificate
+};
+use np_feasibility::fingerprint::compute_fingerprint;
+use np_feasibility::generator::{generate_problem, generate_task_set, GeneratorConfig, TaskSetConfig};
+use np_feasibility::infeasible_subset::{find_infeasible_subset, minimize_counterexample};
+use np_feasibility::necessary::*;
+use np_feasibility::parser::{
+	parse_certificate, parse_dispatch_order, parse_job_ordering, parse_problem_with_criticalities,
+	parse_segments
+};
+use np_feasibility::permutation::{ProblemPermutation, ReadyJobOrder};
+use np_feasibility::problem::{Constraint, ConstraintType, Criticality, Problem, Time};
+use np_feasibility::regression::{check_regression, parse_regression_corpus, record_regression, RegressionKind};
+use np_feasibility::repl::run_repl;
+use np_feasibility::schedule::Schedule;
+use np_feasibility::segments::{split_into_segments, Segmentation};
+use np_feasibility::sensitivity::find_maximum_inflation;
+use np_feasibility::simulator::{
+	estimate_feasibility, locate_deadline_misses, simulate_earliest_deadline_first, simulate_fixed_priority,
+	verify_dispatch_order, DispatchViolation, Simulator
+};
+use np_feasibility::stats::{NarrowingReport, ProblemStats, Verdict};
+use np_feasibility::warm_start::{load_analysis_state, save_analysis_state, AnalysisState};
+use std::panic::{set_hook, take_hook};
+use std::process::exit;
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+
+fn solve_by_enumeration_with_prefix(problem: &Problem, prefix: &[usize], objective: Objective) -> ExactResult {
+	solve_by_enumeration_with_checkpoints(problem, prefix, objective, None, Duration::MAX, |_| {})
+}
+
+fn solve_exactly(problem: &Problem, method: ExactMethod, prefix: &[usize], objective: Objective) -> ExactResult {
+	match method {
+		#[cfg(feature = "milp")]
+		ExactMethod::Milp => solve_using_milp(problem, prefix, objective).unwrap_or_else(|error| {
+			eprintln!("{}", error);
+			exit(1);
+		}),
+		ExactMethod::Enumeration => solve_by_enumeration_with_prefix(problem, prefix, objective),
+	}
+}
+
+/// Like `solve_exactly`, but an enumeration saves its progress to the `--checkpoint` of `args`, and
+/// continues from its `--resume` checkpoint when that was saved for the same objective
+fn solve_with_checkpoints(
+	args: &Args, problem: &Problem, method: ExactMethod, prefix: &[usize], objective: Objective
+) -> ExactResult {
+	if let Some(directory) = &args.distribute {
+		let num_items = publish_work(problem, args.distribute_depth, directory);
+		println!("Distributed the search over {} work items in {}", num_items, directory);
+		run_worker(directory);
+		return wait_for_verdict(problem, directory, num_items);
+	}
+	if method != ExactMethod::Enumeration || (args.checkpoint.is_none() && args.resume.is_none()) {
+		return solve_exactly(problem, method, prefix, objective);
+	}
+
+	let resume = args.resume.as_deref().map(load_enumeration_checkpoint).filter(
+		|checkpoint| checkpoint.objective == objective
+	);
+	if let Some(checkpoint) = &resume {
+		println!("Resuming the search after {} visited nodes", checkpoint.num_visited_nodes);
+	}
+	let interval = Duration::from_secs(args.checkpoint_interval);
+	solve_by_enumeration_with_checkpoints(problem, prefix, objective, resume.as_ref(), interval, |checkpoint| {
+		if let Some(checkpoint_file) = &args.checkpoint {
+			save_enumeration_checkpoint(checkpoint, checkpoint_file);
+		}
+	})
+}
+
+#[cfg(feature = "daemon")]
+fn run_daemon() {
+	np_feasibility::daemon::run_daemon(std::io::stdin().lock(), std::io::stdout().lock());
+}
+
+#[cfg(feature = "http")]
+fn serve(address: &str) {
+	println!("Listening on http://{}", address);
+	np_feasibility::http::serve(address);
+}
+
+fn simulate_policy(problem: &Problem, policy: Policy) -> Simulator {
+	match policy {
+		Policy::Edf => simulate_earliest_deadline_first(problem),
+		Policy::FixedPriority => {
+			let priorities: Vec<u32> = problem.jobs.iter().map(|job| job.get_priority()).collect();
+			simulate_fixed_priority(problem, &priorities)
+		},
+	}
+}
+
+/// Simulates `policy` on `problem`, and returns the resulting schedule if it meets all deadlines
+fn schedule_using_policy(problem: &Problem, policy: Policy) -> Option<Schedule> {
+	let simulator = simulate_policy(problem, policy);
+	if simulator.has_missed_deadline() {
+		println!("The scheduling policy missed a deadline");
+		return None;
+	}
+	simulator.get_schedule().cloned()
+}
+
+/// Simulates `num_samples` random dispatch orders on `problem`, prints how many of them met all
+/// deadlines, and returns a feasible schedule if any of them did. The lateness is multiplied by
+/// `time_scale` to undo the normalization. When some of them missed a deadline, their deadline
+/// misses are written to the `--miss-heatmap` of `args` (if any), in terms of the jobs before
+/// `permutation` was applied to `problem`.
+fn estimate_using_samples(
+	args: &Args, problem: &Problem, permutation: &ProblemPermutation, num_samples: usize, time_scale: Time
+) -> Option<Schedule> {
+	let estimate = estimate_feasibility(problem, num_samples, args.seed);
+	println!(
+		"{} of the {} random dispatch orders ({:.1}%) met all deadlines, and the smallest lateness is {}",
+		estimate.num_feasible_samples, estimate.num_samples, 100.0 * estimate.get_feasible_fraction(),
+		estimate.minimum_lateness * time_scale
+	);
+	if let Some(heatmap_file) = &args.miss_heatmap && estimate.num_feasible_samples < estimate.num_samples {
+		let mut heatmap = locate_deadline_misses(problem, num_samples, args.seed, args.heatmap_regions);
+		heatmap.misses = permutation.unapply_to_jobs(&heatmap.misses);
+		heatmap.blocking = permutation.unapply_to_jobs(&heatmap.blocking);
+		heatmap.region_length *= time_scale;
+		if let Some(job) = (0 .. problem.jobs.len()).max_by_key(|job| heatmap.get_num_misses(*job)) {
+			println!(
+				"Job {} missed its deadline in {} of the {} random dispatch orders that missed a deadline",
+				job, heatmap.get_num_misses(job), heatmap.num_failed_samples
+			);
+		}
+		write_deadline_miss_heatmap(&heatmap, heatmap_file);
+	}
+	(estimate.num_feasible_samples > 0).then_some(estimate.best_schedule)
+}
+
+/// Simulates the `--policy` of `args` (or EDF when it has no policy) and its random dispatch orders
+/// of `--samples` on `problem` without stopping at deadline misses, and reports the lateness of the
+/// jobs in the schedule with the smallest total tardiness. When `problem` was split into segments,
+/// the lateness is reported for the jobs of the original problem of `segmentation`.
+fn report_tardiness(args: &Args, problem: &Problem, segmentation: Option<&Segmentation>) {
+	let mut schedule = simulate_policy(problem, args.policy.unwrap_or(Policy::Edf)).get_schedule().unwrap().clone();
+	if let Some(num_samples) = args.samples {
+		let estimate = estimate_feasibility(problem, num_samples, args.seed);
+		if estimate.best_schedule.get_total_tardiness(problem) < schedule.get_total_tardiness(problem) {
+			schedule = estimate.best_schedule;
+		}
+	}
+	let (problem, schedule) = match segmentation {
+		Some(segmentation) => (&segmentation.original_problem, segmentation.merge_schedule(&schedule)),
+		None => (problem, schedule),
+	};
+
+	println!(
+		"The best dispatch order that was found has a total tardiness of {} and a maximum tardiness of {}",
+		schedule.get_total_tardiness(problem), schedule.get_maximum_tardiness(problem)
+	);
+	for (job, lateness) in schedule.get_lateness(problem).into_iter().enumerate() {
+		if let Some(lateness) = lateness && lateness > 0 {
+			println!("Job {} finishes {} time units after its deadline", job, lateness);
+		}
+	}
+}
+
+/// Checks whether the policy and exact method of `args` (if any) find a feasible schedule for
+/// `problem`
+fn is_solved(args: &Args, problem: &Problem) -> bool {
+	if args.policy.is_some_and(|policy| simulate_policy(problem, policy).has_missed_deadline()) {
+		return false;
+	}
+	args.exact.is_none_or(|method| matches!(
+		solve_exactly(problem, method, &[], Objective::EarlyStartTimes), ExactResult::Feasible(_)
+	))
+}
+
+/// Prints the largest percentage by which the execution time of `job` (or each job, and all jobs
+/// together) can be inflated, such that `problem` can't be proven infeasible, and such that the
+/// policy or exact method of `args` (if any) still finds a schedule
+fn analyze_sensitivity(args: &Args, problem: &Problem, job: Option<usize>) {
+	let is_acceptable = |problem: &Problem| !is_proven_infeasible(problem) && is_solved(args, problem);
+
+	let mut job_sets: Vec<(String, Vec<usize>)> = match job {
+		Some(job) => vec![(format!("Job {}", job), vec![job])],
+		None => (0 .. problem.jobs.len()).map(|job| (format!("Job {}", job), vec![job])).collect(),
+	};
+	if job.is_none() && problem.jobs.len() > 1 {
+		job_sets.push((String::from("All jobs together"), (0 .. problem.jobs.len()).collect()));
+	}
+	for (description, jobs) in job_sets {
+		match find_maximum_inflation(problem, &jobs, is_acceptable) {
+			Some(percentage) => println!("{} can tolerate an execution time of {}%", description, percentage),
+			None => {
+				println!("The problem itself is not accepted, so no inflation is possible");
+				return;
+			},
+		}
+	}
+}
+
+/// Checks whether the necessary tests prove that `problem` is infeasible while the policy or exact
+/// method of `args` finds a schedule. If so, writes a minimized version of `problem` for which this
+/// is still the case to `<output>.csv` and `<output>.prec.csv`.
+fn minimize(args: &Args, problem: &Problem, output: &str) {
+	assert!(args.policy.is_some() || args.exact.is_some(), "--minimize needs --policy or --exact");
+	let has_discrepancy = |problem: &Problem| is_proven_infeasible(problem) && is_solved(args, problem);
+	if !has_discrepancy(problem) {
+		println!("The necessary tests don't contradict the policy or exact method, so there is nothing to minimize");
+		return;
+	}
+
+	let minimized = minimize_counterexample(problem, has_discrepancy);
+	if let Some(directory) = &args.record_regressions {
+		record_regression(&minimized, RegressionKind::Disagreement, directory);
+	}
+	let jobs_path = format!("{}.csv", output);
+	let precedence_path = format!("{}.prec.csv", output);
+	write_problem(&minimized, &jobs_path, &precedence_path);
+	println!(
+		"The necessary tests claim that this problem is infeasible, but a feasible schedule was found! \
+		Wrote a counterexample with {} jobs and {} constraints using {} cores to {} and {}",
+		minimized.jobs.len(), minimized.constraints.len(), minimized.num_cores, jobs_path, precedence_path
+	);
+}
+
+/// Prints a small subset of the jobs and constraints of `problem` that the necessary tests can
+/// still prove to be infeasible
+fn report_infeasible_subset(problem: &Problem) {
+	let mut profile = TestProfile::new();
+	let subset = find_infeasible_subset(
+		problem, |problem| strengthen_and_test_with_profile(&mut problem.clone(), &mut profile)
+	);
+	println!("The following {} jobs and {} constraints are already infeasible:", subset.jobs.len(), subset.constraints.len());
+	for &index in &subset.jobs {
+		let job = problem.jobs[index];
+		println!(
+			"Job {} with release time {}, execution time {}, and deadline {}",
+			index, job.earliest_start, job.get_execution_time(), job.get_deadline()
+		);
+	}
+	for &index in &subset.constraints {
+		println!("{}", describe_constraint(problem.constraints[index], 1));
+	}
+}
+
+/// Describes `constraint` in words, after multiplying its (maximum) delay by `time_scale`
+fn describe_constraint(constraint: Constraint, time_scale: Time) -> String {
+	let point = match constraint.get_type() {
+		ConstraintType::StartToStart => "starts",
+		ConstraintType::FinishToStart => "finishes",
+	};
+	let max_delay = constraint.get_max_delay().map(
+		|max_delay| format!(" and at most {}", max_delay * time_scale)
+	).unwrap_or_default();
+	format!(
+		"Job {} must start at least {}{} time units after job {} {}",
+		constraint.get_after(), constraint.get_delay() * time_scale, max_delay, constraint.get_before(), point
+	)
+}
+
+fn verify(args: &Args, problem: &Problem, dispatch_order_file: &str) {
+	let order = parse_dispatch_order(dispatch_order_file);
+	match verify_dispatch_order(problem, &order) {
+		Ok(schedule) => {
+			println!("VALID");
+			report_schedule(args, problem, &schedule, 1);
+		},
+		Err(DispatchViolation::InvalidOrder(position)) => println!(
+			"INVALID: job {} at position {} is duplicated, unknown, or dispatched before one of its predecessors",
+			order[position].job, position
+		),
+		Err(DispatchViolation::MissingJob(job)) => println!("INVALID: job {} is never dispatched", job),
+		Err(DispatchViolation::CannotStartAt { position, earliest_start }) => println!(
+			"INVALID: job {} at position {} can't start before time {}",
+			order[position].job, position, earliest_start
+		),
+		Err(DispatchViolation::MissesDeadline(position)) => println!(
+			"INVALID: job {} at position {} misses its deadline", order[position].job, position
+		),
+		Err(DispatchViolation::ExceedsMaxDelay(constraint)) => println!(
+			"INVALID: job {} starts more than {} time units after job {} {}",
+			constraint.get_after(), constraint.get_max_delay().unwrap(), constraint.get_before(),
+			if constraint.get_type() == ConstraintType::StartToStart { "starts" } else { "finishes" }
+		),
+	}
+}
+
+/// Checks whether the certificate in `certificate_file` proves that `problem` is feasible or
+/// infeasible
+fn check_certificate(problem: &Problem, certificate_file: &str) {
+	match parse_certificate(certificate_file) {
+		Certificate::Schedule(certificate) => check_feasibility_certificate(problem, &certificate),
+		Certificate::Infeasibility(certificate) => check_infeasibility_certificate(problem, &certificate),
+	}
+}
+
+fn check_feasibility_certificate(problem: &Problem, certificate: &[CertifiedStart]) {
+	match check_schedule_certificate(problem, certificate) {
+		Ok(()) => println!("VALID: the problem is FEASIBLE"),
+		Err(CertificateViolation::UnknownJob(position)) => println!("INVALID: line {} contains an unknown job", position),
+		Err(CertificateViolation::DuplicateJob(job)) => println!("INVALID: job {} appears more than once", job),
+		Err(CertificateViolation::MissingJob(job)) => println!("INVALID: job {} is missing", job),
+		Err(CertificateViolation::UnknownCore(job)) => println!("INVALID: job {} runs on a core that doesn't exist", job),
+		Err(CertificateViolation::ViolatesAffinity(job)) => println!("INVALID: job {} runs on a core outside its affinity", job),
+		Err(CertificateViolation::StartsBeforeRelease(job)) => println!("INVALID: job {} starts before its release", job),
+		Err(CertificateViolation::FinishesAfterDeadline(job)) => println!("INVALID: job {} misses its deadline", job),
+		Err(CertificateViolation::ViolatesConstraint(index)) => println!(
+			"INVALID: this constraint is violated: {}", describe_constraint(problem.constraints[index], 1)
+		),
+		Err(CertificateViolation::CoreOccupied { core, first, second }) => println!(
+			"INVALID: job {} starts on core {} while job {} is still running there", second, core, first
+		),
+		Err(CertificateViolation::ResourceOccupied { resource, first, second }) => println!(
+			"INVALID: job {} starts while job {} is still using shared resource {}", second, first, resource
+		),
+	}
+}
+
+fn check_infeasibility_certificate(problem: &Problem, certificate: &InfeasibilityCertificate) {
+	print!("{}", certificate);
+	match verify_infeasibility_certificate(problem, certificate) {
+		Ok(()) => println!("VALID: the problem is INFEASIBLE"),
+		Err(InfeasibilityCertificateError::UnknownJob(job)) => println!("INVALID: job {} doesn't exist", job),
+		Err(InfeasibilityCertificateError::DuplicateJob(job)) => println!("INVALID: job {} is counted more than once", job),
+		Err(InfeasibilityCertificateError::WrongJobValue(job)) => println!(
+			"INVALID: the numbers of job {} don't match the problem", job
+		),
+		Err(InfeasibilityCertificateError::MissingConstraint { before, after }) => println!(
+			"INVALID: there is no constraint from job {} to job {} with this separation", before, after
+		),
+		Err(InfeasibilityCertificateError::BrokenCycle) => println!("INVALID: the constraints don't form a cycle"),
+		Err(InfeasibilityCertificateError::EmptyInterval) => println!("INVALID: the interval ends before it starts"),
+		Err(InfeasibilityCertificateError::WrongCapacity) => println!("INVALID: the capacity of the interval is wrong"),
+		Err(InfeasibilityCertificateError::NoContradiction) => println!("INVALID: the numbers don't contradict each other"),
+	}
+}
+
+/// Writes an infeasibility certificate of `problem` if the user asked for it, and explains it.
+/// `problem` must not have been strengthened or normalized.
+fn report_infeasibility(args: &Args, problem: &Problem) {
+	if let Some(features_file) = &args.features {
+		write_job_features(problem, Verdict::Infeasible, None, features_file);
+	}
+	let Some(certificate_file) = &args.certificate else { return };
+	match find_infeasibility_certificate(problem) {
+		Some(certificate) => {
+			print!("{}", certificate);
+			write_infeasibility_certificate(&certificate, certificate_file);
+		},
+		None => println!(
+			"Couldn't find an infeasibility certificate, since the proof relies on strengthened bounds"
+		),
+	}
+}
+
+/// The verdict that is printed when a schedule was found. With `--hyperperiod`, the schedule only
+/// covers the unrolled hyperperiods, which doesn't prove that the steady state of the periodic
+/// tasks is feasible.
+fn feasible_verdict(args: &Args) -> String {
+	match args.hyperperiod {
+		Some(_) => format!("FEASIBLE for the first {} hyperperiods", args.num_hyperperiods),
+		None => String::from("FEASIBLE"),
+	}
+}
+
+/// Prints `schedule`, and writes its event trace, Chrome trace, dispatch table, SAG schedule, MAST
+/// model, certificate, and job features if the user asked for them. The start and finish times of `schedule` are
+/// multiplied by `time_scale` to undo the normalization. With `--hyperperiod`, no certificate is
+/// written, and the features are labelled as unknown, since the schedule doesn't prove feasibility.
+fn report_schedule(args: &Args, problem: &Problem, schedule: &Schedule, time_scale: Time) {
+	let mut schedule = schedule.clone();
+	schedule.multiply_times(time_scale);
+	let schedule = &schedule;
+
+	for scheduled in schedule.get_jobs() {
+		println!(
+			"Job {} starts at time {} on core {} with slack {}", scheduled.job, scheduled.start,
+			scheduled.core, scheduled.get_slack(problem)
+		);
+	}
+	println!(
+		"Makespan is {} and minimum slack is {}",
+		schedule.get_makespan(), schedule.get_minimum_slack(problem).unwrap_or(0)
+	);
+	if let Some(trace_file) = &args.trace {
+		write_event_trace(schedule, trace_file);
+	}
+	if let Some(chrome_trace_file) = &args.chrome_trace {
+		write_chrome_trace(problem, schedule, chrome_trace_file);
+	}
+	if let Some(table_file) = &args.dispatch_table {
+		write_dispatch_table(problem, schedule, table_file);
+	}
+	if let Some(sag_file) = &args.sag_schedule {
+		write_sag_schedule(problem, schedule, sag_file);
+	}
+	if let Some(mast_file) = &args.mast {
+		write_mast_model(problem, Some(schedule), mast_file);
+	}
+	if args.hyperperiod.is_some() {
+		if args.certificate.is_some() {
+			println!("Didn't write a schedule certificate, since the schedule only covers the unrolled hyperperiods");
+		}
+		if let Some(features_file) = &args.features {
+			write_job_features(problem, Verdict::Unknown, Some(schedule), features_file);
+		}
+	} else {
+		if let Some(certificate_file) = &args.certificate {
+			write_schedule_certificate(schedule, certificate_file);
+		}
+		if let Some(features_file) = &args.features {
+			write_job_features(problem, Verdict::Feasible, Some(schedule), features_file);
+		}
+	}
+}
 
 fn main() {
 	let args = Args::parse();
-	let mut problem = parse_problem(
-		&args.jobs_file, args.precedence_file.as_deref(), args.num_cores
+	match &args.command {
+		Some(Command::Generate(generate_args)) => {
+			generate(generate_args);
+			return;
+		},
+		Some(Command::Replay { corpus }) => {
+			replay(corpus);
+			return;
+		},
+		#[cfg(feature = "daemon")]
+		Some(Command::Daemon) => {
+			run_daemon();
+			return;
+		},
+		#[cfg(feature = "http")]
+		Some(Command::Serve { address }) => {
+			serve(address);
+			return;
+		},
+		Some(Command::Work { directory }) => {
+			let num_items = run_worker(directory);
+			println!("Searched {} work items", num_items);
+			return;
+		},
+		None => {},
+	}
+	if args.distribute.is_some() && args.exact != Some(ExactMethod::Enumeration) {
+		eprintln!("--distribute requires --exact enumeration");
+		exit(1);
+	}
+
+	let mut problem = parse_problem_with_criticalities(
+		args.jobs_file.as_deref().unwrap(), args.precedence_file.as_deref(), args.resources_file.as_deref(),
+		args.criticalities_file.as_deref(), args.num_cores.unwrap()
 	);
 	println!("Found {} jobs and {} constraints using {} cores", problem.jobs.len(), problem.constraints.len(), problem.num_cores);
+	if !problem.resources.is_empty() {
+		println!("Found {} shared resources", problem.resources.len());
+	}
+	if problem.is_mixed_criticality() {
+		println!("Found {} HI jobs", problem.get_mode_jobs(Criticality::Hi).len());
+	}
+	if args.stats {
+		println!("{}", ProblemStats::new(&problem));
+		println!("Fingerprint: {:016x}", compute_fingerprint(&problem));
+	}
+
+	if let Some(hyperperiod) = args.hyperperiod {
+		let num_crossing_jobs = problem.jobs.iter().filter(|job| job.get_latest_finish() > hyperperiod).count();
+		problem = problem.unroll_hyperperiods(hyperperiod, args.num_hyperperiods).unwrap_or_else(|error| {
+			eprintln!("{}", error);
+			exit(1);
+		});
+		println!(
+			"Unrolled {} hyperperiods, which gives {} jobs; {} jobs can finish after the end of the first hyperperiod",
+			args.num_hyperperiods, problem.jobs.len(), num_crossing_jobs
+		);
+	}
+
+	// The MAST model is written again when a schedule is found
+	if let Some(mast_file) = &args.mast {
+		write_mast_model(&problem, None, mast_file);
+	}
+	if let Some(cheddar_file) = &args.cheddar {
+		write_cheddar_model(&problem, cheddar_file);
+	}
+	// The features are written again when the verdict is known
+	if let Some(features_file) = &args.features {
+		write_job_features(&problem, Verdict::Unknown, None, features_file);
+	}
+
+	let segmentation = args.segments_file.as_deref().map(|segments_file| {
+		let (split_problem, segmentation) = split_into_segments(&problem, &parse_segments(segments_file))
+			.unwrap_or_else(|error| {
+				eprintln!("{}", error);
+				exit(1);
+			});
+		println!(
+			"Split {} jobs into segments, which gives {} jobs in total", segmentation.get_num_split_jobs(),
+			split_problem.jobs.len()
+		);
+		problem = split_problem;
+		segmentation
+	});
+
+	if let Some(directory) = &args.record_regressions {
+		record_panics(&problem, directory.clone());
+	}
+	if let Some(timeout) = args.timeout {
+		start_watchdog(&problem, timeout, args.record_regressions.clone());
+	}
+
+	if let Some(dispatch_order_file) = &args.verify {
+		verify(&args, &problem, dispatch_order_file);
+		return;
+	}
+
+	if let Some(certificate_file) = &args.check_certificate {
+		check_certificate(&problem, certificate_file);
+		return;
+	}
+
+	if let Some(job) = args.sensitivity {
+		if let Some(job) = job {
+			assert!(job < problem.jobs.len(), "Job {} doesn't exist", job);
+		}
+		analyze_sensitivity(&args, &problem, job);
+		return;
+	}
+
+	if let Some(output) = &args.minimize {
+		minimize(&args, &problem, output);
+		return;
+	}
+
+	if args.interactive {
+		run_repl(problem, std::io::stdin().lock(), std::io::stdout().lock());
+		return;
+	}
+
+	let prefix_order = args.prefix_order.as_deref().map(parse_job_ordering).unwrap_or_default();
+	if let Err(position) = problem.check_dispatch_prefix(&prefix_order) {
+		eprintln!(
+			"Job {} at position {} of the prefix order is duplicated, unknown, or dispatched before one of its predecessors",
+			prefix_order[position], position
+		);
+		exit(1);
+	}
+
+	if problem.is_mixed_criticality() {
+		println!("Analyzing LO mode with all {} jobs", problem.jobs.len());
+		analyze(&args, problem.get_mode_problem(Criticality::Lo), &prefix_order, None);
+
+		let hi_jobs = problem.get_mode_jobs(Criticality::Hi);
+		println!("Analyzing HI mode with {} jobs, which are renumbered in this order: {:?}", hi_jobs.len(), hi_jobs);
+		analyze(&args, problem.get_mode_problem(Criticality::Hi), &prefix_order, None);
+	} else {
+		analyze(&args, problem, &prefix_order, segmentation.as_ref());
+	}
+}
 
-	let maybe_permutation = ProblemPermutation::possible(&mut problem);
-	if let Some(permutation) = maybe_permutation {
-		strengthen_bounds_using_constraints(&mut problem);
-		debug_assert!(!strengthen_bounds_using_constraints(&mut problem));
-		strengthen_bounds_using_core_occupation(&mut problem);
-		permutation.transform_back(&mut problem);
-		if problem.is_certainly_infeasible() || run_feasibility_load_test(&problem) || run_feasibility_interval_test(&problem) {
+/// Makes sure that `problem` is added to the regression corpus in `directory` when the analysis
+/// panics
+fn record_panics(problem: &Problem, directory: String) {
+	let problem = problem.clone();
+	let default_hook = take_hook();
+	set_hook(Box::new(move |info| {
+		default_hook(info);
+		let path = record_regression(&problem, RegressionKind::Panic, &directory);
+		eprintln!("Added this problem to the regression corpus as {}", path);
+	}));
+}
+
+/// Stops the program when the analysis of `problem` takes more than `timeout` seconds, after adding
+/// `problem` to the regression corpus in `directory` (if any)
+fn start_watchdog(problem: &Problem, timeout: u64, directory: Option<String>) {
+	let problem = problem.clone();
+	spawn(move || {
+		sleep(Duration::from_secs(timeout));
+		println!("The analysis didn't finish within {} seconds", timeout);
+		if let Some(directory) = directory {
+			let path = record_regression(&problem, RegressionKind::Hang, &directory);
+			println!("Added this problem to the regression corpus as {}", path);
+		}
+		exit(1);
+	});
+}
+
+/// Replays the regression corpus in `directory`, and prints the problems whose analyses contradict
+/// each other
+fn replay(directory: &str) {
+	let corpus = parse_regression_corpus(directory);
+	let mut num_failures = 0;
+	for (path, problem) in &corpus {
+		if let Err(description) = check_regression(problem) {
+			println!("FAILED {}: {}", path, description);
+			num_failures += 1;
+		}
+	}
+	println!("Replayed {} problems, of which {} failed", corpus.len(), num_failures);
+	if num_failures > 0 {
+		exit(1);
+	}
+}
+
+/// Generates a random problem with the options of the `generate` subcommand, and writes it
+fn generate(args: &GenerateArgs) {
+	let problem = if let Some(num_tasks) = args.num_tasks {
+		generate_task_set(&TaskSetConfig {
+			seed: args.seed,
+			num_tasks,
+			num_cores: args.num_cores,
+			utilization: args.utilization,
+			min_period: args.min_period,
+			max_period: args.max_period,
+			period_granularity: args.period_granularity,
+			horizon: args.horizon,
+			num_layers: args.layers,
+			max_fan_in: args.max_fan_in,
+			max_delay: args.max_delay,
+		})
+	} else {
+		generate_problem(&GeneratorConfig {
+			seed: args.seed,
+			num_jobs: args.num_jobs,
+			num_cores: args.num_cores,
+			utilization: args.utilization,
+			horizon: args.horizon,
+			max_window_factor: args.max_window_factor,
+			num_layers: args.layers,
+			max_fan_in: args.max_fan_in,
+			max_delay: args.max_delay,
+		})
+	};
+	let jobs_path = format!("{}.csv", args.output);
+	let precedence_path = format!("{}.prec.csv", args.output);
+	write_problem(&problem, &jobs_path, &precedence_path);
+	println!(
+		"Generated {} jobs and {} constraints for {} cores in {} and {}",
+		problem.jobs.len(), problem.constraints.len(), problem.num_cores, jobs_path, precedence_path
+	);
+}
+
+/// Copies `original_problem` with the strengthened bounds of `problem`, which was obtained by
+/// normalizing and permuting `original_problem`
+fn get_strengthened_original(
+	original_problem: &Problem, problem: &Problem, permutation: &ProblemPermutation, time_scale: Time
+) -> Problem {
+	let mut strengthened = original_problem.clone();
+	for (job, bounds) in strengthened.jobs.iter_mut().zip(permutation.unapply_to_jobs(&problem.jobs)) {
+		job.earliest_start = bounds.earliest_start * time_scale;
+		job.latest_start = bounds.latest_start * time_scale;
+	}
+	strengthened
+}
+
+/// Saves the bounds of `strengthened` (see `get_strengthened_original`), the `implied_constraints`
+/// of `original_problem`, and `profile` to `directory`, so that the next analysis of
+/// `original_problem` can skip the strengthening
+fn save_warm_state(
+	original_problem: &Problem, strengthened: &Problem, implied_constraints: Vec<Constraint>,
+	profile: TestProfile, directory: &str
+) {
+	let state = AnalysisState::new(compute_fingerprint(original_problem), strengthened, implied_constraints, profile);
+	save_analysis_state(&state, directory);
+}
+
+/// Runs the analysis that was requested by `args` on `problem`, and prints the verdict. When
+/// `problem` was split into segments, the schedule is reported for the original problem of
+/// `segmentation`.
+fn analyze(args: &Args, mut problem: Problem, prefix_order: &[usize], segmentation: Option<&Segmentation>) {
+	let num_duplicate_constraints = problem.merge_duplicate_constraints();
+	if num_duplicate_constraints > 0 {
+		println!("Merged {} duplicate constraints", num_duplicate_constraints);
+	}
+	if let Some(index) = problem.find_contradictory_constraint() {
+		let constraint = problem.constraints[index];
+		println!(
+			"The constraint from job {} to job {} can't be satisfied within the windows of these jobs! INFEASIBLE",
+			constraint.get_before(), constraint.get_after()
+		);
+		report_infeasibility(args, &problem);
+		return;
+	}
+	if let Some((first, second)) = problem.find_contradictory_constraint_pair() {
+		println!(
+			"These constraints can't be satisfied together! INFEASIBLE\n{}\n{}",
+			describe_constraint(problem.constraints[first], 1), describe_constraint(problem.constraints[second], 1)
+		);
+		report_infeasibility(args, &problem);
+		return;
+	}
+
+	let original_problem = problem.clone();
+	let fingerprint = compute_fingerprint(&problem);
+	let warm_state = args.warm_start.as_deref().and_then(|directory| load_analysis_state(directory, fingerprint));
+	let is_warm = warm_state.as_ref().is_some_and(|state| state.apply_to(&mut problem));
+	if is_warm {
+		println!(
+			"Loaded the strengthened bounds and {} implied constraints of an earlier analysis",
+			problem.constraints.len() - original_problem.constraints.len()
+		);
+	} else if warm_state.is_some() {
+		println!("Ignored the state of an earlier analysis, since the jobs are in a different order");
+	}
+	let mut profile = warm_state.map(|state| state.profile).unwrap_or_default();
+
+	// The stored bounds and implied constraints are multiples of the time scale of the original
+	// problem, so they don't change it
+	let time_scale = problem.get_time_gcd();
+	if time_scale > 1 {
+		println!("Divided all times by {}", time_scale);
+		problem.divide_times(time_scale);
+	}
+
+	if !is_warm {
+		let implied_constraints = find_implied_constraints(&problem);
+		if !implied_constraints.is_empty() {
+			println!("Found {} implied constraints", implied_constraints.len());
+			problem.constraints.extend(implied_constraints);
+		}
+	}
+	let implied_constraints: Vec<Constraint> = problem.constraints[original_problem.constraints.len() ..].iter().map(
+		|c| Constraint::new(c.get_before(), c.get_after(), c.get_delay() * time_scale, c.get_type())
+	).collect();
+
+	// All analyses run on the permuted problem, in which `c.before < c.after` holds for every
+	// constraint `c`. Results that refer to jobs are mapped back to the original indices right
+	// before they are reported.
+	let maybe_permutation = ProblemPermutation::possible_with_order(&mut problem, ReadyJobOrder::EarliestStart);
+	if let Ok(permutation) = &maybe_permutation {
+		assert!(problem.is_job_order_possible());
+		if !is_warm {
+			strengthen_bounds_using_constraints(&mut problem);
+			debug_assert!(!strengthen_bounds_using_constraints(&mut problem));
+			strengthen_bounds_using_core_occupation(&mut problem);
+		}
+
+		let prefix_order: Vec<usize> = prefix_order.iter().map(|job| permutation.map_index(*job)).collect();
+		let prefix_order = &prefix_order;
+		// Reports a schedule of `original_problem` whose times are divided by `time_scale`
+		let report_original = |schedule: &Schedule, time_scale: Time| match segmentation {
+			Some(segmentation) => report_schedule(
+				args, &segmentation.original_problem, &segmentation.merge_schedule(schedule), time_scale
+			),
+			None => report_schedule(args, &original_problem, schedule, time_scale),
+		};
+		let report = |schedule: &Schedule| {
+			let mut schedule = schedule.clone();
+			permutation.unapply_to(&mut schedule);
+			report_original(&schedule, time_scale);
+		};
+
+		let index = ProblemIndex::new(&problem);
+		let is_infeasible = profile.run_tests(&problem, &index);
+		let strengthened = get_strengthened_original(&original_problem, &problem, permutation, time_scale);
+		if let Some(directory) = &args.warm_start {
+			save_warm_state(&original_problem, &strengthened, implied_constraints, profile, directory);
+		}
+		if args.narrowing && !is_infeasible {
+			println!("{}", NarrowingReport::new(&original_problem, &strengthened));
+		}
+
+		if is_infeasible {
 			println!("INFEASIBLE");
+			report_infeasibility(args, &original_problem);
+			if args.tardiness {
+				report_tardiness(args, &original_problem, segmentation);
+			}
+			if let Some(directory) = &args.record_regressions
+				&& (args.policy.is_some() || args.exact.is_some()) && is_solved(args, &original_problem) {
+				let path = record_regression(&original_problem, RegressionKind::Disagreement, directory);
+				println!(
+					"However, a feasible schedule was found as well! Added this problem to the regression corpus as {}",
+					path
+				);
+			}
+			if args.shrink {
+				report_infeasible_subset(&original_problem);
+			}
+		} else if let Some(schedule) = args.policy.and_then(|policy| schedule_using_policy(&original_problem, policy)) {
+			// The policy is simulated on the original problem, since the strengthened earliest start
+			// times would act as release times that the real scheduler doesn't have
+			println!("{}", feasible_verdict(args));
+			report_original(&schedule, 1);
+		} else if let Some(method) = args.exact {
+			match solve_with_checkpoints(args, &problem, method, prefix_order, Objective::EarlyStartTimes) {
+				ExactResult::Feasible(schedule) => {
+					println!("{}", feasible_verdict(args));
+					report(&schedule);
+
+					if let Some(goal) = args.optimize {
+						let objective = match goal {
+							OptimizationGoal::Makespan => Objective::Makespan,
+							OptimizationGoal::Slack => Objective::MinimumSlack,
+						};
+						match solve_with_checkpoints(args, &problem, method, prefix_order, objective) {
+							ExactResult::Feasible(optimized) => {
+								println!("Optimized schedule:");
+								report(&optimized);
+							},
+							ExactResult::Infeasible => panic!("The optimization claims that a feasible problem is infeasible"),
+						}
+					}
+				},
+				ExactResult::Infeasible if !prefix_order.is_empty() => {
+					// Only the schedules that start with the prefix order were considered
+					println!("The prefix order can't be completed to a feasible schedule, so this problem may or may not be feasible.");
+					if args.tardiness {
+						report_tardiness(args, &original_problem, segmentation);
+					}
+				},
+				ExactResult::Infeasible if method == ExactMethod::Enumeration && problem.has_max_delays() => {
+					println!(
+						"The enumeration starts every job as early as possible, which can exceed maximum delays, \
+						so this problem may or may not be feasible."
+					);
+					if args.tardiness {
+						report_tardiness(args, &original_problem, segmentation);
+					}
+				},
+				ExactResult::Infeasible if method == ExactMethod::Enumeration && problem.has_affinities() => {
+					println!(
+						"The enumeration puts every job on the cores that become available first, which can block \
+						jobs with an affinity, so this problem may or may not be feasible."
+					);
+					if args.tardiness {
+						report_tardiness(args, &original_problem, segmentation);
+					}
+				},
+				ExactResult::Infeasible => {
+					println!("INFEASIBLE");
+					report_infeasibility(args, &original_problem);
+					if args.tardiness {
+						report_tardiness(args, &original_problem, segmentation);
+					}
+				},
+			}
+		} else if let Some(schedule) = args.samples.and_then(
+			|num_samples| estimate_using_samples(args, &problem, permutation, num_samples, time_scale)
+		) {
+			println!("{}", feasible_verdict(args));
+			report(&schedule);
 		} else {
 			println!("This problem may or may not be feasible.");
+			if args.tardiness {
+				report_tardiness(args, &original_problem, segmentation);
+			}
 		}
-	} else {
+	} else if let Err(cycle) = maybe_permutation {
 		println!("This problem is cyclic! INFEASIBLE");
+		println!("The following constraints form a cycle:");
+		for index in cycle.constraints {
+			let description = describe_constraint(problem.constraints[index], time_scale);
+			if index < original_problem.constraints.len() {
+				println!("{}", description);
+			} else {
+				println!("{} (implied by their windows)", description);
+			}
+		}
+		report_infeasibility(args, &original_problem);
+		if args.shrink {
+			report_infeasible_subset(&original_problem);
+		}
+
+		// The jobs can only be dispatched when the cycle consists of implied constraints
+		let mut acyclic_problem = original_problem.clone();
+		if args.tardiness && ProblemPermutation::possible_with_order(&mut acyclic_problem, ReadyJobOrder::EarliestStart).is_ok() {
+			report_tardiness(args, &original_problem, segmentation);
+		}
 	}
 }
@@ -0,0 +1,48 @@
+use crate::bounds::*;
+use crate::necessary::*;
+use crate::permutation::{ProblemPermutation, ReadyJobOrder};
+use crate::problem::*;
+
+/// Adds the implied constraints (see `find_implied_constraints`) to `problem`, strengthens its
+/// bounds, and runs all necessary tests. Returns `true` if any of them proves that `problem` is
+/// infeasible, in which case the contents of `problem` are unspecified.
+///
+/// Otherwise, the implied constraints stay in `problem` (merged with duplicates), so that later
+/// passes like the enumeration can prune dispatch orders that violate them. Since they are only
+/// implied by the bounds, callers that report or export a problem should use the original one.
+pub fn strengthen_and_test(problem: &mut Problem) -> bool {
+	strengthen_and_run(problem, |problem, index| NecessaryTest::ALL.iter().any(|test| test.run(problem, index)))
+}
+
+/// Like `strengthen_and_test`, but runs the necessary tests in the order of `profile`, and records
+/// their statistics in it
+pub fn strengthen_and_test_with_profile(problem: &mut Problem, profile: &mut TestProfile) -> bool {
+	strengthen_and_run(problem, |problem, index| profile.run_tests(problem, index))
+}
+
+fn strengthen_and_run(problem: &mut Problem, run_tests: impl FnOnce(&Problem, &ProblemIndex) -> bool) -> bool {
+	if problem.jobs.is_empty() {
+		return false;
+	}
+	if problem.find_contradictory_constraint().is_some() || problem.find_contradictory_constraint_pair().is_some() {
+		return true;
+	}
+	let implied_constraints = find_implied_constraints(problem);
+	problem.constraints.extend(implied_constraints);
+	problem.merge_duplicate_constraints();
+
+	let Ok(permutation) = ProblemPermutation::possible_with_order(problem, ReadyJobOrder::EarliestStart) else { return true; };
+	strengthen_bounds_using_constraints(problem);
+	strengthen_bounds_using_core_occupation(problem);
+
+	let index = ProblemIndex::new(problem);
+	let is_infeasible = run_tests(problem, &index);
+	permutation.transform_back(problem);
+	is_infeasible
+}
+
+/// Runs all necessary tests on (a strengthened copy of) `problem`, and returns `true` if any of
+/// them proves that `problem` is infeasible
+pub fn is_proven_infeasible(problem: &Problem) -> bool {
+	strengthen_and_test(&mut problem.clone())
+}
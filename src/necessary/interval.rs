@@ -1,5 +1,6 @@
 use crate::necessary::interval_tree::{IntervalTree, JobInterval};
 use crate::necessary::pack::is_certainly_unpackable;
+use crate::necessary::ProblemIndex;
 use crate::problem::*;
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
@@ -11,6 +12,7 @@ enum IntervalResult {
 
 struct IntervalTest<'a> {
 	problem: &'a Problem,
+	index: &'a ProblemIndex,
 	interval_tree: IntervalTree,
 
 	next_job_index: usize,
@@ -19,52 +21,52 @@ struct IntervalTest<'a> {
 	start_time: Time,
 	end_time: Time,
 
+	job_loads: Vec<(usize, Time)>,
 	required_loads: Vec<Time>,
-	corresponding_jobs: Vec<usize>,
+
+	/// The groups of `Problem::get_affinity_groups`, with their number of cores
+	affinity_groups: Vec<(CoreSet, u32)>,
 }
 
 impl<'a> IntervalTest<'a> {
-	fn new(problem: &'a Problem) -> Self {
-		let mut interval_tree = IntervalTree::new();
-		for job in &problem.jobs {
-			interval_tree.insert(JobInterval {
-				job: job.get_index(),
-				start: job.earliest_start,
-				end: job.get_latest_finish()
-			});
-		}
-		interval_tree.split();
+	fn new(problem: &'a Problem, index: &'a ProblemIndex) -> Self {
+		let interval_tree = IntervalTree::new((0 .. problem.jobs.len()).map(|job| JobInterval {
+			job,
+			start: index.earliest_starts[job],
+			end: index.latest_finishes[job]
+		}).collect());
 
 		Self {
-			problem, interval_tree,
+			problem, index, interval_tree,
 			next_job_index: 0,
 			relevant_jobs: Vec::new(),
 			start_time: 0,
 			end_time: 0,
+			job_loads: Vec::new(),
 			required_loads: Vec::new(),
-			corresponding_jobs: Vec::new(),
+			affinity_groups: problem.get_affinity_groups().into_iter().map(
+				|group| (group, group.get_cores(problem.num_cores).count() as u32)
+			).collect(),
 		}
 	}
 
 	fn next(&mut self) -> IntervalResult {
-		let next_job = self.problem.jobs[self.next_job_index];
+		let next_job = self.next_job_index;
 		self.next_job_index += 1;
 
-		self.start_time = next_job.earliest_start;
-		self.end_time = next_job.get_latest_finish();
+		self.start_time = self.index.earliest_starts[next_job];
+		self.end_time = self.index.latest_finishes[next_job];
 
 		// Find all jobs that satisfy both conditions:
 		// - their latest start time is smaller than end_time
 		// - their earliest finish time is larger than start_time
 		self.interval_tree.query(JobInterval {
-			job: next_job.get_index(),
+			job: next_job,
 			start: self.start_time,
 			end: self.end_time
 		}, &mut self.relevant_jobs);
 
-		self.required_loads.clear();
-		self.corresponding_jobs.clear();
-
+		self.job_loads.clear();
 		for interval in &self.relevant_jobs {
 			let mut non_overlapping_time = 0;
 			if interval.start < self.start_time {
@@ -76,28 +78,61 @@ impl<'a> IntervalTest<'a> {
 				);
 			}
 
-			let exec_time = self.problem.jobs[interval.job].get_execution_time();
+			let exec_time = self.index.execution_times[interval.job];
 			if exec_time > non_overlapping_time {
-				self.required_loads.push(Time::min(
-					exec_time - non_overlapping_time, self.end_time - self.start_time
-				));
-				self.corresponding_jobs.push(interval.job);
+				let required_load = Time::min(exec_time - non_overlapping_time, self.end_time - self.start_time);
+				self.job_loads.push((interval.job, required_load));
 			}
 		}
-
 		self.relevant_jobs.clear();
-		if is_certainly_unpackable(self.problem.num_cores, self.end_time - self.start_time, &mut self.required_loads) {
-			IntervalResult::CertainlyInfeasible
-		} else if self.next_job_index < self.problem.jobs.len() {
+
+		if self.is_certainly_unpackable(CoreSet::all(), self.problem.num_cores) {
+			return IntervalResult::CertainlyInfeasible;
+		}
+
+		// The jobs whose affinity is a subset of a group must fit on the cores of that group
+		for group_index in 0 .. self.affinity_groups.len() {
+			let (group, num_cores) = self.affinity_groups[group_index];
+			if self.is_certainly_unpackable(group, num_cores) {
+				return IntervalResult::CertainlyInfeasible;
+			}
+		}
+
+		if self.next_job_index < self.problem.jobs.len() {
 			IntervalResult::Running
 		} else {
 			IntervalResult::Finished
 		}
 	}
+
+	/// Checks whether the required loads of the jobs whose affinity is a subset of `group` certainly
+	/// don't fit on `num_cores` cores during the current interval
+	fn is_certainly_unpackable(&mut self, group: CoreSet, num_cores: u32) -> bool {
+		// A gang job is treated like `parallelism` independent jobs, which is a relaxation since
+		// they would need to run on different cores at the same time
+		self.required_loads.clear();
+		for &(job, required_load) in &self.job_loads {
+			if self.problem.jobs[job].get_affinity().is_subset_of(group) {
+				for _ in 0 .. self.index.parallelisms[job] {
+					self.required_loads.push(required_load);
+				}
+			}
+		}
+
+		if num_cores == 0 {
+			return !self.required_loads.is_empty();
+		}
+		is_certainly_unpackable(num_cores, self.end_time - self.start_time, &mut self.required_loads)
+	}
 }
 
 pub fn run_feasibility_interval_test(problem: &Problem) -> bool {
-	let mut test = IntervalTest::new(problem);
+	run_feasibility_interval_test_with_index(problem, &ProblemIndex::new(problem))
+}
+
+/// Runs the interval test using the job arrays of `index`, which must be computed from `problem`
+pub fn run_feasibility_interval_test_with_index(problem: &Problem, index: &ProblemIndex) -> bool {
+	let mut test = IntervalTest::new(problem, index);
 	loop {
 		match test.next() {
 			IntervalResult::Finished => return false,
@@ -1,5 +1,4 @@
 use crate::problem::Time;
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy)]
 pub struct JobInterval {
@@ -8,104 +7,139 @@ pub struct JobInterval {
 	pub end: Time,
 }
 
-pub struct IntervalTree {
+/// Nodes with fewer intervals than this are not split any further
+const MIN_SPLIT_SIZE: usize = 50;
+
+/// Indicates that a node doesn't have a child on that side
+const NO_NODE: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct IntervalTreeNode {
 	split_time: Time,
-	middle: Vec<JobInterval>,
 
-	before: Option<Rc<Self>>,
-	after: Option<Rc<Self>>,
+	/// The intervals of this node that contain `split_time` are
+	/// `IntervalTree::intervals[middle_start .. middle_end]`
+	middle_start: usize,
+	middle_end: usize,
 
-	stack: Vec<Rc<Self>>,
-}
+	/// The index of the child node with the intervals that end before `split_time`, or `NO_NODE`
+	before: usize,
 
-impl IntervalTree {
-	pub fn new() -> Self {
-		Self {
-			split_time: 0,
-			middle: Vec::new(),
+	/// The index of the child node with the intervals that start after `split_time`, or `NO_NODE`
+	after: usize,
+}
 
-			before: None,
-			after: None,
+/// A static interval tree. All nodes are stored in a single `Vec`, and the intervals of all nodes
+/// are stored in another `Vec`, which is good for cache locality, and makes the tree `Send` and
+/// `Sync`.
+pub struct IntervalTree {
+	nodes: Vec<IntervalTreeNode>,
+	intervals: Vec<JobInterval>,
+}
 
-			stack: Vec::new(),
-		}
+impl IntervalTree {
+	pub fn new(intervals: Vec<JobInterval>) -> Self {
+		let mut tree = Self { nodes: Vec::new(), intervals: Vec::with_capacity(intervals.len()) };
+		tree.build(intervals);
+		tree
 	}
 
-	pub fn insert(&mut self, interval: JobInterval) {
-		debug_assert!(self.before.is_none());
-		debug_assert!(self.after.is_none());
-		self.middle.push(interval);
-	}
+	/// Adds a node with the given intervals (and its children), and returns its index
+	fn build(&mut self, mut intervals: Vec<JobInterval>) -> usize {
+		let index = self.nodes.len();
+		self.nodes.push(IntervalTreeNode {
+			split_time: 0,
+			middle_start: 0,
+			middle_end: 0,
+			before: NO_NODE,
+			after: NO_NODE,
+		});
 
-	pub fn split(&mut self) {
-		debug_assert!(self.before.is_none());
-		debug_assert!(self.after.is_none());
-		if self.middle.len() < 50 {
-			return;
+		let mut before = Vec::new();
+		let mut after = Vec::new();
+		let mut split_time = 0;
+		if intervals.len() >= MIN_SPLIT_SIZE {
+			intervals.sort_by_key(|i| i.start + i.end);
+			let split_interval = &intervals[intervals.len() / 2];
+			split_time = (split_interval.start + split_interval.end) / 2;
+
+			intervals.retain(|i| {
+				if i.end <= split_time {
+					before.push(*i);
+					false
+				} else if i.start >= split_time {
+					after.push(*i);
+					false
+				} else {
+					true
+				}
+			});
 		}
 
-		let mut before = Self::new();
-		let mut after = Self::new();
-		self.middle.sort_by_key(|i| i.start + i.end);
-		let split_interval = &self.middle[self.middle.len() / 2];
-		self.split_time = (split_interval.start + split_interval.end) / 2;
-
-		self.middle.retain(|i| {
-			if i.end <= self.split_time {
-				before.insert(*i);
-				false
-			} else if i.start >= self.split_time {
-				after.insert(*i);
-				false
-			} else {
-				true
-			}
-		});
+		let middle_start = self.intervals.len();
+		self.intervals.extend_from_slice(&intervals);
+		let middle_end = self.intervals.len();
+		drop(intervals);
 
-		before.split();
-		after.split();
-		self.before = Some(Rc::new(before));
-		self.after = Some(Rc::new(after));
+		let before = if before.is_empty() { NO_NODE } else { self.build(before) };
+		let after = if after.is_empty() { NO_NODE } else { self.build(after) };
+		self.nodes[index] = IntervalTreeNode { split_time, middle_start, middle_end, before, after };
+		index
 	}
 
-	pub fn query(&mut self, interval: JobInterval, output: &mut Vec<JobInterval>) {
-		debug_assert_eq!(0, self.stack.len());
+	/// Appends all intervals that overlap with `interval` to `output`. Since this doesn't modify the
+	/// tree, multiple threads can query the same tree concurrently.
+	pub fn query(&self, interval: JobInterval, output: &mut Vec<JobInterval>) {
+		self.query_node(0, interval, output);
+	}
 
-		if let Some(before) = &self.before {
-			if interval.start < self.split_time {
-				self.stack.push(Rc::clone(before));
+	fn query_node(&self, index: usize, interval: JobInterval, output: &mut Vec<JobInterval>) {
+		let node = &self.nodes[index];
+		for candidate in &self.intervals[node.middle_start .. node.middle_end] {
+			if candidate.start < interval.end && candidate.end > interval.start {
+				output.push(*candidate);
 			}
 		}
 
-		if let Some(after) = &self.after {
-			if interval.end > self.split_time {
-				self.stack.push(Rc::clone(after));
-			}
+		// The depth of the tree is logarithmic in the number of intervals, so recursion is fine
+		if node.before != NO_NODE && interval.start < node.split_time {
+			self.query_node(node.before, interval, output);
 		}
-
-		for candidate in &self.middle {
-			if candidate.start < interval.end && candidate.end > interval.start {
-				output.push(*candidate);
-			}
+		if node.after != NO_NODE && interval.end > node.split_time {
+			self.query_node(node.after, interval, output);
 		}
+	}
+}
 
-		while let Some(current_node) = self.stack.pop() {
-			if let Some(before) = &current_node.before {
-				if interval.start < current_node.split_time {
-					self.stack.push(Rc::clone(before));
-				}
-			}
-			if let Some(after) = &current_node.after {
-				if interval.end > current_node.split_time {
-					self.stack.push(Rc::clone(after));
-				}
-			}
-			for candidate in &current_node.middle {
-				if candidate.start < interval.end && candidate.end > interval.start {
-					output.push(*candidate);
-				}
-			}
+#[cfg(test)]
+mod tests {
+	use crate::necessary::interval_tree::*;
+
+	fn assert_send_sync<T: Send + Sync>() {}
+
+	#[test]
+	fn test_query_matches_linear_search() {
+		assert_send_sync::<IntervalTree>();
+
+		let mut intervals = Vec::new();
+		for job in 0 .. 1000 {
+			let start = (job as Time * 7919) % 5000;
+			intervals.push(JobInterval { job, start, end: start + 1 + (job as Time * 31) % 200 });
+		}
+		let tree = IntervalTree::new(intervals.clone());
+		assert!(tree.nodes.len() > 10);
+
+		for (start, end) in [(0, 1), (100, 300), (2500, 2501), (4000, 6000), (-10, 0), (0, 10_000)] {
+			let query = JobInterval { job: 0, start, end };
+			let mut output = Vec::new();
+			tree.query(query, &mut output);
+
+			let mut actual: Vec<usize> = output.iter().map(|interval| interval.job).collect();
+			actual.sort();
+			let expected: Vec<usize> = intervals.iter().filter(
+				|interval| interval.start < end && interval.end > start
+			).map(|interval| interval.job).collect();
+			assert_eq!(expected, actual);
 		}
-		self.stack.clear();
 	}
 }
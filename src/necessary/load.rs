@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use crate::necessary::ProblemIndex;
 use crate::problem::*;
 use crate::sorted_job_iterator::SortedJobIterator;
 
@@ -28,7 +28,7 @@ struct LoadTest<'a> {
 	jobs_by_earliest_start: SortedJobIterator,
 	jobs_by_latest_start: SortedJobIterator,
 
-	times_of_interest: Vec<Time>,
+	index: &'a ProblemIndex,
 	current_time: Time,
 	time_index: usize,
 
@@ -41,20 +41,12 @@ struct LoadTest<'a> {
 }
 
 impl<'a> LoadTest<'a> {
-	fn new(problem: &'a Problem) -> Self {
+	fn new(problem: &'a Problem, index: &'a ProblemIndex) -> Self {
 		let jobs_by_earliest_start = SortedJobIterator::new(&problem.jobs, |j| j.earliest_start);
 		let jobs_by_latest_start = SortedJobIterator::new(&problem.jobs, |j| j.latest_start);
-		let mut times_of_interest: HashSet<Time> = HashSet::with_capacity(2 * problem.jobs.len());
-		for job in &problem.jobs {
-			times_of_interest.insert(job.latest_start);
-			times_of_interest.insert(job.get_latest_finish());
-		}
-		times_of_interest.remove(&0);
-		let mut sorted_times_of_interest = times_of_interest.into_iter().collect::<Vec<_>>();
-		sorted_times_of_interest.sort();
 		LoadTest {
 			problem, jobs_by_earliest_start, jobs_by_latest_start,
-			times_of_interest: sorted_times_of_interest,
+			index,
 			current_time: 0, time_index: 0,
 			certainly_finished_jobs_load: 0,
 			minimum_executed_load: 0,
@@ -65,17 +57,17 @@ impl<'a> LoadTest<'a> {
 	}
 
 	fn next(&mut self) -> LoadResult {
-		let next_time = self.times_of_interest[self.time_index];
+		let next_time = self.index.times_of_interest[self.time_index];
 		self.time_index += 1;
 		let spent_time = next_time - self.current_time;
 
 		let mut earliest_step_arrival = next_time;
 		let earliest_possibly_running_job = self.possibly_running_jobs.iter().min_by_key(
-			|j| self.problem.jobs[j.job].earliest_start
+			|j| self.index.earliest_starts[j.job]
 		);
 		if let Some(earliest) = earliest_possibly_running_job {
 			earliest_step_arrival = Time::min(
-				earliest_step_arrival, self.problem.jobs[earliest.job].earliest_start
+				earliest_step_arrival, self.index.earliest_starts[earliest.job]
 			);
 		}
 
@@ -86,30 +78,32 @@ impl<'a> LoadTest<'a> {
 				running_job.maximum_remaining_time -= spent_time;
 				true
 			} else {
-				self.certainly_finished_jobs_load += self.problem.jobs[running_job.job].get_execution_time();
+				self.certainly_finished_jobs_load += self.index.execution_times[running_job.job];
 				maximum_load_this_step += running_job.maximum_remaining_time;
 				false
 			}
 		});
 
 		while let Some(early_index) = self.jobs_by_earliest_start.next(|time| time <= next_time) {
-			let early_job = &self.problem.jobs[early_index];
-			if early_job.get_latest_finish() > next_time {
+			let execution_time = self.index.execution_times[early_index];
+			let earliest_start = self.index.earliest_starts[early_index];
+			let latest_finish = self.index.latest_finishes[early_index];
+			if latest_finish > next_time {
 				self.possibly_running_jobs.push(LoadJob {
 					job: early_index,
-					maximum_remaining_time: early_job.get_latest_finish() - next_time,
+					maximum_remaining_time: latest_finish - next_time,
 				});
-				maximum_load_this_step += Time::min(early_job.get_execution_time(), next_time - early_job.earliest_start);
+				maximum_load_this_step += Time::min(execution_time, next_time - earliest_start);
 			} else {
-				self.certainly_finished_jobs_load += early_job.get_execution_time();
-				maximum_load_this_step += early_job.get_execution_time();
-				earliest_step_arrival = Time::min(earliest_step_arrival, early_job.earliest_start);
+				self.certainly_finished_jobs_load += execution_time;
+				maximum_load_this_step += execution_time;
+				earliest_step_arrival = Time::min(earliest_step_arrival, earliest_start);
 			}
 		}
 
 		self.certainly_started_jobs.retain_mut(|started| {
 			if started.maximum_remaining_time > spent_time {
-				started.maximum_remaining_time = self.problem.jobs[started.job].get_latest_finish() - next_time;
+				started.maximum_remaining_time = self.index.latest_finishes[started.job] - next_time;
 				true
 			} else {
 				false
@@ -117,11 +111,11 @@ impl<'a> LoadTest<'a> {
 		});
 
 		while let Some(late_index) = self.jobs_by_latest_start.next(|time| time <= next_time) {
-			let late_job = &self.problem.jobs[late_index];
-			if late_job.get_latest_finish() > next_time {
+			let latest_finish = self.index.latest_finishes[late_index];
+			if latest_finish > next_time {
 				self.certainly_started_jobs.push(LoadJob {
 					job: late_index,
-					maximum_remaining_time: late_job.get_latest_finish() - next_time,
+					maximum_remaining_time: latest_finish - next_time,
 				});
 			}
 		}
@@ -135,35 +129,34 @@ impl<'a> LoadTest<'a> {
 		let num_cores = self.problem.num_cores as usize;
 		if num_cores < self.certainly_started_jobs.len() {
 			while start_index < self.certainly_started_jobs.len() - num_cores {
-				let job = self.problem.jobs[self.certainly_started_jobs[start_index].job];
-				self.minimum_executed_load += job.get_execution_time();
+				self.minimum_executed_load += self.index.execution_times[self.certainly_started_jobs[start_index].job];
 				start_index += 1;
 			}
 		}
 
 		while start_index < self.certainly_started_jobs.len() {
-			let job = self.problem.jobs[self.certainly_started_jobs[start_index].job];
-			self.minimum_executed_load += self.certainly_started_jobs[start_index].get_minimum_spent_time(job.get_execution_time());
+			let started = &self.certainly_started_jobs[start_index];
+			self.minimum_executed_load += started.get_minimum_spent_time(self.index.execution_times[started.job]);
 			start_index += 1;
 		}
 
 		let mut max_load_bound2 = self.certainly_finished_jobs_load;
 		for running_job in &self.possibly_running_jobs {
-			let job = self.problem.jobs[running_job.job];
-			max_load_bound2 += job.get_execution_time();
-			earliest_step_arrival = Time::min(earliest_step_arrival, job.earliest_start);
+			max_load_bound2 += self.index.execution_times[running_job.job];
+			earliest_step_arrival = Time::min(earliest_step_arrival, self.index.earliest_starts[running_job.job]);
 		}
 
 		earliest_step_arrival = Time::max(earliest_step_arrival, self.current_time);
+		// The capacity of a long step may not fit in a `Time`, in which case it doesn't limit the load
 		self.maximum_executed_load += Time::min(
-			num_cores as Time * (next_time - earliest_step_arrival), maximum_load_this_step
+			(num_cores as Time).saturating_mul(next_time - earliest_step_arrival), maximum_load_this_step
 		);
 		self.maximum_executed_load = Time::min(self.maximum_executed_load, max_load_bound2);
 		self.current_time = next_time;
 
 		if self.minimum_executed_load > self.maximum_executed_load {
 			LoadResult::CertainlyInfeasible
-		} else if self.time_index < self.times_of_interest.len() {
+		} else if self.time_index < self.index.times_of_interest.len() {
 			LoadResult::Running
 		} else {
 			LoadResult::Finished
@@ -182,7 +175,13 @@ impl<'a> LoadTest<'a> {
 /// If the minimum amount of time spent in any interval is larger than the maximum amount of time
 /// spent in that interval, `problem` is certainly infeasible.
 pub fn run_feasibility_load_test(problem: &Problem) -> bool {
-	let mut load_test = LoadTest::new(problem);
+	run_feasibility_load_test_with_index(problem, &ProblemIndex::new(problem))
+}
+
+/// Runs the Feasibility Load Test (see `run_feasibility_load_test`) using the times of interest of
+/// `index`, which must be computed from `problem`
+pub fn run_feasibility_load_test_with_index(problem: &Problem, index: &ProblemIndex) -> bool {
+	let mut load_test = LoadTest::new(problem, index);
 	loop {
 		let result = load_test.next();
 		if result == LoadResult::CertainlyInfeasible {
@@ -206,8 +205,10 @@ mod tests {
 			jobs: vec![Job::release_to_deadline(0, 0, 1000, 1000)],
 			constraints: vec![],
 			num_cores: 1,
+			resources: Vec::new(),
 		};
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		assert_eq!(load_test.next(), LoadResult::Finished);
 		assert_eq!(load_test.current_time, 1000);
 		assert_eq!(load_test.minimum_executed_load, 1000);
@@ -223,8 +224,10 @@ mod tests {
 			jobs: vec![Job::release_to_deadline(0, 0, 999, 1000)],
 			constraints: vec![],
 			num_cores: 1,
+			resources: Vec::new(),
 		};
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		assert_eq!(load_test.next(), LoadResult::Running);
 		assert_eq!(load_test.current_time, 1);
 		assert_eq!(load_test.minimum_executed_load, 0);
@@ -245,6 +248,7 @@ mod tests {
 			jobs: vec![Job::release_to_deadline(0, 0, 1001, 1000)],
 			constraints: vec![],
 			num_cores: 1,
+			resources: Vec::new(),
 		};
 		assert!(run_feasibility_load_test(&problem));
 	}
@@ -259,9 +263,11 @@ mod tests {
 			],
 			constraints: vec![],
 			num_cores: 1,
+			resources: Vec::new(),
 		};
 
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		assert_eq!(load_test.next(), LoadResult::Running);
 		assert_eq!(load_test.current_time, 3);
 		assert_eq!(load_test.minimum_executed_load, 0);
@@ -301,9 +307,11 @@ mod tests {
 			],
 			constraints: vec![],
 			num_cores: 1,
+			resources: Vec::new(),
 		};
 
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		assert_eq!(load_test.next(), LoadResult::Running);
 		assert_eq!(load_test.current_time, 2);
 		assert_eq!(load_test.minimum_executed_load, 0);
@@ -326,10 +334,12 @@ mod tests {
 				Job::release_to_deadline(1, 4, 5, 19)
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		assert_eq!(load_test.next(), LoadResult::Running);
 		assert_eq!(load_test.current_time, 12);
 		assert_eq!(load_test.minimum_executed_load, 0);
@@ -362,10 +372,12 @@ mod tests {
 				Job::release_to_deadline(1, 4, 7, 20)
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		assert_eq!(load_test.next(), LoadResult::Running);
 		assert_eq!(load_test.current_time, 12);
 		assert_eq!(load_test.minimum_executed_load, 0);
@@ -407,10 +419,12 @@ mod tests {
 				Job::release_to_deadline(9, 0, 6, 20)
 			],
 			constraints: vec![],
-			num_cores: 2
+			num_cores: 2,
+			resources: Vec::new()
 		};
 
-		let mut load_test = LoadTest::new(&problem);
+		let index = ProblemIndex::new(&problem);
+		let mut load_test = LoadTest::new(&problem, &index);
 		loop {
 			let next = load_test.next();
 			if next == LoadResult::Finished {
@@ -445,7 +459,8 @@ mod tests {
 				Job::release_to_deadline(9, 0, 6, 20)
 			],
 			constraints: vec![],
-			num_cores: 2
+			num_cores: 2,
+			resources: Vec::new()
 		};
 
 		assert!(run_feasibility_load_test(&problem));
@@ -465,7 +480,8 @@ mod tests {
 				Job::release_to_deadline(4, 30, 5, 40),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 
 		assert!(!run_feasibility_load_test(&problem));
@@ -488,7 +504,8 @@ mod tests {
 				Job::release_to_deadline(4, 30, 5, 40),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 
 		assert!(run_feasibility_load_test(&problem));
@@ -512,7 +529,8 @@ mod tests {
 				Job::release_to_deadline(5, 0, 50, 100),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert!(run_feasibility_interval_test(&problem));
 	}
@@ -530,7 +548,8 @@ mod tests {
 				Job::release_to_deadline(4, 30, 5, 40),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert!(!run_feasibility_load_test(&problem));
 		assert!(!run_feasibility_interval_test(&problem));
@@ -555,7 +574,8 @@ mod tests {
 		let problem = Problem {
 			jobs: middle_overload_jobs(),
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert!(run_feasibility_load_test(&problem));
 		assert!(run_feasibility_interval_test(&problem));
@@ -566,7 +586,8 @@ mod tests {
 		let mut problem = Problem {
 			jobs: middle_overload_jobs(),
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		problem.jobs.push(Job::release_to_deadline(4, 30, 5, 40));
 		assert!(run_feasibility_interval_test(&problem));
@@ -586,7 +607,8 @@ mod tests {
 				Job::release_to_deadline(4, 30, 5, 40),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert!(!run_feasibility_load_test(&problem));
 		assert!(!run_feasibility_interval_test(&problem));
@@ -607,7 +629,8 @@ mod tests {
 				Job::release_to_deadline(4, 30, 5, 40),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert!(run_feasibility_load_test(&problem));
 		assert!(run_feasibility_interval_test(&problem));
@@ -629,7 +652,8 @@ mod tests {
 				Job::release_to_deadline(5, 0, 50, 100),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		assert_eq!(OccupationStrengthenResult::Infeasible, strengthen_bounds_using_core_occupation(&mut problem));
 		assert!(run_feasibility_interval_test(&problem));
@@ -645,7 +669,8 @@ mod tests {
 				Job::release_to_deadline(3, 60, 34, 100),
 			],
 			constraints: vec![],
-			num_cores: 2
+			num_cores: 2,
+			resources: Vec::new()
 		};
 
 		assert!(!run_feasibility_load_test(&problem));
@@ -665,10 +690,56 @@ mod tests {
 				Job::release_to_deadline(3, 0, 34, 38),
 			],
 			constraints: vec![],
-			num_cores: 2
+			num_cores: 2,
+			resources: Vec::new()
 		};
 
 		assert!(run_feasibility_interval_test(&problem));
 		assert_eq!(OccupationStrengthenResult::Infeasible, strengthen_bounds_using_core_occupation(&mut problem));
 	}
+
+	#[test]
+	fn test_interval_test_with_parallel_job() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 10),
+				Job::release_to_deadline(1, 0, 6, 15),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		assert!(!run_feasibility_interval_test(&problem));
+
+		// Job 0 occupies both cores until time 10, so job 1 can't finish before time 16
+		problem.jobs[0].set_parallelism(2);
+		assert!(run_feasibility_interval_test(&problem));
+	}
+
+	#[test]
+	fn test_interval_test_with_affinities() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 14),
+				Job::release_to_deadline(1, 0, 5, 14),
+				Job::release_to_deadline(2, 0, 4, 14),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		assert!(!run_feasibility_interval_test(&problem));
+
+		// Jobs 0 and 1 need 15 time units on core 0, but only 14 are available
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[0]));
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[0]));
+		assert!(run_feasibility_interval_test(&problem));
+
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[0, 1]));
+		assert!(!run_feasibility_interval_test(&problem));
+
+		// A job whose affinity contains no existing cores can never run
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[]));
+		assert!(run_feasibility_interval_test(&problem));
+	}
 }
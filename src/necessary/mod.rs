@@ -1,7 +1,15 @@
+mod combined;
 mod interval;
 mod interval_tree;
 mod load;
 mod pack;
+mod problem_index;
+mod profile;
+mod resource;
 
-pub use interval::run_feasibility_interval_test;
-pub use load::run_feasibility_load_test;
+pub use combined::{is_proven_infeasible, strengthen_and_test, strengthen_and_test_with_profile};
+pub use interval::{run_feasibility_interval_test, run_feasibility_interval_test_with_index};
+pub use load::{run_feasibility_load_test, run_feasibility_load_test_with_index};
+pub use problem_index::ProblemIndex;
+pub use profile::{NecessaryTest, TestProfile, TestStatistics};
+pub use resource::run_feasibility_resource_test;
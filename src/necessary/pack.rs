@@ -1,80 +1,86 @@
 use crate::problem::Time;
 
-pub fn is_certainly_unpackable(num_processors: u32, bin_size: Time, jobs: &mut Vec<Time>) -> bool {
+pub fn is_certainly_unpackable(num_processors: u32, bin_size: Time, jobs: &mut [Time]) -> bool {
 	debug_assert!(num_processors >= 1);
 	if jobs.is_empty() {
 		return false;
 	}
 
-	let mut total = 0;
+	// The sums and the capacity saturate rather than overflow: a saturated capacity is never
+	// exceeded, and a saturated sum only exceeds capacities that the real sum exceeds as well
+	let capacity = (num_processors as Time).saturating_mul(bin_size);
+	let mut total: Time = 0;
 	for job in jobs.iter() {
 		if *job > bin_size {
 			return true;
 		}
-		total += *job;
+		total = total.saturating_add(*job);
 	}
 
 	if jobs.len() as u32 <= num_processors {
 		return false;
 	}
-	if total > num_processors as Time * bin_size {
+	if total > capacity {
 		return true;
 	}
 	if num_processors == 1 || jobs.len() <= 2 {
 		return false;
 	}
 
-	jobs.sort();
+	// Only the 3 smallest jobs need to be in order, which takes linear time rather than sorting
+	jobs.select_nth_unstable(2);
+	jobs[.. 3].sort_unstable();
 
 	if jobs.len() == 3 {
 		debug_assert_eq!(num_processors, 2);
-		return jobs[0] + jobs[1] > bin_size;
+		return jobs[0].saturating_add(jobs[1]) > bin_size;
 	}
 	debug_assert!(jobs.len() >= 4);
 
-	let smallest2 = Time::min(jobs[2], jobs[0] + jobs[1]);
-	let mut min_wasted_space = 0;
+	let smallest2 = Time::min(jobs[2], jobs[0].saturating_add(jobs[1]));
+	let mut min_wasted_space: Time = 0;
 	for index in (1 .. jobs.len()).rev() {
 		let duration = jobs[index];
 
-		if duration + jobs[0] > bin_size {
-			min_wasted_space += bin_size - duration;
+		if duration.saturating_add(jobs[0]) > bin_size {
+			min_wasted_space = min_wasted_space.saturating_add(bin_size - duration);
 			continue;
 		}
 
-		if index > 1 && duration + jobs[1] > bin_size {
+		if index > 1 && duration.saturating_add(jobs[1]) > bin_size {
 			debug_assert!(duration + jobs[0] <= bin_size);
-			min_wasted_space += bin_size - jobs[0] - duration;
+			min_wasted_space = min_wasted_space.saturating_add(bin_size - jobs[0] - duration);
 			continue;
 		}
 
-		if index > 2 && duration + smallest2 > bin_size {
+		if index > 2 && duration.saturating_add(smallest2) > bin_size {
 			debug_assert!(duration + jobs[1] <= bin_size);
-			min_wasted_space += bin_size - jobs[1] - duration;
+			min_wasted_space = min_wasted_space.saturating_add(bin_size - jobs[1] - duration);
 		}
 	}
 
-	total + min_wasted_space > num_processors as Time * bin_size
+	total.saturating_add(min_wasted_space) > capacity
 }
 
 #[cfg(test)]
 mod tests {
 	use super::is_certainly_unpackable;
+	use crate::problem::Time;
 
 	#[test]
 	fn test_without_jobs() {
-		assert!(!is_certainly_unpackable(1, 10, &mut vec![]));
-		assert!(!is_certainly_unpackable(2, 10, &mut vec![]));
-		assert!(!is_certainly_unpackable(5, 10, &mut vec![]));
+		assert!(!is_certainly_unpackable(1, 10, &mut []));
+		assert!(!is_certainly_unpackable(2, 10, &mut []));
+		assert!(!is_certainly_unpackable(5, 10, &mut []));
 
-		assert!(!is_certainly_unpackable(1, 0, &mut vec![]));
-		assert!(!is_certainly_unpackable(2, 0, &mut vec![]));
-		assert!(!is_certainly_unpackable(5, 0, &mut vec![]));
+		assert!(!is_certainly_unpackable(1, 0, &mut []));
+		assert!(!is_certainly_unpackable(2, 0, &mut []));
+		assert!(!is_certainly_unpackable(5, 0, &mut []));
 	}
 
 	#[test]
 	fn test_with_1_job() {
-		let job = &mut vec![100];
+		let job = &mut [100];
 		assert!(is_certainly_unpackable(1, 99, job));
 		assert!(is_certainly_unpackable(5, 99, job));
 
@@ -186,4 +192,26 @@ mod tests {
 
 		assert!(!is_certainly_unpackable(3, 100, &mut jobs));
 	}
+
+	#[test]
+	fn test_with_huge_jobs() {
+		let huge = Time::MAX / 2;
+		assert!(is_certainly_unpackable(1, Time::MAX - 1, &mut [huge, huge, huge]));
+		assert!(is_certainly_unpackable(2, huge, &mut [huge, huge, huge]));
+		assert!(!is_certainly_unpackable(2, Time::MAX, &mut [huge, huge, huge]));
+		assert!(!is_certainly_unpackable(4, huge, &mut [huge, huge, huge, huge]));
+	}
+
+	#[test]
+	fn test_order_of_jobs_does_not_matter() {
+		let mut jobs = vec![70, 20, 95, 30, 60, 25, 90, 45];
+		let expected = (0 .. 500).map(|bin_size| is_certainly_unpackable(3, bin_size, &mut jobs.clone())).collect::<Vec<_>>();
+		for shift in 0 .. jobs.len() {
+			jobs.rotate_left(1);
+			jobs.swap(0, shift);
+			for bin_size in 0 .. 500 {
+				assert_eq!(expected[bin_size as usize], is_certainly_unpackable(3, bin_size, &mut jobs.clone()));
+			}
+		}
+	}
 }
@@ -0,0 +1,65 @@
+use crate::problem::*;
+
+/// Data about a problem that several necessary tests need, which is computed only once. It must be
+/// recomputed after the bounds of the problem change.
+///
+/// It also stores the fields of the jobs that the tests use in separate arrays (indexed by job),
+/// since the hot loops of the tests only need one or two fields of each job, and a `Job` is much
+/// larger than a single `Time`.
+#[derive(Debug, Clone)]
+pub struct ProblemIndex {
+	/// The distinct latest start times and latest finish times of all jobs, except 0, in increasing
+	/// order
+	pub times_of_interest: Vec<Time>,
+
+	pub earliest_starts: Vec<Time>,
+	pub latest_finishes: Vec<Time>,
+	pub execution_times: Vec<Time>,
+	pub parallelisms: Vec<u32>,
+}
+
+impl ProblemIndex {
+	pub fn new(problem: &Problem) -> Self {
+		let mut times_of_interest = Vec::with_capacity(2 * problem.jobs.len());
+		for job in &problem.jobs {
+			times_of_interest.push(job.latest_start);
+			times_of_interest.push(job.get_latest_finish());
+		}
+		times_of_interest.sort_unstable();
+		times_of_interest.dedup();
+		times_of_interest.retain(|time| *time != 0);
+
+		Self {
+			times_of_interest,
+			earliest_starts: problem.jobs.iter().map(|job| job.earliest_start).collect(),
+			latest_finishes: problem.jobs.iter().map(|job| job.get_latest_finish()).collect(),
+			execution_times: problem.jobs.iter().map(|job| job.get_execution_time()).collect(),
+			parallelisms: problem.jobs.iter().map(|job| job.get_parallelism()).collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::necessary::ProblemIndex;
+	use crate::problem::*;
+
+	#[test]
+	fn test_problem_index() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 10),
+				Job::release_to_deadline(1, 0, 5, 20),
+				Job::release_to_deadline(2, 5, 10, 25),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let index = ProblemIndex::new(&problem);
+		assert_eq!(vec![10, 15, 20, 25], index.times_of_interest);
+		assert_eq!(vec![0, 0, 5], index.earliest_starts);
+		assert_eq!(vec![10, 20, 25], index.latest_finishes);
+		assert_eq!(vec![10, 5, 10], index.execution_times);
+	}
+}
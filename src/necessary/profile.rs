@@ -0,0 +1,213 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::necessary::*;
+use crate::problem::*;
+
+/// The necessary tests that `strengthen_and_test` runs after strengthening the bounds
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NecessaryTest {
+	/// Checks whether the window of any job is smaller than its execution time
+	Windows,
+	Load,
+	Interval,
+	Resource,
+}
+
+impl NecessaryTest {
+	/// All tests, in the order in which `strengthen_and_test` runs them
+	pub const ALL: [Self; 4] = [Self::Windows, Self::Load, Self::Interval, Self::Resource];
+
+	pub fn get_name(&self) -> &'static str {
+		match self {
+			Self::Windows => "windows",
+			Self::Load => "load",
+			Self::Interval => "interval",
+			Self::Resource => "resource",
+		}
+	}
+
+	/// Runs this test, and returns `true` if it proves that `problem` is infeasible. `index` must be
+	/// computed from `problem`.
+	pub fn run(&self, problem: &Problem, index: &ProblemIndex) -> bool {
+		match self {
+			Self::Windows => problem.is_certainly_infeasible(),
+			Self::Load => run_feasibility_load_test_with_index(problem, index),
+			Self::Interval => run_feasibility_interval_test_with_index(problem, index),
+			Self::Resource => run_feasibility_resource_test(problem),
+		}
+	}
+}
+
+/// The measurements of a single necessary test
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct TestStatistics {
+	pub num_runs: u64,
+
+	/// The number of runs that proved infeasibility
+	pub num_detections: u64,
+
+	pub total_time: Duration,
+}
+
+impl TestStatistics {
+	/// The expected time spent on this test per detection, which is `None` when it hasn't detected
+	/// anything yet
+	fn get_time_per_detection(&self) -> Option<Duration> {
+		if self.num_detections == 0 {
+			None
+		} else {
+			Some(Duration::from_nanos((self.total_time.as_nanos() / self.num_detections as u128) as u64))
+		}
+	}
+}
+
+/// Measures the runtime and detections of each necessary test across many invocations of
+/// `strengthen_and_test_with_profile`, and uses them to run the tests in the most promising order:
+/// tests that have never run come first (so that they get measured), then the tests with the
+/// smallest time per detection, and finally the tests that never detected anything.
+///
+/// A profile can be frozen to stop collecting statistics, for instance after training it on a
+/// representative corpus.
+#[derive(Debug, Clone, Default)]
+pub struct TestProfile {
+	statistics: [TestStatistics; NecessaryTest::ALL.len()],
+	is_frozen: bool,
+}
+
+impl TestProfile {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn get_statistics(&self, test: NecessaryTest) -> TestStatistics {
+		self.statistics[test as usize]
+	}
+
+	/// Replaces the statistics of `test`, for instance by statistics that were saved by an earlier
+	/// run
+	pub fn set_statistics(&mut self, test: NecessaryTest, statistics: TestStatistics) {
+		self.statistics[test as usize] = statistics;
+	}
+
+	/// Stops updating the statistics, so that the order of the tests no longer changes
+	pub fn freeze(&mut self) {
+		self.is_frozen = true;
+	}
+
+	pub fn is_frozen(&self) -> bool {
+		self.is_frozen
+	}
+
+	/// Gets the order in which the tests should be run
+	pub fn get_order(&self) -> [NecessaryTest; NecessaryTest::ALL.len()] {
+		let mut order = NecessaryTest::ALL;
+		order.sort_by_key(|test| {
+			let statistics = self.get_statistics(*test);
+			let time_per_detection = statistics.get_time_per_detection();
+			(statistics.num_runs > 0, time_per_detection.is_none(), time_per_detection)
+		});
+		order
+	}
+
+	/// Runs the tests in the order of `get_order`, until one of them proves that `problem` is
+	/// infeasible, and records their statistics (unless this profile is frozen)
+	pub fn run_tests(&mut self, problem: &Problem, index: &ProblemIndex) -> bool {
+		for test in self.get_order() {
+			let start_time = Instant::now();
+			let is_infeasible = test.run(problem, index);
+			if !self.is_frozen {
+				let statistics = &mut self.statistics[test as usize];
+				statistics.num_runs += 1;
+				statistics.total_time += start_time.elapsed();
+				if is_infeasible {
+					statistics.num_detections += 1;
+				}
+			}
+			if is_infeasible {
+				return true;
+			}
+		}
+		false
+	}
+}
+
+impl Display for TestProfile {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for test in self.get_order() {
+			let statistics = self.get_statistics(test);
+			writeln!(
+				f, "The {} test ran {} times in {:?} and proved infeasibility {} times",
+				test.get_name(), statistics.num_runs, statistics.total_time, statistics.num_detections
+			)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::necessary::*;
+	use crate::problem::*;
+
+	#[test]
+	fn test_get_order() {
+		let mut profile = TestProfile::new();
+		assert_eq!(NecessaryTest::ALL, profile.get_order());
+
+		profile.statistics[NecessaryTest::Windows as usize] = TestStatistics {
+			num_runs: 10, num_detections: 0, total_time: Duration::from_micros(10)
+		};
+		profile.statistics[NecessaryTest::Load as usize] = TestStatistics {
+			num_runs: 10, num_detections: 5, total_time: Duration::from_micros(1000)
+		};
+		profile.statistics[NecessaryTest::Interval as usize] = TestStatistics {
+			num_runs: 5, num_detections: 5, total_time: Duration::from_micros(500)
+		};
+		assert_eq!([
+			NecessaryTest::Resource, NecessaryTest::Interval, NecessaryTest::Load, NecessaryTest::Windows
+		], profile.get_order());
+	}
+
+	#[test]
+	fn test_get_time_per_detection() {
+		let statistics = TestStatistics { num_runs: 5, num_detections: 0, total_time: Duration::from_micros(500) };
+		assert_eq!(None, statistics.get_time_per_detection());
+
+		let statistics = TestStatistics {
+			num_runs: 1 << 33, num_detections: 1 << 32, total_time: Duration::from_secs(3 << 32)
+		};
+		assert_eq!(Some(Duration::from_secs(3)), statistics.get_time_per_detection());
+	}
+
+	#[test]
+	fn test_run_tests() {
+		let infeasible = Problem {
+			jobs: vec![Job::release_to_deadline(0, 0, 10, 15), Job::release_to_deadline(1, 0, 10, 15)],
+			constraints: Vec::new(),
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let feasible = Problem { num_cores: 2, ..infeasible.clone() };
+
+		let mut profile = TestProfile::new();
+		assert!(profile.run_tests(&infeasible, &ProblemIndex::new(&infeasible)));
+		assert_eq!(1, profile.get_statistics(NecessaryTest::Windows).num_runs);
+		assert_eq!(1, profile.get_statistics(NecessaryTest::Load).num_detections);
+		assert_eq!(0, profile.get_statistics(NecessaryTest::Interval).num_runs);
+
+		// The interval and resource tests haven't run yet, so they go first
+		assert!(!profile.run_tests(&feasible, &ProblemIndex::new(&feasible)));
+		assert_eq!(NecessaryTest::Load, profile.get_order()[0]);
+		for test in NecessaryTest::ALL {
+			assert!(profile.get_statistics(test).num_runs >= 1);
+		}
+
+		profile.freeze();
+		assert!(profile.run_tests(&infeasible, &ProblemIndex::new(&infeasible)));
+		assert_eq!(1, profile.get_statistics(NecessaryTest::Load).num_detections);
+		assert!(profile.to_string().starts_with("The load test ran 2 times in "));
+	}
+}
@@ -0,0 +1,57 @@
+use crate::necessary::interval::run_feasibility_interval_test;
+use crate::necessary::load::run_feasibility_load_test;
+use crate::problem::*;
+
+/// Runs the Feasibility Resource Test and returns `true` if `problem` is certainly infeasible. When
+/// this function returns `false`, `problem` may or may not be feasible.
+///
+/// Since the jobs that share a resource can never run at the same time, they behave as if they
+/// were scheduled on a single core. This test runs the load test and the interval test on each
+/// such single-core subproblem: if any of them is certainly infeasible, so is `problem`.
+pub fn run_feasibility_resource_test(problem: &Problem) -> bool {
+	for resource in &problem.resources {
+		if resource.len() < 2 {
+			continue;
+		}
+
+		let mut subproblem = Problem {
+			jobs: resource.iter().map(|job| problem.jobs[*job]).collect(),
+			constraints: Vec::new(),
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		subproblem.update_job_indices();
+		if run_feasibility_load_test(&subproblem) || run_feasibility_interval_test(&subproblem) {
+			return true;
+		}
+	}
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::necessary::resource::run_feasibility_resource_test;
+	use crate::problem::*;
+
+	#[test]
+	fn test_shared_resource_on_multiple_cores() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 15),
+				Job::release_to_deadline(1, 5, 10, 16),
+				Job::release_to_deadline(2, 15, 10, 40),
+			],
+			constraints: vec![],
+			num_cores: 3,
+			resources: vec![vec![0, 2]],
+		};
+		problem.validate();
+
+		// Jobs 0 and 2 would fit on a single core
+		assert!(!run_feasibility_resource_test(&problem));
+
+		// But jobs 0 and 1 must both run during [5, 10)
+		problem.resources.push(vec![1, 0]);
+		assert!(run_feasibility_resource_test(&problem));
+	}
+}
@@ -1,6 +1,9 @@
+use crate::certificate::*;
 use crate::problem::*;
+use crate::simulator::Dispatch;
 use std::collections::HashMap;
 use std::fs::read_to_string;
+use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 struct SagJobID {
@@ -8,9 +11,12 @@ struct SagJobID {
 	job_id: u32,
 }
 
-fn parse_jobs(file_path: &str) -> (Vec<Job>, HashMap<SagJobID, usize>) {
-	let raw_text = read_to_string(file_path).expect("Couldn't read jobs file");
+/// Parses `value`, or returns an error that mentions `description` and the `line` that contains it
+fn parse_value<T: FromStr>(value: &str, description: &str, line: &str) -> Result<T, String> {
+	value.parse::<T>().map_err(|_| format!("Couldn't parse {} in line: {}", description, line))
+}
 
+fn parse_jobs_text(raw_text: &str) -> Result<(Vec<Job>, HashMap<SagJobID, usize>), String> {
 	let mut jobs = Vec::<Job>::new();
 	let mut id_map = HashMap::<SagJobID, usize>::new();
 
@@ -27,34 +33,94 @@ fn parse_jobs(file_path: &str) -> (Vec<Job>, HashMap<SagJobID, usize>) {
 		let latest_arrival: Time;
 		let worst_case_execution_time: Time;
 		let deadline: Time;
-
-		if string_values.len() == 8 {
-			let task_id = string_values[0].parse::<u32>().expect("Couldn't parse task ID");
-			let job_id = string_values[1].parse::<u32>().expect("Couldn't parse job ID");
-			latest_arrival = string_values[3].parse::<Time>()
-				.expect("Couldn't parse latest arrival time");
-			worst_case_execution_time = string_values[5].parse::<Time>()
-				.expect("Couldn't parse worst-case execution time");
-			deadline = string_values[6].parse::<Time>().expect("Couldn't parse deadline");
+		let mut sag_details = None;
+		let mut parallelism = 1;
+		let mut affinity = CoreSet::all();
+
+		// SAG job files have the columns `Task ID, Job ID, Arrival min, Arrival max, Cost min, Cost max,
+		// Deadline, Priority`, possibly followed by columns of extensions, which are ignored
+		if string_values.len() >= 8 {
+			let task_id = parse_value(string_values[0], "task ID", line)?;
+			let job_id = parse_value(string_values[1], "job ID", line)?;
+			let earliest_arrival = parse_value(string_values[2], "earliest arrival time", line)?;
+			latest_arrival = parse_value(string_values[3], "latest arrival time", line)?;
+			let best_case_execution_time = parse_value(string_values[4], "best-case execution time", line)?;
+			worst_case_execution_time = parse_value(string_values[5], "worst-case execution time", line)?;
+			deadline = parse_value(string_values[6], "deadline", line)?;
+			let priority = parse_value(string_values[7], "priority", line)?;
+			if earliest_arrival > latest_arrival {
+				return Err(format!("The earliest arrival time exceeds the latest arrival time in line: {}", line));
+			}
+			if best_case_execution_time < 0 || best_case_execution_time > worst_case_execution_time {
+				return Err(format!(
+					"The best-case execution time must be between 0 and the worst-case execution time in line: {}", line
+				));
+			}
+			sag_details = Some((earliest_arrival, best_case_execution_time, priority));
 			id_map.insert(SagJobID { task_id, job_id }, jobs.len());
-		} else if string_values.len() == 3 {
-			latest_arrival = string_values[0].parse::<Time>()
-				.expect("Couldn't parse latest arrival time");
-			worst_case_execution_time = string_values[1].parse::<Time>()
-				.expect("Couldn't parse worst-case execution time");
-			deadline = string_values[2].parse::<Time>().expect("Couldn't parse deadline");
+		} else if (3 ..= 5).contains(&string_values.len()) {
+			latest_arrival = parse_value(string_values[0], "latest arrival time", line)?;
+			worst_case_execution_time = parse_value(string_values[1], "worst-case execution time", line)?;
+			deadline = parse_value(string_values[2], "deadline", line)?;
+
+			// The optional fourth column is the number of cores that the job needs simultaneously
+			if string_values.len() >= 4 {
+				parallelism = parse_value(string_values[3], "parallelism", line)?;
+				if parallelism == 0 {
+					return Err(format!("The parallelism must be positive in line: {}", line));
+				}
+			}
+
+			// The optional fifth column contains the cores on which the job may run, separated by
+			// spaces. When it's empty, the job may run on all cores.
+			if string_values.len() == 5 && !string_values[4].is_empty() {
+				let cores = string_values[4].split_whitespace().map(
+					|core| parse_value(core, "affinity", line)
+				).collect::<Result<Vec<usize>, String>>()?;
+				if cores.iter().any(|core| *core >= CoreSet::MAX_NAMED_CORES) {
+					return Err(format!(
+						"Only the first {} cores can be used in an affinity, in line: {}", CoreSet::MAX_NAMED_CORES, line
+					));
+				}
+				affinity = CoreSet::from_cores(&cores);
+			}
 		} else {
-			panic!("Unexpected line in jobs file: {}", line);
+			return Err(format!("Unexpected line in jobs file: {}", line));
 		}
 
-		jobs.push(Job::release_to_deadline(jobs.len(), latest_arrival, worst_case_execution_time, deadline));
+		if worst_case_execution_time <= 0 {
+			return Err(format!("The execution time must be positive in line: {}", line));
+		}
+		if latest_arrival.checked_add(worst_case_execution_time).is_none() ||
+				deadline.checked_sub(worst_case_execution_time).is_none() {
+			return Err(format!("The times are too large in line: {}", line));
+		}
+		let mut job = Job::release_to_deadline(jobs.len(), latest_arrival, worst_case_execution_time, deadline);
+		if let Some((earliest_arrival, best_case_execution_time, priority)) = sag_details {
+			job.set_sag_details(earliest_arrival, best_case_execution_time, priority);
+		}
+		job.set_parallelism(parallelism);
+		job.set_affinity(affinity);
+		jobs.push(job);
 	}
 
-	(jobs, id_map)
+	Ok((jobs, id_map))
 }
 
-fn parse_constraints(file_path: &str, id_map: &HashMap<SagJobID, usize>) -> Vec<Constraint> {
+fn parse_jobs(file_path: &str) -> (Vec<Job>, HashMap<SagJobID, usize>) {
 	let raw_text = read_to_string(file_path).expect("Couldn't read jobs file");
+	parse_jobs_text(&raw_text).unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn parse_constraint_type(value: &str, line: &str) -> Result<ConstraintType, String> {
+	match value {
+		"f-s" => Ok(ConstraintType::FinishToStart),
+		"s-s" => Ok(ConstraintType::StartToStart),
+		_ => Err(format!("Unexpected constraint type {} in line in constraint file: {}", value, line)),
+	}
+}
+
+fn parse_constraints_text(raw_text: &str, id_map: &HashMap<SagJobID, usize>) -> Result<Vec<Constraint>, String> {
 	let mut constraints = Vec::<Constraint>::new();
 
 	let mut allow_header = true;
@@ -66,83 +132,382 @@ fn parse_constraints(file_path: &str, id_map: &HashMap<SagJobID, usize>) -> Vec<
 			if line.chars().any(|c| c != 's' && c != 'f' && c.is_alphabetic()) { continue; }
 		}
 		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		if string_values.len() < 2 {
+			return Err(format!("Unexpected line in constraint file: {}", line));
+		}
 
-		if string_values.len() < 4 || (string_values.len() == 4 && string_values[3].chars().any(|c| c == 's' || c == 'f')) {
-			let before = string_values[0].parse::<usize>()
-				.expect("Couldn't parse the index of the 'before' job of a constraint");
-			let after = string_values[1].parse::<usize>()
-				.expect("Couldn't parse the index of the 'after' job of a constraint");
+		let max_delay_column;
+		if string_values.len() < 4 || (string_values.len() <= 5 && string_values[3].chars().any(|c| c == 's' || c == 'f')) {
+			max_delay_column = 4;
+			let before = parse_value(string_values[0], "the index of the 'before' job of a constraint", line)?;
+			let after = parse_value(string_values[1], "the index of the 'after' job of a constraint", line)?;
 
 			let mut delay = 0;
 			if string_values.len() >= 3 {
-				delay = string_values[2].parse::<Time>()
-					.expect("Couldn't parse the delay of a constraint");
+				delay = parse_value(string_values[2], "the delay of a constraint", line)?;
 			}
 
 			let mut constraint_type = ConstraintType::FinishToStart;
 			if string_values.len() >= 4 {
-				if string_values[3] == "f-s" {
-					constraint_type = ConstraintType::FinishToStart;
-				} else if string_values[3] == "s-s" {
-					constraint_type = ConstraintType::StartToStart;
-				} else {
-					panic!("Unexpected constraint type {} in line in constraint file: {}", string_values[3], line);
-				}
+				constraint_type = parse_constraint_type(string_values[3], line)?;
 			}
 
 			constraints.push(Constraint::new(before, after, delay, constraint_type));
 		} else {
-			let before_task = string_values[0].parse::<u32>()
-				.expect("Couldn't parse the task ID of the 'before' job of a constraint");
-			let before_job = string_values[1].parse::<u32>()
-				.expect("Couldn't parse the job ID of the 'before' job of a constraint");
-			let before = id_map[&SagJobID { task_id: before_task, job_id: before_job }];
-
-			let after_task = string_values[2].parse::<u32>()
-				.expect("Couldn't parse the task ID of the 'after' job of a constraint");
-			let after_job = string_values[3].parse::<u32>()
-				.expect("Couldn't parse the job ID of the 'after' job of a constraint");
-			let after = id_map[&SagJobID { task_id: after_task, job_id: after_job }];
+			max_delay_column = 7;
+			let find_job = |task_id, job_id| id_map.get(&SagJobID { task_id, job_id }).copied().ok_or_else(
+				|| format!("Unknown job {}:{} in line in constraint file: {}", task_id, job_id, line)
+			);
+			let before_task = parse_value(string_values[0], "the task ID of the 'before' job of a constraint", line)?;
+			let before_job = parse_value(string_values[1], "the job ID of the 'before' job of a constraint", line)?;
+			let before = find_job(before_task, before_job)?;
+
+			let after_task = parse_value(string_values[2], "the task ID of the 'after' job of a constraint", line)?;
+			let after_job = parse_value(string_values[3], "the job ID of the 'after' job of a constraint", line)?;
+			let after = find_job(after_task, after_job)?;
 
 			let mut delay = 0;
 			if string_values.len() >= 6 {
-				delay = string_values[5].parse::<Time>()
-					.expect("Couldn't parse the delay of a constraint");
+				delay = parse_value(string_values[5], "the delay of a constraint", line)?;
 			}
 
 			let mut constraint_type = ConstraintType::FinishToStart;
 			if string_values.len() >= 7 {
-				if string_values[6] == "f-s" {
-					constraint_type = ConstraintType::FinishToStart;
-				} else if string_values[6] == "s-s" {
-					constraint_type = ConstraintType::StartToStart;
-				} else {
-					panic!("Unexpected constraint type {} in line in constraint file: {}", string_values[6], line);
-				}
+				constraint_type = parse_constraint_type(string_values[6], line)?;
 			}
 
 			constraints.push(Constraint::new(before, after, delay, constraint_type));
 		}
+
+		if let Some(max_delay) = string_values.get(max_delay_column).filter(|value| !value.is_empty()) {
+			let max_delay = parse_value(max_delay, "the maximum delay of a constraint", line)?;
+			let constraint = constraints.last_mut().unwrap();
+			if max_delay < constraint.get_delay() {
+				return Err(format!("The maximum delay of a constraint is smaller than its delay in line: {}", line));
+			}
+			constraint.set_max_delay(Some(max_delay));
+		}
+	}
+
+	Ok(constraints)
+}
+
+fn parse_constraints(file_path: &str, id_map: &HashMap<SagJobID, usize>) -> Vec<Constraint> {
+	let raw_text = read_to_string(file_path).expect("Couldn't read jobs file");
+	parse_constraints_text(&raw_text, id_map).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Parses a problem from the contents of a jobs file and (optionally) a constraints file. Unlike
+/// `parse_problem`, this doesn't panic on malformed input, and it also rejects constraints that
+/// refer to unknown jobs or have a negative delay, so the result always passes
+/// `Problem::validate`. This is meant for untrusted input, for instance from a fuzzer.
+pub fn try_parse_problem_text(
+	jobs_text: &str, constraints_text: Option<&str>, num_cores: u32
+) -> Result<Problem, String> {
+	let (jobs, id_map) = parse_jobs_text(jobs_text)?;
+	let constraints = match constraints_text {
+		Some(constraints_text) => parse_constraints_text(constraints_text, &id_map)?,
+		None => Vec::new(),
+	};
+	for constraint in &constraints {
+		if constraint.get_before() >= jobs.len() || constraint.get_after() >= jobs.len() {
+			return Err(format!("A constraint refers to a job that doesn't exist: {:?}", constraint));
+		}
+		if constraint.get_delay() < 0 {
+			return Err(format!("A constraint has a negative delay: {:?}", constraint));
+		}
+	}
+	for job in &jobs {
+		let affinity = job.get_affinity();
+		if !affinity.is_all() && (num_cores as usize .. CoreSet::MAX_NAMED_CORES).any(|core| affinity.contains(core)) {
+			return Err(format!("The affinity of job {} contains a core that doesn't exist", job.get_index()));
+		}
+	}
+	Ok(Problem { jobs, constraints, num_cores, resources: Vec::new() })
+}
+
+/// Parses a job ordering: a sequence of job indices separated by commas and/or newlines. The first
+/// line may be a header.
+pub fn parse_job_ordering(file_path: &str) -> Vec<usize> {
+	let raw_text = read_to_string(file_path).expect("Couldn't read job ordering file");
+	let mut ordering = Vec::new();
+
+	let mut allow_header = true;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		if allow_header {
+			allow_header = false;
+			if line.chars().any(|c| c.is_alphabetic()) { continue; }
+		}
+		for value in line.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+			ordering.push(value.parse::<usize>().expect("Couldn't parse job index in job ordering"));
+		}
+	}
+
+	ordering
+}
+
+/// Parses a dispatch order: each line contains the index of the next job to be dispatched, and
+/// optionally (after a comma) the time at which it should start. The first line may be a header.
+pub fn parse_dispatch_order(file_path: &str) -> Vec<Dispatch> {
+	let raw_text = read_to_string(file_path).expect("Couldn't read dispatch order file");
+	let mut order = Vec::new();
+
+	let mut allow_header = true;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		if allow_header {
+			allow_header = false;
+			if line.chars().any(|c| c.is_alphabetic()) { continue; }
+		}
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		if string_values.len() > 2 {
+			panic!("Unexpected line in dispatch order file: {}", line);
+		}
+
+		let job = string_values[0].parse::<usize>().expect("Couldn't parse job index in dispatch order");
+		let start = string_values.get(1).map(
+			|value| value.parse::<Time>().expect("Couldn't parse start time in dispatch order")
+		);
+		order.push(Dispatch { job, start });
 	}
 
-	constraints
+	order
+}
+
+/// Parses a schedule certificate: each line contains the index of a job, its start time, and its
+/// core. The first line may be a header.
+fn parse_schedule_certificate_text(raw_text: &str) -> Vec<CertifiedStart> {
+	let mut certificate = Vec::new();
+
+	let mut allow_header = true;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		if allow_header {
+			allow_header = false;
+			if line.chars().any(|c| c.is_alphabetic()) { continue; }
+		}
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		if string_values.len() != 3 {
+			panic!("Unexpected line in schedule certificate file: {}", line);
+		}
+
+		certificate.push(CertifiedStart {
+			job: string_values[0].parse::<usize>().expect("Couldn't parse job index in schedule certificate"),
+			start: string_values[1].parse::<Time>().expect("Couldn't parse start time in schedule certificate"),
+			core: string_values[2].parse::<usize>().expect("Couldn't parse core in schedule certificate"),
+		});
+	}
+
+	certificate
+}
+
+/// Parses a schedule certificate. See `parse_schedule_certificate_text`.
+pub fn parse_schedule_certificate(file_path: &str) -> Vec<CertifiedStart> {
+	parse_schedule_certificate_text(&read_to_string(file_path).expect("Couldn't read schedule certificate file"))
+}
+
+/// The kinds of lines in an infeasibility certificate file
+const INFEASIBILITY_CERTIFICATE_KINDS: [&str; 5] = [
+	"empty-window", "unsatisfiable-constraint", "cycle", "overloaded-interval", "demand"
+];
+
+/// Parses an infeasibility certificate in the format of `format_infeasibility_certificate`: each line
+/// starts with its kind, followed by the numbers of the certificate.
+fn parse_infeasibility_certificate_text(raw_text: &str) -> InfeasibilityCertificate {
+	let mut certificate = None;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		let values: Vec<Time> = string_values[1 ..].iter().map(
+			|value| value.parse::<Time>().expect("Couldn't parse number in infeasibility certificate")
+		).collect();
+		let certified_constraint = |values: &[Time]| CertifiedConstraint {
+			before: values[0] as usize, after: values[1] as usize, separation: values[2]
+		};
+
+		certificate = match (string_values[0], values.len(), certificate) {
+			("empty-window", 4, None) => Some(InfeasibilityCertificate::EmptyWindow {
+				job: values[0] as usize, release: values[1], execution_time: values[2], deadline: values[3]
+			}),
+			("unsatisfiable-constraint", 6, None) => Some(InfeasibilityCertificate::UnsatisfiableConstraint {
+				constraint: certified_constraint(&values), release: values[3], execution_time: values[4], deadline: values[5]
+			}),
+			("cycle", 3, None) => Some(InfeasibilityCertificate::Cycle(vec![certified_constraint(&values)])),
+			("cycle", 3, Some(InfeasibilityCertificate::Cycle(mut constraints))) => {
+				constraints.push(certified_constraint(&values));
+				Some(InfeasibilityCertificate::Cycle(constraints))
+			},
+			("overloaded-interval", 3, None) => Some(InfeasibilityCertificate::OverloadedInterval {
+				start: values[0], end: values[1], capacity: values[2], demands: Vec::new()
+			}),
+			("demand", 2, Some(InfeasibilityCertificate::OverloadedInterval { start, end, capacity, mut demands })) => {
+				demands.push(JobDemand { job: values[0] as usize, demand: values[1] });
+				Some(InfeasibilityCertificate::OverloadedInterval { start, end, capacity, demands })
+			},
+			_ => panic!("Unexpected line in infeasibility certificate file: {}", line),
+		};
+	}
+
+	certificate.expect("The infeasibility certificate file is empty")
+}
+
+/// Parses a certificate file, which can contain either a schedule certificate (see
+/// `parse_schedule_certificate`) or an infeasibility certificate (see
+/// `format_infeasibility_certificate`). The kind is determined by the first line.
+pub fn parse_certificate(file_path: &str) -> Certificate {
+	let raw_text = read_to_string(file_path).expect("Couldn't read certificate file");
+	let first_value = raw_text.split([',', '\n']).map(|s| s.trim()).find(|s| !s.is_empty());
+	if first_value.is_some_and(|value| INFEASIBILITY_CERTIFICATE_KINDS.contains(&value)) {
+		Certificate::Infeasibility(parse_infeasibility_certificate_text(&raw_text))
+	} else {
+		Certificate::Schedule(parse_schedule_certificate_text(&raw_text))
+	}
+}
+
+/// Parses the shared resources: each line contains the name of a resource, followed by either
+/// the index of a job, or the task ID and job ID of a job (for SAG job files). The result contains
+/// the indices of the jobs that use each resource, in order of first appearance of the resource.
+fn parse_resources(file_path: &str, id_map: &HashMap<SagJobID, usize>) -> Vec<Vec<usize>> {
+	let raw_text = read_to_string(file_path).expect("Couldn't read resources file");
+	let mut resources = Vec::<Vec<usize>>::new();
+	let mut resource_map = HashMap::<String, usize>::new();
+
+	let mut allow_header = true;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		if allow_header {
+			allow_header = false;
+			if string_values[1 ..].iter().any(|value| value.chars().any(|c| c.is_alphabetic())) { continue; }
+		}
+
+		let job = if string_values.len() == 2 {
+			string_values[1].parse::<usize>().expect("Couldn't parse the job index of a resource user")
+		} else if string_values.len() == 3 {
+			let task_id = string_values[1].parse::<u32>()
+				.expect("Couldn't parse the task ID of a resource user");
+			let job_id = string_values[2].parse::<u32>()
+				.expect("Couldn't parse the job ID of a resource user");
+			id_map[&SagJobID { task_id, job_id }]
+		} else {
+			panic!("Unexpected line in resources file: {}", line);
+		};
+
+		let resource = *resource_map.entry(string_values[0].to_string()).or_insert_with(|| {
+			resources.push(Vec::new());
+			resources.len() - 1
+		});
+		resources[resource].push(job);
+	}
+
+	resources
+}
+
+/// Parses the criticalities: each line contains either the index of a job, or the task ID and job ID
+/// of a job (for SAG job files), followed by its HI execution time. These jobs become HI jobs,
+/// whereas all other jobs remain LO jobs.
+fn parse_criticalities(file_path: &str, id_map: &HashMap<SagJobID, usize>, jobs: &mut [Job]) {
+	let raw_text = read_to_string(file_path).expect("Couldn't read criticalities file");
+
+	let mut allow_header = true;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		if allow_header {
+			allow_header = false;
+			if line.chars().any(|c| c.is_alphabetic()) { continue; }
+		}
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+
+		let job = if string_values.len() == 2 {
+			string_values[0].parse::<usize>().expect("Couldn't parse the job index of a HI job")
+		} else if string_values.len() == 3 {
+			let task_id = string_values[0].parse::<u32>().expect("Couldn't parse the task ID of a HI job");
+			let job_id = string_values[1].parse::<u32>().expect("Couldn't parse the job ID of a HI job");
+			id_map[&SagJobID { task_id, job_id }]
+		} else {
+			panic!("Unexpected line in criticalities file: {}", line);
+		};
+		let hi_execution_time = string_values[string_values.len() - 1].parse::<Time>()
+			.expect("Couldn't parse HI execution time");
+		jobs[job].set_hi_criticality(hi_execution_time);
+	}
+}
+
+/// Parses the non-preemptive segments of jobs with preemption points: each line contains the index
+/// of a job, followed by the execution times of its segments (in order). The first line may be a
+/// header.
+fn parse_segments_text(raw_text: &str) -> Result<Vec<(usize, Vec<Time>)>, String> {
+	let mut segments = Vec::new();
+
+	let mut allow_header = true;
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		if allow_header {
+			allow_header = false;
+			if line.chars().any(|c| c.is_alphabetic()) { continue; }
+		}
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		if string_values.len() < 2 {
+			return Err(format!("Expected a job and at least 1 segment in line: {}", line));
+		}
+
+		let job = parse_value::<usize>(string_values[0], "job index", line)?;
+		let mut execution_times = Vec::with_capacity(string_values.len() - 1);
+		for value in &string_values[1 ..] {
+			let execution_time = parse_value::<Time>(value, "segment execution time", line)?;
+			if execution_time <= 0 {
+				return Err(format!("Segment execution times must be positive, but got line: {}", line));
+			}
+			execution_times.push(execution_time);
+		}
+		segments.push((job, execution_times));
+	}
+
+	Ok(segments)
+}
+
+/// Parses a segments file (see `parse_segments_text`), which can be passed to
+/// `split_into_segments`
+pub fn parse_segments(file_path: &str) -> Vec<(usize, Vec<Time>)> {
+	let raw_text = read_to_string(file_path).expect("Couldn't read segments file");
+	parse_segments_text(&raw_text).unwrap_or_else(|error| panic!("{}", error))
 }
 
 pub fn parse_problem(
 	jobs_file_path: &str, constraints_file_path: Option<&str>, num_cores: u32
 ) -> Problem {
-	let (jobs, id_map) = parse_jobs(jobs_file_path);
-	if let Some(constraints_path) = constraints_file_path {
-		let constraints = parse_constraints(constraints_path, &id_map);
-		Problem { jobs, constraints, num_cores }
-	} else {
-		Problem { jobs, constraints: Vec::new(), num_cores }
+	parse_problem_with_resources(jobs_file_path, constraints_file_path, None, num_cores)
+}
+
+pub fn parse_problem_with_resources(
+	jobs_file_path: &str, constraints_file_path: Option<&str>,
+	resources_file_path: Option<&str>, num_cores: u32
+) -> Problem {
+	parse_problem_with_criticalities(jobs_file_path, constraints_file_path, resources_file_path, None, num_cores)
+}
+
+pub fn parse_problem_with_criticalities(
+	jobs_file_path: &str, constraints_file_path: Option<&str>, resources_file_path: Option<&str>,
+	criticalities_file_path: Option<&str>, num_cores: u32
+) -> Problem {
+	let (mut jobs, id_map) = parse_jobs(jobs_file_path);
+	if let Some(criticalities_path) = criticalities_file_path {
+		parse_criticalities(criticalities_path, &id_map, &mut jobs);
 	}
+	let constraints = constraints_file_path.map(
+		|constraints_path| parse_constraints(constraints_path, &id_map)
+	).unwrap_or_default();
+	let resources = resources_file_path.map(
+		|resources_path| parse_resources(resources_path, &id_map)
+	).unwrap_or_default();
+	Problem { jobs, constraints, num_cores, resources }
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::export::format_infeasibility_certificate;
 
 	#[test]
 	fn test_parse_jobs_classic() {
@@ -156,9 +521,16 @@ mod tests {
 		assert_eq!(id_map[&SagJobID { task_id: 1, job_id: 2 }], 1);
 		assert_eq!(id_map[&SagJobID { task_id: 1, job_id: 3 }], 2);
 
-		assert_eq!(Job::release_to_deadline(0, 40, 10, 100), jobs[0]);
-		assert_eq!(Job::release_to_deadline(1, 0, 20, 100), jobs[1]);
-		assert_eq!(Job::release_to_deadline(2, 75, 30, 100), jobs[2]);
+		let sag_job = |index, earliest_arrival, latest_arrival, min_execution_time, execution_time, deadline| {
+			let mut job = Job::release_to_deadline(index, latest_arrival, execution_time, deadline);
+			job.set_sag_details(earliest_arrival, min_execution_time, 1);
+			job
+		};
+		assert_eq!(sag_job(0, 0, 40, 10, 10, 100), jobs[0]);
+		assert_eq!(sag_job(1, 0, 0, 20, 20, 100), jobs[1]);
+		assert_eq!(sag_job(2, 0, 75, 0, 30, 100), jobs[2]);
+		assert_eq!(1, jobs[2].get_priority());
+		assert_eq!(0, jobs[2].get_min_execution_time());
 	}
 
 	#[test]
@@ -236,11 +608,150 @@ mod tests {
 		assert_eq!(vec![Constraint::new(0, 0, 123, ConstraintType::StartToStart)], constraints);
 	}
 
+	#[test]
+	fn test_parse_constraints_with_max_delay() {
+		let constraints = parse_constraints_text(
+			"Before, After, Delay, Type, Max Delay\n0, 1, 2, f-s, 5\n1, 2, 0, s-s,\n", &HashMap::new()
+		).unwrap();
+		let mut bounded = Constraint::new(0, 1, 2, ConstraintType::FinishToStart);
+		bounded.set_max_delay(Some(5));
+		assert_eq!(vec![bounded, Constraint::new(1, 2, 0, ConstraintType::StartToStart)], constraints);
+
+		assert!(parse_constraints_text("0, 1, 2, f-s, 1\n", &HashMap::new()).is_err());
+	}
+
+	#[test]
+	fn test_try_parse_problem_text() {
+		let problem = try_parse_problem_text("Arrival, Execution, Deadline\n0, 5, 10\n2, 3, 20\n", Some("0, 1, 4, s-s"), 2);
+		assert_eq!(Ok(vec![Constraint::new(0, 1, 4, ConstraintType::StartToStart)]), problem.map(|problem| problem.constraints));
+
+		// Columns of SAG extensions are ignored
+		let problem = try_parse_problem_text("Task ID, Job ID, Arrival min, Arrival max, Cost min, Cost max, \
+			Deadline, Priority, Affinity\n3, 1, 2, 4, 1, 5, 20, 7, 0\n", None, 1).unwrap();
+		let job = problem.jobs[0];
+		assert_eq!((2, 4, 1, 5, 20, 7), (
+			job.get_earliest_arrival(), job.earliest_start, job.get_min_execution_time(), job.get_execution_time(),
+			job.get_deadline(), job.get_priority()
+		));
+
+		// The optional fourth column is the parallelism of a gang job
+		let problem = try_parse_problem_text("Arrival, Execution, Deadline, Parallelism\n0, 5, 10, 2\n", None, 2).unwrap();
+		assert_eq!(2, problem.jobs[0].get_parallelism());
+
+		// The optional fifth column is the affinity, which is all cores when it's empty
+		let problem = try_parse_problem_text(
+			"Arrival, Execution, Deadline, Parallelism, Affinity\n0, 5, 10, 2, 0 2\n0, 5, 10, 1, \n", None, 3
+		).unwrap();
+		assert_eq!(CoreSet::from_cores(&[0, 2]), problem.jobs[0].get_affinity());
+		assert_eq!(CoreSet::all(), problem.jobs[1].get_affinity());
+
+		for (jobs_text, constraints_text) in [
+			("0, 5", None),
+			("0, 0, 10", None),
+			("0, 5, 10, 0", None),
+			("0, 5, 10, -1", None),
+			("0, 5, 10\n0, 5, 10, 1, x", None),
+			("0, 5, 10, 1, 64", None),
+			("0, 5, 10, 1, 0 1", None),
+			("0, 5, 10\n1, five, 10", None),
+			("0, 5, 10", Some("0")),
+			("0, 5, 10", Some("0, 1")),
+			("0, 5, 10", Some("0, 0, -1")),
+			("0, 5, 10", Some("0, 0, 1, s-s\n0, 0, 1, x-s")),
+			("1, 1, 0, 0, 0, 5, 10, 0", Some("1, 1, 2, 2, 0")),
+			("1, 1, 5, 0, 0, 5, 10, 0", None),
+			("1, 1, 0, 0, 6, 5, 10, 0", None),
+			("1, 1, 0, 0, 0, 5, 10, -1", None),
+			("9223372036854775807, 5, 9223372036854775807", None),
+			("0, 5, -9223372036854775808", None),
+		] {
+			assert!(try_parse_problem_text(jobs_text, constraints_text, 1).is_err(), "{} {:?}", jobs_text, constraints_text);
+		}
+	}
+
+	#[test]
+	fn test_parse_job_ordering() {
+		assert_eq!(vec![0, 2, 1], parse_job_ordering("./test-problems/feasible/1core/case1.order.csv"));
+	}
+
+	#[test]
+	fn test_parse_dispatch_order() {
+		assert_eq!(vec![
+			Dispatch { job: 0, start: None },
+			Dispatch { job: 2, start: None },
+			Dispatch { job: 1, start: None },
+		], parse_dispatch_order("./test-problems/feasible/1core/case1.order.csv"));
+		assert_eq!(vec![
+			Dispatch { job: 0, start: Some(4) },
+			Dispatch { job: 2, start: Some(11) },
+			Dispatch { job: 1, start: Some(22) },
+		], parse_dispatch_order("./test-problems/feasible/1core/case1.dispatch.csv"));
+	}
+
+	#[test]
+	fn test_parse_schedule_certificate() {
+		assert_eq!(vec![
+			CertifiedStart { job: 0, start: 4, core: 0 },
+			CertifiedStart { job: 1, start: 22, core: 0 },
+			CertifiedStart { job: 2, start: 11, core: 0 },
+		], parse_schedule_certificate("./test-problems/feasible/1core/case1.cert.csv"));
+	}
+
+	#[test]
+	fn test_parse_infeasibility_certificate() {
+		let constraint = CertifiedConstraint { before: 1, after: 2, separation: 5 };
+		for certificate in [
+			InfeasibilityCertificate::EmptyWindow { job: 1, release: 6, execution_time: 5, deadline: 10 },
+			InfeasibilityCertificate::UnsatisfiableConstraint { constraint, release: 0, execution_time: 5, deadline: 9 },
+			InfeasibilityCertificate::Cycle(vec![constraint, CertifiedConstraint { before: 2, after: 1, separation: 0 }]),
+			InfeasibilityCertificate::OverloadedInterval { start: 10, end: 15, capacity: 5, demands: vec![
+				JobDemand { job: 0, demand: 5 }, JobDemand { job: 3, demand: 1 }
+			] },
+		] {
+			assert_eq!(certificate, parse_infeasibility_certificate_text(&format_infeasibility_certificate(&certificate)));
+		}
+	}
+
+	#[test]
+	fn test_parse_resources() {
+		let problem = parse_problem_with_resources(
+			"./test-problems/feasible/1core/case1.csv", None,
+			Some("./test-problems/feasible/1core/case1.res.csv"), 2
+		);
+		assert_eq!(vec![vec![0, 2], vec![1]], problem.resources);
+		problem.validate();
+	}
+
+	#[test]
+	fn test_parse_criticalities() {
+		let problem = parse_problem_with_criticalities(
+			"./test-problems/feasible/1core/case1.csv", None, None,
+			Some("./test-problems/feasible/1core/case1.crit.csv"), 1
+		);
+		assert!(problem.is_mixed_criticality());
+		assert_eq!(Criticality::Lo, problem.jobs[0].get_criticality());
+		assert_eq!(Criticality::Hi, problem.jobs[1].get_criticality());
+		assert_eq!(3, problem.jobs[1].get_execution_time());
+		assert_eq!(6, problem.jobs[1].get_hi_execution_time());
+		assert_eq!(Criticality::Hi, problem.jobs[2].get_criticality());
+		assert_eq!(12, problem.jobs[2].get_hi_execution_time());
+	}
+
+	#[test]
+	fn test_parse_segments() {
+		let segments = parse_segments_text("Job, Segments\n2, 3, 4\n\n0, 5\n").unwrap();
+		assert_eq!(vec![(2, vec![3, 4]), (0, vec![5])], segments);
+
+		assert!(parse_segments_text("2\n").is_err());
+		assert!(parse_segments_text("2, 3, 0\n").is_err());
+		assert!(parse_segments_text("2, 3\n2, 3, x\n").is_err());
+	}
+
 	#[test]
 	fn test_parse_problem_without_constraints() {
 		let jobs_file_path = "./test-problems/infeasible/difficulty0/case1-cores1.csv";
 		let problem = parse_problem(jobs_file_path, None, 1);
-		assert_eq!(Problem { jobs: parse_jobs(jobs_file_path).0, constraints: Vec::new(), num_cores: 1 }, problem);
+		assert_eq!(Problem { jobs: parse_jobs(jobs_file_path).0, constraints: Vec::new(), num_cores: 1, resources: Vec::new() }, problem);
 	}
 
 	#[test]
@@ -251,7 +762,8 @@ mod tests {
 		assert_eq!(Problem {
 			jobs: parse_jobs(jobs_file_path).0,
 			constraints: vec![Constraint::new(0, 0, 5, ConstraintType::FinishToStart)],
-			num_cores: 12
+			num_cores: 12,
+			resources: Vec::new()
 		}, problem);
 	}
 
@@ -263,7 +775,8 @@ mod tests {
 		assert_eq!(Problem {
 			jobs: parse_jobs(jobs_file_path).0,
 			constraints: vec![Constraint::new(0, 0, 123, ConstraintType::FinishToStart)],
-			num_cores: 3
+			num_cores: 3,
+			resources: Vec::new()
 		}, problem);
 	}
 }
@@ -1,4 +1,8 @@
+use crate::infeasible_subset::InfeasibleSubset;
 use crate::problem::*;
+use crate::schedule::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 #[derive(Debug)]
 struct JobBuilder {
@@ -16,9 +20,126 @@ impl JobBuilder {
 	}
 }
 
+/// A result that refers to the jobs and/or constraints of a problem by their index, like a schedule
+/// or a witness of infeasibility. Such results can be mapped through a `ProblemPermutation`.
+pub trait Renumber {
+	/// Replaces each job index `j` by `new_job_indices[j]`, and each constraint index `c` by
+	/// `new_constraint_indices[c]`
+	fn renumber(&mut self, new_job_indices: &[usize], new_constraint_indices: &[usize]);
+}
+
+impl Renumber for Schedule {
+	fn renumber(&mut self, new_job_indices: &[usize], _new_constraint_indices: &[usize]) {
+		self.renumber_jobs(new_job_indices);
+	}
+}
+
+impl Renumber for InfeasibleSubset {
+	fn renumber(&mut self, new_job_indices: &[usize], new_constraint_indices: &[usize]) {
+		for job in &mut self.jobs {
+			*job = new_job_indices[*job];
+		}
+		self.jobs.sort();
+		for constraint in &mut self.constraints {
+			*constraint = new_constraint_indices[*constraint];
+		}
+		self.constraints.sort();
+	}
+}
+
+/// Determines which job `ProblemPermutation::possible_with_order` puts next when multiple jobs
+/// are ready (have no remaining predecessors)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ReadyJobOrder {
+	/// Take the ready job that became ready most recently (this is the fastest)
+	Arbitrary,
+	/// Take the ready job with the smallest `earliest_start`, breaking ties by index
+	EarliestStart,
+	/// Take the ready job with the smallest `latest_start`, breaking ties by index
+	LatestStart,
+}
+
+/// The jobs that are ready to be put in the permutation
+enum ReadyJobs {
+	Stack(Vec<usize>),
+	Heap(BinaryHeap<Reverse<(Time, usize)>>),
+}
+
+impl ReadyJobs {
+	fn push(&mut self, job: usize, problem: &Problem, order: ReadyJobOrder) {
+		match self {
+			ReadyJobs::Stack(stack) => stack.push(job),
+			ReadyJobs::Heap(heap) => {
+				let key = match order {
+					ReadyJobOrder::EarliestStart => problem.jobs[job].earliest_start,
+					_ => problem.jobs[job].latest_start,
+				};
+				heap.push(Reverse((key, job)));
+			},
+		}
+	}
+
+	fn pop(&mut self) -> Option<usize> {
+		match self {
+			ReadyJobs::Stack(stack) => stack.pop(),
+			ReadyJobs::Heap(heap) => heap.pop().map(|Reverse((_, job))| job),
+		}
+	}
+}
+
+/// A cycle of constraints, which proves that a problem is infeasible. It contains the indices of
+/// the constraints in the cycle, in order: the `after` job of each constraint is the `before` job of
+/// the next constraint, and the `after` job of the last constraint is the `before` job of the
+/// first constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintCycle {
+	pub constraints: Vec<usize>,
+}
+
+impl Renumber for ConstraintCycle {
+	fn renumber(&mut self, _new_job_indices: &[usize], new_constraint_indices: &[usize]) {
+		for constraint in &mut self.constraints {
+			*constraint = new_constraint_indices[*constraint];
+		}
+	}
+}
+
+impl ConstraintCycle {
+	/// Finds a cycle among the jobs `j` for which `is_stuck[j]` is `true`. Each of these jobs must
+	/// have a predecessor that is also stuck, which is the case for the jobs with remaining
+	/// predecessors after a topological sort.
+	fn find(problem: &Problem, is_stuck: &[bool]) -> ConstraintCycle {
+		let mut predecessor_constraint = vec![usize::MAX; problem.jobs.len()];
+		for (index, constraint) in problem.constraints.iter().enumerate() {
+			if is_stuck[constraint.get_before()] && is_stuck[constraint.get_after()] {
+				predecessor_constraint[constraint.get_after()] = index;
+			}
+		}
+
+		// Walk backwards along the predecessor constraints until we visit a job for the second time
+		let mut position_in_walk = vec![usize::MAX; problem.jobs.len()];
+		let mut walk = Vec::new();
+		let mut job = is_stuck.iter().position(|stuck| *stuck).expect("Couldn't find any stuck job");
+		while position_in_walk[job] == usize::MAX {
+			position_in_walk[job] = walk.len();
+			let constraint = predecessor_constraint[job];
+			walk.push(constraint);
+			job = problem.constraints[constraint].get_before();
+		}
+
+		let mut constraints = walk.split_off(position_in_walk[job]);
+		constraints.reverse();
+		ConstraintCycle { constraints }
+	}
+}
+
 /// Represents a permutation (reordering) of the jobs and constraints of a problem. Sometimes,
 /// sorting/reordering the jobs and constraints makes later analysis steps much simpler.
 /// Currently, we always use a **possible** permutation.
+///
+/// The permutation moves the job at index `j` to index `map_index(j)`, and the constraint at index
+/// `c` to index `map_constraint_index(c)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ProblemPermutation {
 	jobs: Vec<usize>,
 	constraints: Vec<usize>,
@@ -26,6 +147,102 @@ pub struct ProblemPermutation {
 
 impl ProblemPermutation {
 
+	/// Creates the permutation that doesn't move any job or constraint
+	pub fn identity(num_jobs: usize, num_constraints: usize) -> Self {
+		Self { jobs: (0 .. num_jobs).collect(), constraints: (0 .. num_constraints).collect() }
+	}
+
+	/// Gets the index to which this permutation moves the job at index `job`
+	pub fn map_index(&self, job: usize) -> usize {
+		self.jobs[job]
+	}
+
+	/// Gets the index to which this permutation moves the constraint at index `constraint`
+	pub fn map_constraint_index(&self, constraint: usize) -> usize {
+		self.constraints[constraint]
+	}
+
+	/// Creates the permutation that undoes this permutation
+	pub fn invert(&self) -> Self {
+		let invert = |mapping: &[usize]| {
+			let mut inverse = vec![0usize; mapping.len()];
+			for (index, &new_index) in mapping.iter().enumerate() {
+				inverse[new_index] = index;
+			}
+			inverse
+		};
+		Self { jobs: invert(&self.jobs), constraints: invert(&self.constraints) }
+	}
+
+	/// Creates the permutation that is equivalent to applying this permutation, followed by `next`
+	pub fn compose(&self, next: &ProblemPermutation) -> Self {
+		assert_eq!(self.jobs.len(), next.jobs.len());
+		assert_eq!(self.constraints.len(), next.constraints.len());
+		Self {
+			jobs: self.jobs.iter().map(|job| next.jobs[*job]).collect(),
+			constraints: self.constraints.iter().map(|constraint| next.constraints[*constraint]).collect(),
+		}
+	}
+
+	/// Moves all jobs and constraints of `problem` to their new position (index), and fixes all the
+	/// indices (including those in the resources)
+	pub fn apply(&self, problem: &mut Problem) {
+		assert_eq!(self.jobs.len(), problem.jobs.len());
+		assert_eq!(self.constraints.len(), problem.constraints.len());
+		problem.jobs = self.apply_to_jobs(&problem.jobs);
+		problem.update_job_indices();
+
+		let mut new_constraints = vec![Constraint::dummy(); problem.constraints.len()];
+		for (index, constraint) in problem.constraints.iter().enumerate() {
+			new_constraints[self.constraints[index]] = constraint.with_jobs(
+				self.jobs[constraint.get_before()], self.jobs[constraint.get_after()]
+			);
+		}
+		problem.constraints = new_constraints;
+
+		for resource in &mut problem.resources {
+			for job in resource.iter_mut() {
+				*job = self.jobs[*job];
+			}
+		}
+	}
+
+	/// Moves all jobs and constraints of `problem` back to the position they had before this
+	/// permutation was applied
+	pub fn unapply(&self, problem: &mut Problem) {
+		self.invert().apply(problem);
+	}
+
+	/// Reorders values that are associated with the jobs (such as bounds) in the same way as this
+	/// permutation reorders the jobs: the result contains `values[j]` at index `map_index(j)`
+	pub fn apply_to_jobs<T: Clone>(&self, values: &[T]) -> Vec<T> {
+		assert_eq!(self.jobs.len(), values.len());
+		let mut result = values.to_vec();
+		for (index, value) in values.iter().enumerate() {
+			result[self.jobs[index]] = value.clone();
+		}
+		result
+	}
+
+	/// Undoes `apply_to_jobs`: the result contains `values[map_index(j)]` at index `j`
+	pub fn unapply_to_jobs<T: Clone>(&self, values: &[T]) -> Vec<T> {
+		assert_eq!(self.jobs.len(), values.len());
+		self.jobs.iter().map(|new_index| values[*new_index].clone()).collect()
+	}
+
+	/// Maps the job and constraint indices in `value` in the same way as this permutation maps the
+	/// jobs and constraints of a problem
+	pub fn apply_to<T: Renumber>(&self, value: &mut T) {
+		value.renumber(&self.jobs, &self.constraints);
+	}
+
+	/// Maps the job and constraint indices in `value`, which must refer to the permuted problem,
+	/// back to the indices of the original problem. All results that are computed on a permuted
+	/// problem should pass through this method before they are reported to the user.
+	pub fn unapply_to<T: Renumber>(&self, value: &mut T) {
+		self.invert().apply_to(value);
+	}
+
 	fn sort_constraints(
 		problem: &Problem, builders: &mut [JobBuilder],
 		sorted_constraints: &mut [Constraint], constraint_permutation: &mut [usize]
@@ -45,10 +262,9 @@ impl ProblemPermutation {
 			}
 		}
 
-		for index in 0 .. problem.constraints.len() {
-			let constraint = problem.constraints[index];
+		for (index, constraint) in problem.constraints.iter().enumerate() {
 			let predecessor = &mut builders[constraint.get_before()];
-			sorted_constraints[predecessor.offset + predecessor.num_successors] = constraint;
+			sorted_constraints[predecessor.offset + predecessor.num_successors] = *constraint;
 			constraint_permutation[index] = predecessor.offset + predecessor.num_successors;
 			predecessor.num_successors += 1;
 		}
@@ -62,8 +278,20 @@ impl ProblemPermutation {
 	/// This is very convenient for many steps in the feasibility analysis.
 	///
 	/// When no **possible** permutation exists (so when the constraints are cyclic), this function
-	/// returns `None`.
-	pub fn possible(problem: &mut Problem) -> Option<ProblemPermutation> {
+	/// leaves `problem` unchanged, and returns one of the cycles.
+	///
+	/// The order among jobs that are not (indirectly) constrained is arbitrary. Use
+	/// `possible_with_order` to control it.
+	pub fn possible(problem: &mut Problem) -> Result<ProblemPermutation, ConstraintCycle> {
+		Self::possible_with_order(problem, ReadyJobOrder::Arbitrary)
+	}
+
+	/// Like `possible`, but whenever multiple jobs could be put next, `order` determines which one
+	/// goes first. Sorting the jobs by their start times improves the locality of later analysis
+	/// steps, and makes their output easier to read.
+	pub fn possible_with_order(
+		problem: &mut Problem, order: ReadyJobOrder
+	) -> Result<ProblemPermutation, ConstraintCycle> {
 		let mut builders: Vec<JobBuilder> = problem.jobs.iter().map(
 			|job| JobBuilder::new(job.get_index())
 		).collect();
@@ -73,15 +301,17 @@ impl ProblemPermutation {
 		Self::sort_constraints(problem, &mut builders, &mut sorted_constraints, &mut constraint_permutation);
 
 		let mut completed_jobs: Vec<usize> = Vec::with_capacity(builders.len());
-		let mut next_jobs: Vec<usize> = Vec::with_capacity(builders.len());
+		let mut next_jobs = match order {
+			ReadyJobOrder::Arbitrary => ReadyJobs::Stack(Vec::with_capacity(builders.len())),
+			_ => ReadyJobs::Heap(BinaryHeap::with_capacity(builders.len())),
+		};
 		for builder in &builders {
 			if builder.remaining_predecessors == 0 {
-				next_jobs.push(builder.job);
+				next_jobs.push(builder.job, problem, order);
 			}
 		}
 
-		while !next_jobs.is_empty() {
-			let predecessor = next_jobs.pop().unwrap();
+		while let Some(predecessor) = next_jobs.pop() {
 			let start_index = builders[predecessor].offset;
 			let bound_index = start_index + builders[predecessor].num_successors;
 
@@ -91,7 +321,7 @@ impl ProblemPermutation {
 				debug_assert!(successor.remaining_predecessors >= 0);
 
 				if successor.remaining_predecessors == 0 {
-					next_jobs.push(successor.job);
+					next_jobs.push(successor.job, problem, order);
 				}
 			}
 			completed_jobs.push(predecessor);
@@ -99,11 +329,11 @@ impl ProblemPermutation {
 
 		// If we did not encounter all jobs, the problem is cyclic
 		if completed_jobs.len() != problem.jobs.len() {
-			return None;
+			let is_stuck: Vec<bool> = builders.iter().map(|builder| builder.remaining_predecessors > 0).collect();
+			return Err(ConstraintCycle::find(problem, &is_stuck));
 		}
 
-		let mut reverse_jobs = next_jobs;
-		reverse_jobs.resize(completed_jobs.len(), 0);
+		let mut reverse_jobs = vec![0; completed_jobs.len()];
 		for index in 0 .. completed_jobs.len() {
 			reverse_jobs[completed_jobs[index]] = index;
 		}
@@ -117,14 +347,17 @@ impl ProblemPermutation {
 
 		for index in 0 .. problem.constraints.len() {
 			let old = problem.constraints[index];
-			let new = Constraint::new(
-				reverse_jobs[old.get_before()], reverse_jobs[old.get_after()],
-				old.get_delay(), old.get_type()
-			);
+			let new = old.with_jobs(reverse_jobs[old.get_before()], reverse_jobs[old.get_after()]);
 			problem.constraints[index] = new;
 			debug_assert!(new.get_before() < new.get_after());
 		}
 
+		for resource in &mut problem.resources {
+			for job in resource.iter_mut() {
+				*job = reverse_jobs[*job];
+			}
+		}
+
 		for builder in &mut builders {
 			builder.num_successors = 0;
 			builder.remaining_predecessors = 0;
@@ -134,48 +367,29 @@ impl ProblemPermutation {
 		problem.constraints = sorted_constraints;
 		debug_assert!(problem.constraints.is_sorted_by_key(|c| c.get_before()));
 
-		Some(ProblemPermutation { jobs: reverse_jobs, constraints: constraint_permutation })
+		Ok(ProblemPermutation { jobs: reverse_jobs, constraints: constraint_permutation })
 	}
 
 	/// Puts all jobs and precedence constraints back at their original position (index), and fixes
-	/// all the indices.
+	/// all the indices (including those in the resources).
 	pub fn transform_back(self, problem: &mut Problem) {
-		let mut reverse_job_mapping = vec![0usize; problem.jobs.len()];
-
-		let mut new_jobs = vec![Job::dummy(); problem.jobs.len()];
-		for original_index in 0 .. self.jobs.len() {
-			let current_index = self.jobs[original_index];
-			new_jobs[original_index] = problem.jobs[current_index];
-			reverse_job_mapping[current_index] = original_index;
-		}
-		problem.jobs = new_jobs;
-		problem.update_job_indices();
-
-		let mut new_constraints = vec![Constraint::dummy(); problem.constraints.len()];
-		for original_index in 0 .. self.constraints.len() {
-			let current_index = self.constraints[original_index];
-			let current_constraint = problem.constraints[current_index];
-			let original_before = reverse_job_mapping[current_constraint.get_before()];
-			let original_after = reverse_job_mapping[current_constraint.get_after()];
-			let original_constraint = Constraint::new(
-				original_before, original_after, current_constraint.get_delay(), current_constraint.get_type()
-			);
-			new_constraints[original_index] = original_constraint
-		}
-		problem.constraints = new_constraints;
+		self.unapply(problem);
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::parse_problem;
-	use super::ProblemPermutation;
+	use crate::infeasible_subset::InfeasibleSubset;
+	use crate::parser::parse_problem;
+	use crate::problem::*;
+	use crate::schedule::*;
+	use super::{ConstraintCycle, ProblemPermutation, ReadyJobOrder};
 
 	#[test]
 	fn sanity_check_without_precedence_constraints() {
 		let jobs_file = "./test-problems/infeasible/difficulty0/case1-cores1.csv";
 		let mut problem = parse_problem(jobs_file, None, 1);
-		assert!(ProblemPermutation::possible(&mut problem).is_some());
+		assert!(ProblemPermutation::possible(&mut problem).is_ok());
 		assert_eq!(problem.jobs.len(), 3);
 	}
 
@@ -221,11 +435,125 @@ mod tests {
 			"./test-problems/infeasible/regression/panic3-cores3.csv",
 			Some("./test-problems/infeasible/regression/panic3.prec.csv"), 3
 		);
+		problem.resources = vec![vec![2, 0], vec![1]];
 		let old_problem = problem.clone();
 		let permutation = ProblemPermutation::possible(&mut problem).unwrap();
 		assert!(problem.constraints.is_sorted_by_key(|c| c.get_before()));
+		problem.validate();
+		permutation.transform_back(&mut problem);
+
+		assert_eq!(old_problem, problem);
+	}
+
+	#[test]
+	fn test_ready_job_order() {
+		let create_problem = || Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 20, 10, 100),
+				Job::release_to_deadline(1, 10, 10, 50),
+				Job::release_to_deadline(2, 0, 10, 200),
+				Job::release_to_deadline(3, 0, 10, 60),
+			],
+			constraints: vec![Constraint::new(3, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+
+		let mut problem = create_problem();
+		let permutation = ProblemPermutation::possible_with_order(&mut problem, ReadyJobOrder::EarliestStart).unwrap();
+		assert_eq!(vec![200, 60, 50, 100], problem.jobs.iter().map(|job| job.get_deadline()).collect::<Vec<_>>());
+		permutation.transform_back(&mut problem);
+		assert_eq!(create_problem(), problem);
+
+		let permutation = ProblemPermutation::possible_with_order(&mut problem, ReadyJobOrder::LatestStart).unwrap();
+		assert_eq!(vec![60, 50, 100, 200], problem.jobs.iter().map(|job| job.get_deadline()).collect::<Vec<_>>());
 		permutation.transform_back(&mut problem);
+		assert_eq!(create_problem(), problem);
+	}
+
+	#[test]
+	fn test_apply_invert_and_compose() {
+		let original = parse_problem(
+			"./test-problems/feasible/1core/case1.csv", Some("./test-problems/feasible/1core/case1.prec.csv"), 1
+		);
+		let mut problem = original.clone();
+		let permutation = ProblemPermutation::possible(&mut problem).unwrap();
+		let permuted = problem.clone();
+
+		assert_eq!(1, permutation.map_index(2));
+		assert_eq!(2, permutation.map_index(1));
+		assert_eq!(original.jobs[2].get_execution_time(), permuted.jobs[permutation.map_index(2)].get_execution_time());
+
+		permutation.unapply(&mut problem);
+		assert_eq!(original, problem);
+		permutation.apply(&mut problem);
+		assert_eq!(permuted, problem);
+		permutation.invert().apply(&mut problem);
+		assert_eq!(original, problem);
+
+		let identity = ProblemPermutation::identity(3, 2);
+		assert_eq!(identity, permutation.compose(&permutation.invert()));
+		assert_eq!(permutation, identity.compose(&permutation));
+		permutation.compose(&permutation).apply(&mut problem);
+		permutation.apply(&mut problem);
+		permutation.invert().compose(&permutation.invert()).apply(&mut problem);
+		assert_eq!(permuted, problem);
+	}
+
+	#[test]
+	fn test_apply_to_external_values() {
+		let mut problem = parse_problem(
+			"./test-problems/feasible/1core/case1.csv", Some("./test-problems/feasible/1core/case1.prec.csv"), 1
+		);
+		let permutation = ProblemPermutation::possible(&mut problem).unwrap();
+		assert_eq!(vec!['a', 'c', 'b'], permutation.apply_to_jobs(&['a', 'b', 'c']));
+		assert_eq!(vec!['a', 'b', 'c'], permutation.unapply_to_jobs(&['a', 'c', 'b']));
+
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 1, start: 11, finish: 20, core: 0 });
+		permutation.unapply_to(&mut schedule);
+		assert_eq!(2, schedule.get_jobs()[0].job);
+
+		let mut subset = InfeasibleSubset { jobs: vec![0, 2], constraints: vec![0, 1] };
+		permutation.unapply_to(&mut subset);
+		assert_eq!(vec![0, 1], subset.jobs);
+		assert_eq!(vec![0, 1], subset.constraints);
+		permutation.apply_to(&mut subset);
+		assert_eq!(vec![0, 2], subset.jobs);
+
+		let mut cycle = ConstraintCycle { constraints: vec![1, 0] };
+		permutation.unapply_to(&mut cycle);
+		assert_eq!(vec![permutation.invert().map_constraint_index(1), permutation.invert().map_constraint_index(0)], cycle.constraints);
+	}
 
+	#[test]
+	fn test_find_cycle() {
+		let mut problem = Problem {
+			jobs: (0 .. 5).map(|index| Job::release_to_deadline(index, 0, 10, 100)).collect(),
+			constraints: vec![
+				Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+				Constraint::new(1, 2, 0, ConstraintType::FinishToStart),
+				Constraint::new(3, 1, 0, ConstraintType::StartToStart),
+				Constraint::new(2, 3, 0, ConstraintType::FinishToStart),
+				Constraint::new(3, 4, 0, ConstraintType::FinishToStart),
+			],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let old_problem = problem.clone();
+
+		let cycle = ProblemPermutation::possible(&mut problem).unwrap_err();
 		assert_eq!(old_problem, problem);
+		assert_eq!(3, cycle.constraints.len());
+		for (position, &index) in cycle.constraints.iter().enumerate() {
+			let next_index = cycle.constraints[(position + 1) % cycle.constraints.len()];
+			assert_eq!(problem.constraints[index].get_after(), problem.constraints[next_index].get_before());
+		}
+		let mut sorted_constraints = cycle.constraints.clone();
+		sorted_constraints.sort();
+		assert_eq!(vec![1, 2, 3], sorted_constraints);
+
+		problem.constraints = vec![Constraint::new(4, 4, 0, ConstraintType::StartToStart)];
+		assert_eq!(Err(ConstraintCycle { constraints: vec![0] }), ProblemPermutation::possible(&mut problem).map(|_| ()));
 	}
 }
@@ -1,11 +1,118 @@
+use std::collections::HashMap;
+
+use crate::index_set::SparseIndexSet;
+
 pub type Time = i64;
 
+/// Computes the greatest common divisor of `a` and `b`, which is always non-negative
+pub fn gcd(a: Time, b: Time) -> Time {
+	let (mut a, mut b) = (a.abs(), b.abs());
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+/// The criticality level of a job, in a dual-criticality system. The system starts in LO mode, in
+/// which all jobs run, and no job exceeds its LO execution time. When a HI job exceeds its LO
+/// execution time, the system switches to HI mode, in which only the HI jobs run, and each of them
+/// may need its (larger) HI execution time.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum Criticality {
+	Lo,
+	Hi,
+}
+
+impl Criticality {
+	pub fn get_name(&self) -> &'static str {
+		match self {
+			Criticality::Lo => "LO",
+			Criticality::Hi => "HI",
+		}
+	}
+}
+
+/// A set of cores, which is used to restrict the cores on which a job may run. Only the first
+/// `CoreSet::MAX_NAMED_CORES` cores can be named individually: the set of all cores (the default)
+/// also contains every core beyond those.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct CoreSet {
+	mask: u64,
+}
+
+impl CoreSet {
+	pub const MAX_NAMED_CORES: usize = 64;
+
+	pub fn all() -> Self {
+		Self { mask: u64::MAX }
+	}
+
+	/// Creates the set of the given cores, which must all be smaller than `MAX_NAMED_CORES`
+	pub fn from_cores(cores: &[usize]) -> Self {
+		let mut mask = 0;
+		for &core in cores {
+			assert!(core < Self::MAX_NAMED_CORES, "Core {} can't be named in a core set", core);
+			mask |= 1 << core;
+		}
+		Self { mask }
+	}
+
+	pub fn is_all(&self) -> bool {
+		self.mask == u64::MAX
+	}
+
+	pub fn contains(&self, core: usize) -> bool {
+		if core < Self::MAX_NAMED_CORES { self.mask & (1 << core) != 0 } else { self.is_all() }
+	}
+
+	/// Checks whether every core of this set is also in `other`
+	pub fn is_subset_of(&self, other: CoreSet) -> bool {
+		self.mask & !other.mask == 0
+	}
+
+	/// Gets the cores of this set that are smaller than `num_cores`, in increasing order
+	pub fn get_cores(&self, num_cores: u32) -> impl Iterator<Item = usize> + '_ {
+		(0 .. num_cores as usize).filter(|core| self.contains(*core))
+	}
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Job {
 	index: usize,
 	execution_time: Time,
 	pub earliest_start: Time,
 	pub latest_start: Time,
+
+	/// The original deadline of this job. Unlike `latest_start`, this is never strengthened, so it
+	/// can be used to report the slack of a job relative to the deadline of the user.
+	deadline: Time,
+
+	criticality: Criticality,
+
+	/// The execution time of this job in HI mode, which is equal to `execution_time` for LO jobs
+	hi_execution_time: Time,
+
+	/// The earliest arrival time and best-case execution time of a SAG job file. Since the arrival
+	/// and execution time of a job are at most `earliest_start` and `execution_time`, they don't
+	/// influence the feasibility, but they are kept for tools that need them. The simulator uses
+	/// the best-case execution time to check that maximum delays hold when jobs finish early.
+	earliest_arrival: Time,
+	min_execution_time: Time,
+
+	/// The priority of this job in a SAG job file, where a lower value means a higher priority. This
+	/// is 0 for jobs from other files.
+	priority: u32,
+
+	/// The number of cores that this (gang) job needs simultaneously: it occupies all of them from
+	/// its start time until its finish time. This is 1 for ordinary jobs.
+	parallelism: u32,
+
+	/// The cores on which this job may run, which are all cores unless the job is pinned to a
+	/// partition or cluster. Besides the simulator and the schedule validation, the occupation
+	/// bound and the interval test respect them: they also check the capacity of each group of
+	/// cores in `Problem::get_affinity_groups`. The other necessary tests ignore affinities, which
+	/// is a relaxation.
+	affinity: CoreSet,
 }
 
 impl Job {
@@ -17,18 +124,104 @@ impl Job {
 			index,
 			execution_time,
 			earliest_start: release_time,
-			latest_start: deadline - execution_time
+			latest_start: deadline - execution_time,
+			deadline,
+			criticality: Criticality::Lo,
+			hi_execution_time: execution_time,
+			earliest_arrival: release_time,
+			min_execution_time: execution_time,
+			priority: 0,
+			parallelism: 1,
+			affinity: CoreSet::all(),
 		}
 	}
 
 	pub fn dummy() -> Job {
-		Job { index: 0, execution_time: 1, earliest_start: 0, latest_start: 0 }
+		Job {
+			index: 0, execution_time: 1, earliest_start: 0, latest_start: 0, deadline: 1,
+			criticality: Criticality::Lo, hi_execution_time: 1, earliest_arrival: 0, min_execution_time: 1, priority: 0,
+			parallelism: 1, affinity: CoreSet::all()
+		}
 	}
 
 	pub fn get_index(&self) -> usize { self.index }
 
 	pub fn get_execution_time(&self) -> Time { self.execution_time }
 
+	pub fn get_deadline(&self) -> Time { self.deadline }
+
+	/// Changes the (LO) execution time of this job, while keeping its latest finish time and
+	/// deadline. The HI execution time of a HI job is raised if it would be smaller.
+	pub fn set_execution_time(&mut self, execution_time: Time) {
+		assert!(execution_time > 0);
+		self.latest_start += self.execution_time - execution_time;
+		self.execution_time = execution_time;
+		self.min_execution_time = Time::min(self.min_execution_time, execution_time);
+		if self.criticality == Criticality::Lo {
+			self.hi_execution_time = execution_time;
+		} else {
+			self.hi_execution_time = Time::max(self.hi_execution_time, execution_time);
+		}
+	}
+
+	pub fn get_criticality(&self) -> Criticality { self.criticality }
+
+	pub fn get_hi_execution_time(&self) -> Time { self.hi_execution_time }
+
+	/// Turns this job into a HI job, which needs `hi_execution_time` in HI mode. This must be at
+	/// least its (LO) execution time.
+	pub fn set_hi_criticality(&mut self, hi_execution_time: Time) {
+		assert!(hi_execution_time >= self.execution_time);
+		self.criticality = Criticality::Hi;
+		self.hi_execution_time = hi_execution_time;
+	}
+
+	pub fn get_earliest_arrival(&self) -> Time { self.earliest_arrival }
+
+	pub fn get_min_execution_time(&self) -> Time { self.min_execution_time }
+
+	pub fn get_priority(&self) -> u32 { self.priority }
+
+	/// Gets a copy of this job whose execution time is its best-case execution time, which can be 0,
+	/// and whose latest start time is unbounded. This copy is only suitable for simulating the best
+	/// case, in which the deadlines are not checked: the (worst-case) execution times determine
+	/// whether they are met.
+	pub fn get_best_case(&self) -> Job {
+		Job { execution_time: self.min_execution_time, latest_start: Time::MAX, ..*self }
+	}
+
+	/// Sets the columns of a SAG job file that don't influence the feasibility. The earliest arrival
+	/// can't exceed the release time, and the best-case execution time must be between 0 and the
+	/// (worst-case) execution time.
+	pub fn set_sag_details(&mut self, earliest_arrival: Time, min_execution_time: Time, priority: u32) {
+		assert!(earliest_arrival <= self.earliest_start);
+		assert!(0 <= min_execution_time && min_execution_time <= self.execution_time);
+		self.earliest_arrival = earliest_arrival;
+		self.min_execution_time = min_execution_time;
+		self.priority = priority;
+	}
+
+	pub fn get_parallelism(&self) -> u32 { self.parallelism }
+
+	/// Turns this job into a gang job that needs `parallelism` cores at the same time
+	pub fn set_parallelism(&mut self, parallelism: u32) {
+		assert!(parallelism >= 1);
+		self.parallelism = parallelism;
+	}
+
+	pub fn get_affinity(&self) -> CoreSet { self.affinity }
+
+	/// Restricts the cores on which this job may run to `affinity`
+	pub fn set_affinity(&mut self, affinity: CoreSet) {
+		self.affinity = affinity;
+	}
+
+	/// Gets the slack of this job when it would start at `start_time`: the time between its finish
+	/// time and its original deadline
+	pub fn get_slack(&self, start_time: Time) -> Time {
+		self.deadline - start_time - self.execution_time
+	}
+
 	pub fn get_earliest_finish(&self) -> Time {
 		self.earliest_start + self.execution_time
 	}
@@ -48,9 +241,27 @@ impl Job {
 	pub fn is_certainly_infeasible(&self) -> bool {
 		self.earliest_start > self.latest_start
 	}
+
+	/// Adds `offset` to the release time, bounds, and deadline of this job
+	pub fn shift_times(&mut self, offset: Time) {
+		for time in [&mut self.earliest_start, &mut self.latest_start, &mut self.deadline, &mut self.earliest_arrival] {
+			*time += offset;
+		}
+	}
+
+	/// Divides all times of this job by `divisor`, which must divide all of them
+	pub fn divide_times(&mut self, divisor: Time) {
+		for time in [
+			&mut self.execution_time, &mut self.earliest_start, &mut self.latest_start,
+			&mut self.deadline, &mut self.hi_execution_time, &mut self.earliest_arrival, &mut self.min_execution_time
+		] {
+			debug_assert_eq!(0, *time % divisor);
+			*time /= divisor;
+		}
+	}
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ConstraintType {
 	StartToStart,
 	FinishToStart,
@@ -62,15 +273,25 @@ pub struct Constraint {
 	after: usize,
 	constraint_type: ConstraintType,
 	delay: Time,
+
+	/// The largest time between the start (start-to-start) or finish (finish-to-start) of the
+	/// `before` job and the start of the `after` job, if the scheduler enforces one. This models
+	/// suspensions with a bounded length, rather than just a minimum separation.
+	max_delay: Option<Time>,
 }
 
 impl Constraint {
 	pub fn new(before: usize, after: usize, delay: Time, constraint_type: ConstraintType) -> Constraint {
-		Constraint { before, after, constraint_type, delay }
+		Constraint { before, after, constraint_type, delay, max_delay: None }
 	}
 
 	pub fn dummy() -> Constraint {
-		Constraint { before: 0, after: 0, constraint_type: ConstraintType::StartToStart, delay: 0 }
+		Constraint { before: 0, after: 0, constraint_type: ConstraintType::StartToStart, delay: 0, max_delay: None }
+	}
+
+	/// Creates a copy of this constraint between the jobs `before` and `after`
+	pub fn with_jobs(&self, before: usize, after: usize) -> Constraint {
+		Constraint { before, after, ..*self }
 	}
 
 	pub fn get_before(&self) -> usize { self.before }
@@ -80,6 +301,24 @@ impl Constraint {
 	pub fn get_type(&self) -> ConstraintType { self.constraint_type }
 
 	pub fn get_delay(&self) -> Time { self.delay }
+
+	pub fn get_max_delay(&self) -> Option<Time> { self.max_delay }
+
+	/// Limits the time between the `before` job and the start of the `after` job to `max_delay`
+	/// (see `get_max_delay`), which must be at least the (minimum) delay
+	pub fn set_max_delay(&mut self, max_delay: Option<Time>) {
+		assert!(max_delay.is_none_or(|max_delay| max_delay >= self.delay));
+		self.max_delay = max_delay;
+	}
+
+	/// Gets the time between the start of the `before` job and the start of the `after` job that
+	/// the `max_delay` allows, or `None` when it doesn't have a maximum delay
+	pub fn get_max_separation(&self, before_execution_time: Time) -> Option<Time> {
+		self.max_delay.map(|max_delay| match self.constraint_type {
+			ConstraintType::StartToStart => max_delay,
+			ConstraintType::FinishToStart => max_delay + before_execution_time,
+		})
+	}
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -87,6 +326,10 @@ pub struct Problem {
 	pub jobs: Vec<Job>,
 	pub constraints: Vec<Constraint>,
 	pub num_cores: u32,
+
+	/// `resources[r]` contains the indices of the jobs that need exclusive access to shared
+	/// resource `r`: no two of these jobs can run at the same time, not even on different cores.
+	pub resources: Vec<Vec<usize>>,
 }
 
 impl Problem {
@@ -95,9 +338,15 @@ impl Problem {
 	/// - `jobs[index].index = index` for all `0 <= index < jobs.len()`
 	/// - `c.delay >= 0` for all constraints `c`
 	/// - `c.before < jobs.len() && c.after < jobs.len()` for all constraints `c`
+	/// - every resource contains only existing jobs, and each of them at most once
+	/// - the affinity of every job is either all cores, or contains only existing cores
 	pub fn validate(&self) {
 		for (index, job) in self.jobs.iter().enumerate() {
 			assert_eq!(job.get_index(), index);
+			assert!(
+				job.affinity.is_all() || (self.num_cores as usize .. CoreSet::MAX_NAMED_CORES).all(|core| !job.affinity.contains(core)),
+				"The affinity of job {} contains cores that don't exist", index
+			);
 		}
 
 		for constraint in &self.constraints {
@@ -105,11 +354,162 @@ impl Problem {
 			assert!(constraint.get_before() < self.jobs.len());
 			assert!(constraint.get_after() < self.jobs.len());
 		}
+
+		let mut uses_resource = SparseIndexSet::new(self.jobs.len());
+		for resource in &self.resources {
+			uses_resource.clear();
+			for &job in resource {
+				assert!(job < self.jobs.len());
+				assert!(uses_resource.insert(job));
+			}
+		}
 	}
 
-	/// A very simple sufficient test that checks whether this problem is certainly infeasible.
+	/// A very simple sufficient test that checks whether this problem is certainly infeasible: when
+	/// the window of a job is too small, or when a job needs more cores than its affinity allows.
 	pub fn is_certainly_infeasible(&self) -> bool {
-		self.jobs.iter().any(|j| j.is_certainly_infeasible())
+		self.jobs.iter().any(|j| j.is_certainly_infeasible() || j.parallelism > self.num_cores || (
+			!j.affinity.is_all() && j.affinity.get_cores(self.num_cores).count() < j.parallelism as usize
+		))
+	}
+
+	/// Checks whether any job of this problem needs more than 1 core
+	pub fn has_parallel_jobs(&self) -> bool {
+		self.jobs.iter().any(|job| job.parallelism > 1)
+	}
+
+	/// Checks whether any job of this problem can't run on all cores
+	pub fn has_affinities(&self) -> bool {
+		self.jobs.iter().any(|job| !job.affinity.is_all())
+	}
+
+	/// Gets the distinct affinities of the jobs that can't run on all cores, in increasing order. The
+	/// jobs whose affinity is a subset of such a group can only run on its cores, so the necessary
+	/// analyses can treat each group as a smaller platform with only those jobs.
+	pub fn get_affinity_groups(&self) -> Vec<CoreSet> {
+		let mut groups: Vec<CoreSet> = self.jobs.iter().map(|job| job.affinity).filter(|affinity| !affinity.is_all()).collect();
+		groups.sort_unstable();
+		groups.dedup();
+		groups
+	}
+
+	/// Checks whether any job of this problem can finish before its (worst-case) execution time
+	pub fn has_execution_time_variation(&self) -> bool {
+		self.jobs.iter().any(|job| job.min_execution_time < job.execution_time)
+	}
+
+	/// Checks whether any constraint of this problem has a maximum delay. Analyses that start every
+	/// job as early as possible (like the simulator) may miss the feasible schedules of such
+	/// problems, since starting a predecessor early can exceed the maximum delay.
+	pub fn has_max_delays(&self) -> bool {
+		self.constraints.iter().any(|constraint| constraint.max_delay.is_some())
+	}
+
+	/// Creates a copy of this problem that contains only the jobs `j` for which `keep_jobs[j]` is
+	/// `true`, and only the constraints `c` for which `keep_constraints[c]` is `true` and whose jobs
+	/// are both kept. The remaining jobs are renumbered, but keep their relative order.
+	pub fn get_subproblem(&self, keep_jobs: &[bool], keep_constraints: &[bool]) -> Problem {
+		let mut new_indices = vec![usize::MAX; self.jobs.len()];
+		let mut jobs = Vec::new();
+		for (index, job) in self.jobs.iter().enumerate() {
+			if keep_jobs[index] {
+				new_indices[index] = jobs.len();
+				jobs.push(*job);
+			}
+		}
+
+		let constraints = self.constraints.iter().enumerate().filter(
+			|(index, c)| keep_constraints[*index] && keep_jobs[c.before] && keep_jobs[c.after]
+		).map(|(_, c)| Constraint {
+			before: new_indices[c.before],
+			after: new_indices[c.after],
+			..*c
+		}).collect();
+
+		let resources = self.resources.iter().map(
+			|resource| resource.iter().filter(|job| keep_jobs[**job]).map(|job| new_indices[*job]).collect()
+		).collect();
+
+		let mut subproblem = Problem { jobs, constraints, num_cores: self.num_cores, resources };
+		subproblem.update_job_indices();
+		subproblem
+	}
+
+	/// Creates the problem that consists of `num_hyperperiods` consecutive copies of this problem,
+	/// which should contain the jobs of periodic tasks in a single hyperperiod of length
+	/// `hyperperiod`. Job `j` of copy `k` gets index `k * jobs.len() + j`, and all its times are
+	/// shifted by `k * hyperperiod`. Each copy gets its own constraints, and shares the resources.
+	///
+	/// Jobs near the end of a hyperperiod can interfere with the jobs of the next hyperperiod, which
+	/// an analysis of a single hyperperiod ignores. When the unrolled problem is infeasible, so is
+	/// the steady state of the tasks. The converse doesn't hold: the first copy doesn't suffer from
+	/// the interference of an earlier hyperperiod.
+	///
+	/// Returns an error when the times of the last copy would not fit in a `Time`.
+	pub fn unroll_hyperperiods(&self, hyperperiod: Time, num_hyperperiods: usize) -> Result<Problem, String> {
+		assert!(hyperperiod > 0, "The hyperperiod must be positive");
+		let largest_time = self.jobs.iter().map(|job| Time::max(job.deadline, job.latest_start)).max().unwrap_or(0);
+		let largest_unrolled_time = Time::try_from(num_hyperperiods.saturating_sub(1)).ok().and_then(
+			|num_shifts| num_shifts.checked_mul(hyperperiod)
+		).and_then(|largest_offset| largest_offset.checked_add(largest_time));
+		if largest_unrolled_time.is_none() {
+			return Err(format!(
+				"The times of {} hyperperiods of length {} are too large to be represented", num_hyperperiods, hyperperiod
+			));
+		}
+
+		let num_jobs = self.jobs.len();
+		let mut unrolled = Problem {
+			jobs: Vec::with_capacity(num_hyperperiods * num_jobs),
+			constraints: Vec::with_capacity(num_hyperperiods * self.constraints.len()),
+			num_cores: self.num_cores,
+			resources: vec![Vec::new(); self.resources.len()],
+		};
+		for copy in 0 .. num_hyperperiods {
+			let offset = copy * num_jobs;
+			unrolled.jobs.extend(self.jobs.iter().map(|job| {
+				let mut shifted = *job;
+				shifted.shift_times(copy as Time * hyperperiod);
+				shifted
+			}));
+			unrolled.constraints.extend(self.constraints.iter().map(|c| Constraint {
+				before: c.before + offset,
+				after: c.after + offset,
+				..*c
+			}));
+			for (users, copied_users) in self.resources.iter().zip(&mut unrolled.resources) {
+				copied_users.extend(users.iter().map(|job| job + offset));
+			}
+		}
+		unrolled.update_job_indices();
+		Ok(unrolled)
+	}
+
+	/// Checks whether any job of this problem is a HI job
+	pub fn is_mixed_criticality(&self) -> bool {
+		self.jobs.iter().any(|job| job.criticality == Criticality::Hi)
+	}
+
+	/// Gets the indices of the jobs that run in the given mode: all jobs in LO mode, and only the HI
+	/// jobs in HI mode
+	pub fn get_mode_jobs(&self, mode: Criticality) -> Vec<usize> {
+		(0 .. self.jobs.len()).filter(|job| self.jobs[*job].criticality >= mode).collect()
+	}
+
+	/// Creates the problem that needs to be solved in the given mode. In LO mode, this is simply a
+	/// copy of this problem. In HI mode, it contains only the HI jobs (see `get_mode_jobs`), which
+	/// are renumbered, and whose execution times are their HI execution times. Constraints and
+	/// resources involving LO jobs are dropped in HI mode.
+	pub fn get_mode_problem(&self, mode: Criticality) -> Problem {
+		if mode == Criticality::Lo {
+			return self.clone();
+		}
+		let keep_jobs: Vec<bool> = self.jobs.iter().map(|job| job.criticality >= mode).collect();
+		let mut mode_problem = self.get_subproblem(&keep_jobs, &vec![true; self.constraints.len()]);
+		for job in &mut mode_problem.jobs {
+			job.set_execution_time(job.hi_execution_time);
+		}
+		mode_problem
 	}
 
 	/// Changes `jobs[i].index` to `i`, for all `0 <= i < jobs.len()`
@@ -124,11 +524,167 @@ impl Problem {
 	pub fn is_job_order_possible(&self) -> bool {
 		self.constraints.iter().all(|constraint| constraint.before < constraint.after)
 	}
+
+	/// Checks whether `prefix` (a sequence of job indices) can be the start of a dispatch order:
+	/// every job must occur at most once, and all predecessors of each job must occur before it.
+	///
+	/// Returns the position (in `prefix`) of the first job that violates this, if any.
+	pub fn check_dispatch_prefix(&self, prefix: &[usize]) -> Result<(), usize> {
+		let mut positions: Vec<Option<usize>> = vec![None; self.jobs.len()];
+		for (position, &job) in prefix.iter().enumerate() {
+			if job >= self.jobs.len() || positions[job].is_some() {
+				return Err(position);
+			}
+			positions[job] = Some(position);
+		}
+
+		let first_violation = self.constraints.iter().filter_map(|constraint| {
+			let after_position = positions[constraint.after]?;
+			match positions[constraint.before] {
+				Some(before_position) if before_position < after_position => None,
+				_ => Some(after_position),
+			}
+		}).min();
+
+		match first_violation {
+			Some(position) => Err(position),
+			None => Ok(()),
+		}
+	}
+
+	/// Merges all constraints with the same `before` job, `after` job, and type into a single
+	/// constraint with the largest delay, since the other constraints are implied by that one. The
+	/// order of the remaining constraints is preserved. Returns the number of removed constraints.
+	pub fn merge_duplicate_constraints(&mut self) -> usize {
+		let mut merged_constraints: Vec<Constraint> = Vec::with_capacity(self.constraints.len());
+		let mut indices = HashMap::new();
+		for constraint in &self.constraints {
+			let key = (constraint.before, constraint.after, constraint.constraint_type);
+			if let Some(&index) = indices.get(&key) {
+				let merged: &mut Constraint = &mut merged_constraints[index];
+				merged.delay = Time::max(merged.delay, constraint.delay);
+				merged.max_delay = match (merged.max_delay, constraint.max_delay) {
+					(Some(first), Some(second)) => Some(Time::min(first, second)),
+					(first, second) => first.or(second),
+				};
+			} else {
+				indices.insert(key, merged_constraints.len());
+				merged_constraints.push(*constraint);
+			}
+		}
+
+		let num_removed_constraints = self.constraints.len() - merged_constraints.len();
+		self.constraints = merged_constraints;
+		num_removed_constraints
+	}
+
+	/// Finds a constraint that can't be satisfied within the windows of its jobs: even when its
+	/// `before` job starts as early as possible, its `after` job would have to start after its
+	/// `latest_start`. Likewise, a constraint with a maximum delay can't be satisfied when its
+	/// `after` job can't start early enough after its `before` job starts as late as possible, or
+	/// when its maximum delay became smaller than its delay by merging constraints. Such a
+	/// constraint is a witness that this problem is infeasible.
+	///
+	/// Returns the index of the first such constraint, if any.
+	pub fn find_contradictory_constraint(&self) -> Option<usize> {
+		self.constraints.iter().position(|constraint| {
+			let before = &self.jobs[constraint.before];
+			let after = &self.jobs[constraint.after];
+			let mut earliest_ready_time = before.earliest_start + constraint.delay;
+			if constraint.constraint_type == ConstraintType::FinishToStart {
+				earliest_ready_time += before.execution_time;
+			}
+			let has_empty_range = constraint.max_delay.is_some_and(|max_delay| max_delay < constraint.delay);
+			earliest_ready_time > after.latest_start || has_empty_range || constraint.get_max_separation(before.execution_time).is_some_and(
+				|max_separation| after.earliest_start > before.latest_start + max_separation
+			)
+		})
+	}
+
+	/// Gets the smallest and largest time between the start of job `first` and the start of job
+	/// `second` that `constraint`, which must be between these jobs, allows. Unbounded sides are
+	/// `Time::MIN` or `Time::MAX`.
+	fn get_start_separation_range(&self, constraint: &Constraint, first: usize) -> (Time, Time) {
+		let before_execution_time = self.jobs[constraint.before].execution_time;
+		let mut min_separation = constraint.delay;
+		if constraint.constraint_type == ConstraintType::FinishToStart {
+			min_separation += before_execution_time;
+		}
+		let max_separation = constraint.get_max_separation(before_execution_time).unwrap_or(Time::MAX);
+		if constraint.before == first {
+			(min_separation, max_separation)
+		} else {
+			(max_separation.saturating_neg(), -min_separation)
+		}
+	}
+
+	/// Finds two constraints between the same pair of jobs that can't be satisfied together, even
+	/// though each of them could be satisfied on its own. For instance, a finish-to-start constraint
+	/// from job `a` to job `b` contradicts a start-to-start constraint from job `b` to job `a`, and a
+	/// maximum delay contradicts a constraint of the other type whose minimum separation is larger.
+	/// Such a pair is a witness that this problem is infeasible. Since each bound of the separation
+	/// comes from one of the constraints, the windows of the jobs are already taken into account
+	/// by `find_contradictory_constraint`.
+	///
+	/// This is meant to run after `merge_duplicate_constraints`, which leaves at most 4
+	/// constraints between each pair of jobs. Returns the indices of the first such pair, if any.
+	pub fn find_contradictory_constraint_pair(&self) -> Option<(usize, usize)> {
+		let mut pair_constraints: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+		for (index, constraint) in self.constraints.iter().enumerate() {
+			if constraint.before == constraint.after {
+				continue;
+			}
+			let pair = (usize::min(constraint.before, constraint.after), usize::max(constraint.before, constraint.after));
+			let earlier_constraints = pair_constraints.entry(pair).or_default();
+			for &earlier in earlier_constraints.iter() {
+				let (earlier_min, earlier_max) = self.get_start_separation_range(&self.constraints[earlier], pair.0);
+				let (min_separation, max_separation) = self.get_start_separation_range(constraint, pair.0);
+				if Time::max(earlier_min, min_separation) > Time::min(earlier_max, max_separation) {
+					return Some((earlier, index));
+				}
+			}
+			earlier_constraints.push(index);
+		}
+		None
+	}
+
+	/// Computes the greatest common divisor of all times in this problem: the execution times,
+	/// bounds, and deadlines of all jobs, and the (maximum) delays of all constraints. Returns 1 when this
+	/// problem has no jobs.
+	pub fn get_time_gcd(&self) -> Time {
+		let job_times = self.jobs.iter().flat_map(
+			|job| [
+				job.execution_time, job.earliest_start, job.latest_start, job.deadline, job.hi_execution_time,
+				job.earliest_arrival, job.min_execution_time
+			]
+		);
+		let delays = self.constraints.iter().flat_map(|constraint| [Some(constraint.delay), constraint.max_delay]).flatten();
+		let divisor = job_times.chain(delays).fold(0, gcd);
+		if divisor == 0 { 1 } else { divisor }
+	}
+
+	/// Divides all times in this problem by `divisor`, which must divide all of them (for instance
+	/// the result of `get_time_gcd`). Since all times are scaled by the same factor, this doesn't
+	/// change whether the problem is feasible, but smaller numbers can speed up the analysis.
+	/// Schedules of the divided problem can be scaled back using `Schedule::multiply_times`.
+	pub fn divide_times(&mut self, divisor: Time) {
+		for job in &mut self.jobs {
+			job.divide_times(divisor);
+		}
+		for constraint in &mut self.constraints {
+			debug_assert_eq!(0, constraint.delay % divisor);
+			constraint.delay /= divisor;
+			if let Some(max_delay) = &mut constraint.max_delay {
+				debug_assert_eq!(0, *max_delay % divisor);
+				*max_delay /= divisor;
+			}
+		}
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{Job, Problem};
+	use super::*;
 
 	#[test]
 	fn test_job() {
@@ -150,6 +706,30 @@ mod tests {
 		assert_eq!(job.get_latest_finish(), 10);
 		assert_eq!(job.latest_start, 0);
 		assert!(job.is_certainly_infeasible());
+
+		job.set_execution_time(8);
+		assert_eq!(job.get_execution_time(), 8);
+		assert_eq!(job.get_latest_finish(), 10);
+		assert_eq!(job.latest_start, 2);
+		assert_eq!(job.get_deadline(), 15);
+		assert!(!job.is_certainly_infeasible());
+	}
+
+	#[test]
+	fn test_core_set() {
+		let all = CoreSet::all();
+		assert!(all.is_all());
+		assert!(all.contains(0) && all.contains(100));
+		assert_eq!(vec![0, 1, 2], all.get_cores(3).collect::<Vec<_>>());
+
+		let cores = CoreSet::from_cores(&[1, 3, 63]);
+		assert!(!cores.is_all());
+		assert!(!cores.contains(0) && cores.contains(1) && cores.contains(63) && !cores.contains(64));
+		assert_eq!(vec![1, 3], cores.get_cores(4).collect::<Vec<_>>());
+
+		assert!(cores.is_subset_of(all) && !all.is_subset_of(cores));
+		assert!(CoreSet::from_cores(&[1, 63]).is_subset_of(cores));
+		assert!(!CoreSet::from_cores(&[0, 1]).is_subset_of(cores));
 	}
 
 	#[test]
@@ -158,12 +738,298 @@ mod tests {
 			jobs: vec![Job::release_to_deadline(0, 0, 10, 15)],
 			constraints: vec![],
 			num_cores: 2,
+			resources: Vec::new(),
 		};
 		assert!(!problem.is_certainly_infeasible());
 		problem.validate();
 
+		problem.jobs[0].set_parallelism(2);
+		assert!(!problem.is_certainly_infeasible());
+		assert!(problem.has_parallel_jobs());
+		problem.jobs[0].set_parallelism(3);
+		assert!(problem.is_certainly_infeasible());
+		problem.jobs[0].set_parallelism(1);
+
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[1]));
+		assert!(problem.has_affinities());
+		assert!(!problem.is_certainly_infeasible());
+		problem.validate();
+		problem.jobs[0].set_parallelism(2);
+		assert!(problem.is_certainly_infeasible());
+		problem.jobs[0].set_parallelism(1);
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[]));
+		assert!(problem.is_certainly_infeasible());
+		problem.jobs[0].set_affinity(CoreSet::all());
+		assert!(!problem.has_affinities());
+		assert!(problem.get_affinity_groups().is_empty());
+
 		problem.jobs.push(Job::release_to_deadline(1, 10, 10, 15));
 		assert!(problem.is_certainly_infeasible());
 		problem.validate();
+
+		let mut job = Job::release_to_deadline(2, 0, 1, 50);
+		job.set_affinity(CoreSet::from_cores(&[1]));
+		problem.jobs.push(job);
+		job.set_affinity(CoreSet::from_cores(&[0]));
+		problem.jobs.push(job);
+		problem.jobs.push(job);
+		assert_eq!(vec![CoreSet::from_cores(&[0]), CoreSet::from_cores(&[1])], problem.get_affinity_groups());
+	}
+
+	#[test]
+	fn test_get_subproblem() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 5, 20, 100),
+				Job::release_to_deadline(2, 10, 30, 100),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+				Constraint::new(1, 2, 3, ConstraintType::StartToStart),
+				Constraint::new(0, 2, 0, ConstraintType::FinishToStart),
+			],
+			num_cores: 2,
+			resources: vec![vec![2, 0, 1]],
+		};
+
+		let subproblem = problem.get_subproblem(&[false, true, true], &[true, true, true]);
+		subproblem.validate();
+		assert_eq!(2, subproblem.jobs.len());
+		assert_eq!(20, subproblem.jobs[0].get_execution_time());
+		assert_eq!(30, subproblem.jobs[1].get_execution_time());
+		assert_eq!(vec![Constraint::new(0, 1, 3, ConstraintType::StartToStart)], subproblem.constraints);
+		assert_eq!(vec![vec![1, 0]], subproblem.resources);
+		assert_eq!(2, subproblem.num_cores);
+
+		let subproblem = problem.get_subproblem(&[true, true, true], &[true, false, true]);
+		assert_eq!(3, subproblem.jobs.len());
+		assert_eq!(vec![problem.constraints[0], problem.constraints[2]], subproblem.constraints);
+		assert_eq!(problem.resources, subproblem.resources);
+	}
+
+	#[test]
+	fn test_unroll_hyperperiods() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 5, 5, 25),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: vec![vec![1]],
+		};
+
+		let unrolled = problem.unroll_hyperperiods(20, 2).unwrap();
+		unrolled.validate();
+		assert_eq!(vec![
+			Job::release_to_deadline(0, 0, 5, 10),
+			Job::release_to_deadline(1, 5, 5, 25),
+			Job::release_to_deadline(2, 20, 5, 30),
+			Job::release_to_deadline(3, 25, 5, 45),
+		], unrolled.jobs);
+		assert_eq!(vec![
+			Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+			Constraint::new(2, 3, 0, ConstraintType::FinishToStart),
+		], unrolled.constraints);
+		assert_eq!(vec![vec![1, 3]], unrolled.resources);
+		assert_eq!(Ok(problem.clone()), problem.unroll_hyperperiods(20, 1));
+		assert!(problem.unroll_hyperperiods(Time::MAX / 2, 3).is_err());
+		assert!(problem.unroll_hyperperiods(Time::MAX - 24, 2).is_err());
+		assert!(problem.unroll_hyperperiods(Time::MAX - 25, 2).is_ok());
+	}
+
+	#[test]
+	fn test_get_mode_problem() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 5, 20, 100),
+				Job::release_to_deadline(2, 10, 30, 100),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+				Constraint::new(1, 2, 3, ConstraintType::StartToStart),
+			],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		assert!(!problem.is_mixed_criticality());
+		assert_eq!(problem, problem.get_mode_problem(Criticality::Lo));
+		assert!(problem.get_mode_problem(Criticality::Hi).jobs.is_empty());
+
+		problem.jobs[1].set_hi_criticality(25);
+		problem.jobs[2].set_hi_criticality(40);
+		assert!(problem.is_mixed_criticality());
+		assert_eq!(problem, problem.get_mode_problem(Criticality::Lo));
+		assert_eq!(vec![1, 2], problem.get_mode_jobs(Criticality::Hi));
+
+		let hi_problem = problem.get_mode_problem(Criticality::Hi);
+		hi_problem.validate();
+		assert_eq!(2, hi_problem.jobs.len());
+		assert_eq!(25, hi_problem.jobs[0].get_execution_time());
+		assert_eq!(75, hi_problem.jobs[0].latest_start);
+		assert_eq!(40, hi_problem.jobs[1].get_execution_time());
+		assert_eq!(100, hi_problem.jobs[1].get_deadline());
+		assert_eq!(vec![Constraint::new(0, 1, 3, ConstraintType::StartToStart)], hi_problem.constraints);
+	}
+
+	#[test]
+	fn test_check_dispatch_prefix() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 15),
+				Job::release_to_deadline(1, 0, 10, 30),
+				Job::release_to_deadline(2, 0, 10, 30),
+			],
+			constraints: vec![Constraint::new(1, 2, 0, ConstraintType::StartToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		assert_eq!(Ok(()), problem.check_dispatch_prefix(&[]));
+		assert_eq!(Ok(()), problem.check_dispatch_prefix(&[0, 1]));
+		assert_eq!(Ok(()), problem.check_dispatch_prefix(&[1, 0, 2]));
+		assert_eq!(Ok(()), problem.check_dispatch_prefix(&[1, 2, 0]));
+		assert_eq!(Err(0), problem.check_dispatch_prefix(&[2]));
+		assert_eq!(Err(1), problem.check_dispatch_prefix(&[0, 2, 1]));
+		assert_eq!(Err(1), problem.check_dispatch_prefix(&[0, 0]));
+		assert_eq!(Err(0), problem.check_dispatch_prefix(&[3]));
+	}
+
+	#[test]
+	fn test_divide_times() {
+		assert_eq!(6, gcd(-12, 18));
+		assert_eq!(5, gcd(0, 5));
+
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 60),
+				Job::release_to_deadline(1, -40, 100, 200),
+			],
+			constraints: vec![Constraint::new(0, 1, 80, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+		assert_eq!(20, problem.get_time_gcd());
+
+		problem.divide_times(20);
+		assert_eq!(Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 1, 3),
+				Job::release_to_deadline(1, -2, 5, 10),
+			],
+			constraints: vec![Constraint::new(0, 1, 4, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		}, problem);
+		assert_eq!(1, problem.get_time_gcd());
+	}
+
+	#[test]
+	fn test_merge_duplicate_constraints() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 5, ConstraintType::FinishToStart),
+				Constraint::new(0, 1, 3, ConstraintType::StartToStart),
+				Constraint::new(0, 1, 8, ConstraintType::FinishToStart),
+				Constraint::new(1, 0, 2, ConstraintType::FinishToStart),
+				Constraint::new(0, 1, 1, ConstraintType::FinishToStart),
+			],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		assert_eq!(2, problem.merge_duplicate_constraints());
+		assert_eq!(vec![
+			Constraint::new(0, 1, 8, ConstraintType::FinishToStart),
+			Constraint::new(0, 1, 3, ConstraintType::StartToStart),
+			Constraint::new(1, 0, 2, ConstraintType::FinishToStart),
+		], problem.constraints);
+		assert_eq!(0, problem.merge_duplicate_constraints());
+
+		let mut bounded = Constraint::new(0, 1, 8, ConstraintType::FinishToStart);
+		bounded.set_max_delay(Some(20));
+		let mut tighter = bounded;
+		tighter.set_max_delay(Some(12));
+		problem.constraints = vec![bounded, Constraint::new(0, 1, 2, ConstraintType::FinishToStart), tighter];
+		assert_eq!(2, problem.merge_duplicate_constraints());
+		assert_eq!(vec![tighter], problem.constraints);
+	}
+
+	#[test]
+	fn test_find_contradictory_constraint() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 30),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 15, ConstraintType::StartToStart),
+				Constraint::new(0, 1, 10, ConstraintType::FinishToStart),
+			],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		assert_eq!(None, problem.find_contradictory_constraint());
+
+		problem.constraints.push(Constraint::new(0, 1, 11, ConstraintType::FinishToStart));
+		assert_eq!(Some(2), problem.find_contradictory_constraint());
+
+		// Job 1 can't start before time 20, so job 0 can't start before time 15
+		let mut bounded = Constraint::new(0, 1, 0, ConstraintType::StartToStart);
+		bounded.set_max_delay(Some(5));
+		problem.constraints = vec![bounded];
+		problem.jobs[1] = Job::release_to_deadline(1, 20, 10, 30);
+		assert_eq!(None, problem.find_contradictory_constraint());
+		problem.jobs[0] = Job::release_to_deadline(0, 0, 10, 14);
+		assert_eq!(Some(0), problem.find_contradictory_constraint());
+	}
+
+	#[test]
+	fn test_find_contradictory_constraint_pair() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+				Constraint::new(1, 2, 0, ConstraintType::StartToStart),
+				Constraint::new(2, 0, 0, ConstraintType::StartToStart),
+			],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		assert_eq!(None, problem.find_contradictory_constraint_pair());
+
+		// Job 1 must start at least 10 time units after job 0, but job 0 can't start before job 1
+		problem.constraints.push(Constraint::new(1, 0, 0, ConstraintType::StartToStart));
+		assert_eq!(None, problem.find_contradictory_constraint());
+		assert_eq!(Some((0, 3)), problem.find_contradictory_constraint_pair());
+
+		// The finish-to-start constraint requires at least 12 time units between the starts of job 0
+		// and job 1, and the maximum delay allows at most 20
+		let mut bounded = Constraint::new(0, 1, 5, ConstraintType::StartToStart);
+		bounded.set_max_delay(Some(20));
+		problem.constraints = vec![bounded, Constraint::new(0, 1, 2, ConstraintType::FinishToStart)];
+		assert_eq!(None, problem.find_contradictory_constraint_pair());
+
+		// Now the maximum delay allows at most 11 time units
+		problem.constraints[0].set_max_delay(Some(11));
+		assert_eq!(None, problem.find_contradictory_constraint());
+		assert_eq!(Some((0, 1)), problem.find_contradictory_constraint_pair());
+
+		// Start-to-start constraints in both directions can only be satisfied when both jobs start at
+		// the same time
+		let mut reversed = Constraint::new(1, 0, 0, ConstraintType::StartToStart);
+		reversed.set_max_delay(Some(5));
+		problem.constraints = vec![Constraint::new(0, 1, 0, ConstraintType::StartToStart), reversed];
+		assert_eq!(None, problem.find_contradictory_constraint_pair());
+		problem.constraints[0] = Constraint::new(0, 1, 1, ConstraintType::StartToStart);
+		assert_eq!(Some((0, 1)), problem.find_contradictory_constraint_pair());
 	}
 }
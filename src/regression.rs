@@ -0,0 +1,146 @@
+use crate::certificate::*;
+use crate::export::{format_constraints, format_jobs};
+use crate::fingerprint::compute_fingerprint;
+use crate::necessary::*;
+use crate::parser::parse_problem;
+use crate::permutation::ProblemPermutation;
+use crate::problem::*;
+use crate::simulator::simulate_earliest_deadline_first;
+use std::fs::{create_dir_all, read_dir, write};
+use std::path::Path;
+
+/// The reason why a problem was added to a regression corpus
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RegressionKind {
+	/// The analysis panicked
+	Panic,
+	/// The necessary tests proved that the problem is infeasible, while a feasible schedule was found
+	Disagreement,
+	/// The analysis didn't finish in time
+	Hang,
+}
+
+impl RegressionKind {
+	pub fn get_name(self) -> &'static str {
+		match self {
+			RegressionKind::Panic => "panic",
+			RegressionKind::Disagreement => "disagreement",
+			RegressionKind::Hang => "hang",
+		}
+	}
+}
+
+/// Writes the jobs and constraints of `problem` to the regression corpus in `directory`, using the
+/// same naming scheme as `test-problems/infeasible/regression`: the jobs are written to
+/// `<kind>-<fingerprint>-cores<num cores>.csv`, and the constraints to `<kind>-<fingerprint>.prec.csv`.
+/// Since the name contains the fingerprint, recording the same problem twice doesn't grow the
+/// corpus. Resources and criticalities are not written. Returns the path of the jobs file.
+pub fn record_regression(problem: &Problem, kind: RegressionKind, directory: &str) -> String {
+	create_dir_all(directory).expect("Couldn't create regression corpus directory");
+	let name = format!("{}-{:016x}", kind.get_name(), compute_fingerprint(problem));
+	let jobs_path = Path::new(directory).join(format!("{}-cores{}.csv", name, problem.num_cores));
+	write(&jobs_path, format_jobs(problem)).expect("Couldn't write regression jobs file");
+	if !problem.constraints.is_empty() {
+		let precedence_path = Path::new(directory).join(format!("{}.prec.csv", name));
+		write(precedence_path, format_constraints(problem)).expect("Couldn't write regression precedence file");
+	}
+	jobs_path.to_string_lossy().into_owned()
+}
+
+/// Parses all problems in the regression corpus in `directory`, sorted by the path of their jobs
+/// file. See `record_regression` for the naming scheme. Files that don't follow it are skipped.
+pub fn parse_regression_corpus(directory: &str) -> Vec<(String, Problem)> {
+	let mut problems = Vec::new();
+	let Ok(entries) = read_dir(directory) else { return problems };
+	for entry in entries {
+		let path = entry.expect("Couldn't read regression corpus directory").path();
+		let file_name = path.file_name().unwrap().to_string_lossy();
+		let Some((name, num_cores)) = file_name.strip_suffix(".csv").and_then(|stem| stem.rsplit_once("-cores")) else {
+			continue;
+		};
+		let Ok(num_cores) = num_cores.parse() else { continue };
+
+		let precedence_path = path.with_file_name(format!("{}.prec.csv", name));
+		let precedence_path = precedence_path.exists().then(|| precedence_path.to_string_lossy().into_owned());
+		let jobs_path = path.to_string_lossy().into_owned();
+		let problem = parse_problem(&jobs_path, precedence_path.as_deref(), num_cores);
+		problems.push((jobs_path, problem));
+	}
+	problems.sort_by(|(path1, _), (path2, _)| path1.cmp(path2));
+	problems
+}
+
+/// Runs the necessary tests, the infeasibility certificate search, and the EDF simulator on
+/// `problem`, and checks that they don't contradict each other. Returns a description of the first
+/// contradiction, or `Ok(())` if there is none. Panics and hangs are not caught, so they show up
+/// as such.
+pub fn check_regression(problem: &Problem) -> Result<(), String> {
+	let is_infeasible = is_proven_infeasible(problem);
+	let infeasibility_certificate = find_infeasibility_certificate(problem);
+	if let Some(certificate) = &infeasibility_certificate {
+		verify_infeasibility_certificate(problem, certificate).map_err(
+			|error| format!("The infeasibility certificate is invalid: {:?}", error)
+		)?;
+	}
+
+	// The simulator can't handle cyclic constraints
+	if ProblemPermutation::possible(&mut problem.clone()).is_err() {
+		return Ok(());
+	}
+	let simulator = simulate_earliest_deadline_first(problem);
+	if simulator.has_missed_deadline() {
+		return Ok(());
+	}
+	let schedule = simulator.get_schedule().unwrap();
+	schedule.validate(problem).map_err(|violation| format!("The EDF schedule is invalid: {:?}", violation))?;
+	check_schedule_certificate(problem, &create_schedule_certificate(schedule)).map_err(
+		|violation| format!("The EDF schedule certificate is invalid: {:?}", violation)
+	)?;
+	if is_infeasible {
+		return Err(String::from("The necessary tests rejected a problem that EDF can schedule"));
+	}
+	if infeasibility_certificate.is_some() {
+		return Err(String::from("Found an infeasibility certificate for a problem that EDF can schedule"));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::regression::*;
+
+	#[test]
+	fn test_record_and_parse_corpus() {
+		let directory = std::env::temp_dir().join(format!("np-feasibility-regression-{}", std::process::id()));
+		let directory = directory.to_str().unwrap();
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 10),
+				Job::release_to_deadline(1, 2, 3, 20),
+			],
+			constraints: vec![Constraint::new(0, 1, 1, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: vec![],
+		};
+		let jobs_path = record_regression(&problem, RegressionKind::Disagreement, directory);
+		assert!(jobs_path.ends_with("-cores2.csv"));
+		assert_eq!(jobs_path, record_regression(&problem, RegressionKind::Disagreement, directory));
+
+		let mut unconstrained = problem.clone();
+		unconstrained.constraints.clear();
+		record_regression(&unconstrained, RegressionKind::Hang, directory);
+
+		let corpus = parse_regression_corpus(directory);
+		std::fs::remove_dir_all(directory).unwrap();
+
+		assert_eq!(2, corpus.len());
+		assert_eq!(jobs_path, corpus[0].0);
+		assert_eq!(problem.jobs, corpus[0].1.jobs);
+		assert_eq!(problem.constraints, corpus[0].1.constraints);
+		assert_eq!(2, corpus[0].1.num_cores);
+		assert!(corpus[1].1.constraints.is_empty());
+		for (_, problem) in &corpus {
+			assert_eq!(Ok(()), check_regression(problem));
+		}
+	}
+}
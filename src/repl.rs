@@ -0,0 +1,255 @@
+use crate::bounds::*;
+use crate::necessary::{NecessaryTest, ProblemIndex};
+use crate::permutation::{ProblemPermutation, ReadyJobOrder};
+use crate::problem::*;
+use crate::simulator::{simulate_earliest_deadline_first, simulate_fixed_priority, Simulator};
+use std::io::{BufRead, Write};
+
+const HELP: &str = "\
+Commands:
+  strengthen                   add the implied constraints, and strengthen the bounds of all jobs
+  windows|load|interval|resource
+                               run a single necessary test
+  search edf|fp                simulate G-NP-EDF or G-NP-FP (using the priorities of the jobs)
+  job <job>                    print the bounds of a job
+  constrain <before> <after> [delay] [f-s|s-s]
+                               add a hypothetical constraint
+  reset                        undo all strengthening and added constraints
+  help                         print this message
+  quit                         stop";
+
+/// The state of an interactive session: the problem that was loaded, and the problem to which the
+/// commands of the user were applied
+pub struct ReplSession {
+	original_problem: Problem,
+	problem: Problem,
+}
+
+/// Permutes a copy of `problem` such that `c.before < c.after` holds for every constraint `c`,
+/// which most passes require. Returns `None` when the constraints are cyclic.
+fn permute(problem: &Problem) -> Option<(Problem, ProblemPermutation)> {
+	let mut permuted = problem.clone();
+	let permutation = ProblemPermutation::possible_with_order(&mut permuted, ReadyJobOrder::EarliestStart).ok()?;
+	Some((permuted, permutation))
+}
+
+impl ReplSession {
+	pub fn new(problem: Problem) -> Self {
+		Self { original_problem: problem.clone(), problem }
+	}
+
+	pub fn get_problem(&self) -> &Problem {
+		&self.problem
+	}
+
+	/// Executes a single command, and returns the text that should be shown to the user
+	pub fn execute(&mut self, command: &str) -> Result<String, String> {
+		let words: Vec<&str> = command.split_whitespace().collect();
+		match words.as_slice() {
+			["help"] => Ok(HELP.to_string()),
+			["strengthen"] => Ok(self.strengthen()),
+			["search", policy] => self.search(policy),
+			["job", job] => self.describe_job(job),
+			["constrain", arguments @ ..] => self.constrain(arguments),
+			["reset"] => {
+				self.problem = self.original_problem.clone();
+				Ok("Restored the original problem".to_string())
+			},
+			[name] => match NecessaryTest::ALL.into_iter().find(|test| test.get_name() == *name) {
+				Some(test) => Ok(self.run_test(test)),
+				None => Err(format!("Unknown command {}; type help for a list of commands", name)),
+			},
+			_ => Err(format!("Unknown command {}; type help for a list of commands", command.trim())),
+		}
+	}
+
+	fn strengthen(&mut self) -> String {
+		if let Some(index) = self.problem.find_contradictory_constraint() {
+			return format!("Constraint {} can't be satisfied within the windows of its jobs, so the problem is INFEASIBLE", index);
+		}
+		if let Some((first, second)) = self.problem.find_contradictory_constraint_pair() {
+			return format!("Constraints {} and {} can't be satisfied together, so the problem is INFEASIBLE", first, second);
+		}
+		let mut problem = self.problem.clone();
+		let implied_constraints = find_implied_constraints(&problem);
+		let num_implied_constraints = implied_constraints.len();
+		problem.constraints.extend(implied_constraints);
+
+		let Some((mut permuted, permutation)) = permute(&problem) else {
+			return "The (implied) constraints are cyclic, so the problem is INFEASIBLE".to_string();
+		};
+		strengthen_bounds_using_constraints(&mut permuted);
+		let result = strengthen_bounds_using_core_occupation(&mut permuted);
+		permutation.transform_back(&mut permuted);
+		if result == OccupationStrengthenResult::Infeasible {
+			return "The core occupation proves that the problem is INFEASIBLE".to_string();
+		}
+
+		let num_changed_jobs = self.problem.jobs.iter().zip(&permuted.jobs).filter(
+			|(old, new)| old.earliest_start != new.earliest_start || old.latest_start != new.latest_start
+		).count();
+		permuted.merge_duplicate_constraints();
+		self.problem = permuted;
+		format!("Found {} implied constraints, and strengthened the bounds of {} jobs", num_implied_constraints, num_changed_jobs)
+	}
+
+	fn run_test(&self, test: NecessaryTest) -> String {
+		let Some((permuted, _)) = permute(&self.problem) else {
+			return "The constraints are cyclic, so the problem is INFEASIBLE".to_string();
+		};
+		if test.run(&permuted, &ProblemIndex::new(&permuted)) {
+			format!("The {} test proves that the problem is INFEASIBLE", test.get_name())
+		} else {
+			format!("The {} test is inconclusive", test.get_name())
+		}
+	}
+
+	fn search(&self, policy: &str) -> Result<String, String> {
+		if permute(&self.problem).is_none() {
+			return Ok("The constraints are cyclic, so the problem is INFEASIBLE".to_string());
+		}
+		let simulator: Simulator = match policy {
+			"edf" => simulate_earliest_deadline_first(&self.problem),
+			"fp" => {
+				let priorities: Vec<u32> = self.problem.jobs.iter().map(|job| job.get_priority()).collect();
+				simulate_fixed_priority(&self.problem, &priorities)
+			},
+			_ => return Err(format!("Unknown policy {}; expected edf or fp", policy)),
+		};
+
+		let schedule = simulator.get_schedule().unwrap();
+		if simulator.has_missed_deadline() {
+			return Ok(format!(
+				"The policy missed a deadline, with a total tardiness of {}",
+				schedule.get_total_tardiness(&self.problem)
+			));
+		}
+		let mut lines = vec!["The policy found a FEASIBLE schedule".to_string()];
+		for scheduled in schedule.get_jobs() {
+			lines.push(format!("Job {} starts at time {} on core {}", scheduled.job, scheduled.start, scheduled.core));
+		}
+		Ok(lines.join("\n"))
+	}
+
+	fn parse_job(&self, value: &str) -> Result<usize, String> {
+		match value.parse::<usize>() {
+			Ok(job) if job < self.problem.jobs.len() => Ok(job),
+			_ => Err(format!("Job {} doesn't exist", value)),
+		}
+	}
+
+	fn describe_job(&self, job: &str) -> Result<String, String> {
+		let job = self.problem.jobs[self.parse_job(job)?];
+		Ok(format!(
+			"Job {} can start between time {} and {}, takes {} time units, and has deadline {}",
+			job.get_index(), job.earliest_start, job.latest_start, job.get_execution_time(), job.get_deadline()
+		))
+	}
+
+	fn constrain(&mut self, arguments: &[&str]) -> Result<String, String> {
+		if arguments.len() < 2 || arguments.len() > 4 {
+			return Err("Usage: constrain <before> <after> [delay] [f-s|s-s]".to_string());
+		}
+		let before = self.parse_job(arguments[0])?;
+		let after = self.parse_job(arguments[1])?;
+		let delay = match arguments.get(2) {
+			Some(delay) => delay.parse::<Time>().ok().filter(|delay| *delay >= 0).ok_or_else(
+				|| format!("Couldn't parse delay {}", delay)
+			)?,
+			None => 0,
+		};
+		let constraint_type = match arguments.get(3) {
+			None | Some(&"f-s") => ConstraintType::FinishToStart,
+			Some(&"s-s") => ConstraintType::StartToStart,
+			Some(other) => return Err(format!("Unexpected constraint type {}", other)),
+		};
+
+		let constraint = Constraint::new(before, after, delay, constraint_type);
+		self.problem.constraints.push(constraint);
+		Ok(format!("Added constraint {}", self.problem.constraints.len() - 1))
+	}
+}
+
+/// Loads `problem`, and keeps executing the commands of the user (one per line of `input`) until
+/// `input` is exhausted or the user types `quit`. A prompt is written to `output` before each
+/// command, and the result after it.
+pub fn run_repl(problem: Problem, input: impl BufRead, mut output: impl Write) {
+	let mut session = ReplSession::new(problem);
+	writeln!(output, "Loaded {} jobs; type help for a list of commands", session.get_problem().jobs.len())
+		.expect("Couldn't write to output");
+
+	let mut lines = input.lines();
+	loop {
+		write!(output, "> ").expect("Couldn't write to output");
+		output.flush().expect("Couldn't write to output");
+		let Some(line) = lines.next() else { break; };
+		let line = line.expect("Couldn't read command from input");
+		match line.trim() {
+			"" => continue,
+			"quit" | "exit" => break,
+			command => match session.execute(command) {
+				Ok(text) => writeln!(output, "{}", text),
+				Err(error) => writeln!(output, "Error: {}", error),
+			}.expect("Couldn't write to output"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::repl::*;
+
+	fn create_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 25),
+				Job::release_to_deadline(1, 0, 10, 25),
+				Job::release_to_deadline(2, 0, 5, 40),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn test_session() {
+		let mut session = ReplSession::new(create_problem());
+		assert_eq!(
+			"Found 0 implied constraints, and strengthened the bounds of 2 jobs",
+			session.execute("strengthen").unwrap()
+		);
+		assert_eq!(
+			"Job 1 can start between time 10 and 15, takes 10 time units, and has deadline 25",
+			session.execute("job 1").unwrap()
+		);
+		assert_eq!("The load test is inconclusive", session.execute("load").unwrap());
+		assert!(session.execute("search edf").unwrap().starts_with("The policy found a FEASIBLE schedule"));
+
+		// Job 2 can't run before job 0 without delaying job 1 too much
+		assert_eq!("Added constraint 1", session.execute("constrain 2 0 1").unwrap());
+		assert_eq!("The windows test is inconclusive", session.execute("windows").unwrap());
+		assert_eq!(
+			"Constraint 1 can't be satisfied within the windows of its jobs, so the problem is INFEASIBLE",
+			session.execute("strengthen").unwrap()
+		);
+		assert_eq!("Restored the original problem", session.execute("reset").unwrap());
+		assert_eq!(create_problem(), *session.get_problem());
+
+		assert!(session.execute("job 3").is_err());
+		assert!(session.execute("constrain 0").is_err());
+		assert!(session.execute("search rm").is_err());
+		assert!(session.execute("fly").is_err());
+	}
+
+	#[test]
+	fn test_run_repl() {
+		let mut output = Vec::new();
+		run_repl(create_problem(), "job 0\n\nfoo\nquit\njob 1\n".as_bytes(), &mut output);
+		let output = String::from_utf8(output).unwrap();
+		assert!(output.starts_with("Loaded 3 jobs"));
+		assert!(output.contains("> Job 0 can start between time 0 and 15"));
+		assert!(output.contains("> Error: Unknown command foo"));
+		assert!(!output.contains("Job 1 can start"));
+	}
+}
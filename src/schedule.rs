@@ -0,0 +1,425 @@
+use crate::problem::*;
+
+/// The start time, finish time, and core of a single job in a `Schedule`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ScheduledJob {
+	pub job: usize,
+	pub start: Time,
+	pub finish: Time,
+	pub core: usize,
+}
+
+impl ScheduledJob {
+	/// Gets the time between the finish time of this job and its original deadline in `problem`. For
+	/// a segment of a job with preemption points, this is relative to the deadline of the whole job.
+	pub fn get_slack(&self, problem: &Problem) -> Time {
+		problem.jobs[self.job].get_deadline() - self.finish
+	}
+}
+
+/// The kind of a `ScheduleEvent`. When multiple events happen on the same core at the same time,
+/// they are ordered like the variants of this enum.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ScheduleEventKind {
+	Finish,
+	/// The core becomes idle: it has no job to run after the job that just finished
+	Idle,
+	Start,
+}
+
+impl ScheduleEventKind {
+	pub fn get_name(&self) -> &'static str {
+		match self {
+			Self::Finish => "finish",
+			Self::Idle => "idle",
+			Self::Start => "start",
+		}
+	}
+}
+
+/// A single event in a `Schedule`. The `job` is `None` for `Idle` events.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ScheduleEvent {
+	pub time: Time,
+	pub core: usize,
+	pub kind: ScheduleEventKind,
+	pub job: Option<usize>,
+}
+
+/// The reason why a `Schedule` is not a valid solution of a `Problem`. Each variant contains the
+/// index of the job (or constraint) that is involved.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ScheduleViolation {
+	MissingJob(usize),
+	DuplicateJob(usize),
+	WrongExecutionTime(usize),
+	InvalidCore(usize),
+	/// The job runs on a core that is not in its affinity
+	ViolatesAffinity(usize),
+	StartsTooEarly(usize),
+	MissesDeadline(usize),
+	ViolatesConstraint(usize),
+	OverlapsOnCore { core: usize, first: usize, second: usize },
+	/// Two jobs that use the same shared resource overlap in time
+	OverlapsOnResource { resource: usize, first: usize, second: usize },
+}
+
+/// A static schedule: an assignment of a start time and a core to (some of) the jobs of a problem.
+/// The jobs are stored in the order in which they were added.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Schedule {
+	jobs: Vec<ScheduledJob>,
+}
+
+impl Schedule {
+	pub fn new() -> Self {
+		Self { jobs: Vec::new() }
+	}
+
+	pub fn push(&mut self, job: ScheduledJob) {
+		self.jobs.push(job);
+	}
+
+	/// Removes the job that was added last, and returns it
+	pub fn pop(&mut self) -> Option<ScheduledJob> {
+		self.jobs.pop()
+	}
+
+	pub fn get_jobs(&self) -> &[ScheduledJob] {
+		&self.jobs
+	}
+
+	/// Multiplies all start and finish times in this schedule by `factor`. This turns a schedule of
+	/// a problem whose times were divided by `factor` into a schedule of the original problem.
+	pub fn multiply_times(&mut self, factor: Time) {
+		for scheduled in &mut self.jobs {
+			scheduled.start *= factor;
+			scheduled.finish *= factor;
+		}
+	}
+
+	/// Replaces the index `j` of each scheduled job by `new_indices[j]`. This can be used to map a
+	/// schedule of a permuted problem back to the original problem.
+	pub fn renumber_jobs(&mut self, new_indices: &[usize]) {
+		for scheduled in &mut self.jobs {
+			scheduled.job = new_indices[scheduled.job];
+		}
+	}
+
+	/// Gets the time at which the last job finishes, or 0 when this schedule is empty
+	pub fn get_makespan(&self) -> Time {
+		self.jobs.iter().map(|j| j.finish).max().unwrap_or(0)
+	}
+
+	/// Gets the smallest slack of all jobs in this schedule, relative to their original deadlines
+	/// (see `ScheduledJob::get_slack`). Returns `None` when this schedule is empty.
+	pub fn get_minimum_slack(&self, problem: &Problem) -> Option<Time> {
+		self.jobs.iter().map(|j| j.get_slack(problem)).min()
+	}
+
+	/// Gets the lateness of each job of `problem` in this schedule: the time between its original
+	/// deadline and its finish time, which is negative when it finishes before its deadline. A job
+	/// that occurs multiple times (like a split job) is late when its last part finishes late. The
+	/// lateness of jobs that are not in this schedule is `None`.
+	pub fn get_lateness(&self, problem: &Problem) -> Vec<Option<Time>> {
+		let mut lateness: Vec<Option<Time>> = vec![None; problem.jobs.len()];
+		for scheduled in &self.jobs {
+			let job_lateness = -scheduled.get_slack(problem);
+			let old_lateness = &mut lateness[scheduled.job];
+			*old_lateness = Some(old_lateness.map_or(job_lateness, |old| Time::max(old, job_lateness)));
+		}
+		lateness
+	}
+
+	/// Gets the sum of the tardiness of all jobs of `problem` in this schedule, where the tardiness of
+	/// a job is its lateness (see `get_lateness`), or 0 when it meets its deadline
+	pub fn get_total_tardiness(&self, problem: &Problem) -> Time {
+		self.get_lateness(problem).into_iter().flatten().map(|lateness| Time::max(0, lateness)).sum()
+	}
+
+	/// Gets the largest tardiness of all jobs of `problem` in this schedule, or 0 when all jobs meet
+	/// their deadlines
+	pub fn get_maximum_tardiness(&self, problem: &Problem) -> Time {
+		self.get_lateness(problem).into_iter().flatten().fold(0, Time::max)
+	}
+
+	/// Gets the start and finish events of all jobs in this schedule, as well as an `Idle` event
+	/// whenever a core finishes a job without starting another job immediately. The events are
+	/// sorted by time, and then by core.
+	pub fn get_events(&self) -> Vec<ScheduleEvent> {
+		let mut sorted_jobs = self.jobs.clone();
+		sorted_jobs.sort_by_key(|j| (j.core, j.start));
+
+		let mut events = Vec::with_capacity(3 * sorted_jobs.len());
+		for (index, scheduled) in sorted_jobs.iter().enumerate() {
+			let (time, core, job) = (scheduled.start, scheduled.core, Some(scheduled.job));
+			events.push(ScheduleEvent { time, core, kind: ScheduleEventKind::Start, job });
+
+			let time = scheduled.finish;
+			events.push(ScheduleEvent { time, core, kind: ScheduleEventKind::Finish, job });
+			let next = sorted_jobs.get(index + 1);
+			if next.is_none_or(|next| next.core != core || next.start > time) {
+				events.push(ScheduleEvent { time, core, kind: ScheduleEventKind::Idle, job: None });
+			}
+		}
+
+		events.sort_by_key(|event| (event.time, event.core, event.kind));
+		events
+	}
+
+	/// Checks whether this schedule is a valid solution of `problem`: every job must be scheduled
+	/// exactly once on an existing core (and a gang job once on each of the cores that it occupies,
+	/// all with the same start time), between its `earliest_start` and `latest_start`, without
+	/// violating any constraint, and without overlapping other jobs on the same core or other jobs
+	/// that use the same shared resource.
+	///
+	/// Returns the first violation that is encountered, or `Ok(())` if the schedule is valid.
+	pub fn validate(&self, problem: &Problem) -> Result<(), ScheduleViolation> {
+		let mut start_times: Vec<Option<Time>> = vec![None; problem.jobs.len()];
+		let mut num_cores = vec![0; problem.jobs.len()];
+		for scheduled in &self.jobs {
+			let job = &problem.jobs[scheduled.job];
+			num_cores[scheduled.job] += 1;
+			if num_cores[scheduled.job] > job.get_parallelism() ||
+					start_times[scheduled.job].is_some_and(|start| start != scheduled.start) {
+				return Err(ScheduleViolation::DuplicateJob(scheduled.job));
+			}
+			if scheduled.finish - scheduled.start != job.get_execution_time() {
+				return Err(ScheduleViolation::WrongExecutionTime(scheduled.job));
+			}
+			if scheduled.core >= problem.num_cores as usize {
+				return Err(ScheduleViolation::InvalidCore(scheduled.job));
+			}
+			if !job.get_affinity().contains(scheduled.core) {
+				return Err(ScheduleViolation::ViolatesAffinity(scheduled.job));
+			}
+			if scheduled.start < job.earliest_start {
+				return Err(ScheduleViolation::StartsTooEarly(scheduled.job));
+			}
+			if scheduled.start > job.latest_start {
+				return Err(ScheduleViolation::MissesDeadline(scheduled.job));
+			}
+			start_times[scheduled.job] = Some(scheduled.start);
+		}
+
+		if let Some(missing_job) = (0 .. problem.jobs.len()).find(
+			|job| num_cores[*job] < problem.jobs[*job].get_parallelism()
+		) {
+			return Err(ScheduleViolation::MissingJob(missing_job));
+		}
+
+		for (index, constraint) in problem.constraints.iter().enumerate() {
+			let before_start = start_times[constraint.get_before()].unwrap();
+			let mut ready_time = before_start + constraint.get_delay();
+			if constraint.get_type() == ConstraintType::FinishToStart {
+				ready_time += problem.jobs[constraint.get_before()].get_execution_time();
+			}
+			let max_ready_time = constraint.get_max_separation(problem.jobs[constraint.get_before()].get_execution_time())
+				.map(|max_separation| before_start + max_separation);
+			let after_start = start_times[constraint.get_after()].unwrap();
+			if after_start < ready_time || max_ready_time.is_some_and(|max_ready_time| after_start > max_ready_time) {
+				return Err(ScheduleViolation::ViolatesConstraint(index));
+			}
+		}
+
+		let mut sorted_jobs = self.jobs.clone();
+		sorted_jobs.sort_by_key(|j| (j.core, j.start));
+		for pair in sorted_jobs.windows(2) {
+			if pair[0].core == pair[1].core && pair[0].finish > pair[1].start {
+				return Err(ScheduleViolation::OverlapsOnCore {
+					core: pair[0].core, first: pair[0].job, second: pair[1].job
+				});
+			}
+		}
+
+		for (resource, users) in problem.resources.iter().enumerate() {
+			let mut intervals: Vec<(Time, usize)> = users.iter().map(
+				|job| (start_times[*job].unwrap(), *job)
+			).collect();
+			intervals.sort();
+			for pair in intervals.windows(2) {
+				let (first_start, first) = pair[0];
+				if first_start + problem.jobs[first].get_execution_time() > pair[1].0 {
+					return Err(ScheduleViolation::OverlapsOnResource { resource, first, second: pair[1].1 });
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn two_job_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 20),
+				Job::release_to_deadline(1, 5, 10, 30),
+			],
+			constraints: vec![Constraint::new(0, 1, 2, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		}
+	}
+
+	fn scheduled(job: usize, start: Time, execution_time: Time, core: usize) -> ScheduledJob {
+		ScheduledJob { job, start, finish: start + execution_time, core }
+	}
+
+	#[test]
+	fn test_tardiness() {
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 14, 10, 0));
+		schedule.push(scheduled(1, 0, 10, 1));
+		schedule.push(scheduled(1, 25, 10, 1));
+		let problem = two_job_problem();
+		assert_eq!(vec![Some(4), Some(5)], schedule.get_lateness(&problem));
+		assert_eq!(9, schedule.get_total_tardiness(&problem));
+		assert_eq!(5, schedule.get_maximum_tardiness(&problem));
+
+		schedule.pop();
+		schedule.pop();
+		assert_eq!(vec![Some(4), None], schedule.get_lateness(&problem));
+		assert_eq!(4, schedule.get_total_tardiness(&problem));
+		assert_eq!(0, Schedule::new().get_maximum_tardiness(&problem));
+	}
+
+	#[test]
+	fn test_valid_schedule() {
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 10, 1));
+		schedule.push(scheduled(1, 12, 10, 1));
+		assert_eq!(Ok(()), schedule.validate(&two_job_problem()));
+	}
+
+	#[test]
+	fn test_multiply_times() {
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 1, 1));
+		schedule.push(scheduled(1, 2, 1, 0));
+		schedule.multiply_times(10);
+		assert_eq!(&[scheduled(0, 0, 10, 1), scheduled(1, 20, 10, 0)], schedule.get_jobs());
+		assert_eq!(Ok(()), schedule.validate(&two_job_problem()));
+	}
+
+	#[test]
+	fn test_makespan_and_slack() {
+		let problem = two_job_problem();
+		let mut schedule = Schedule::new();
+		assert_eq!(0, schedule.get_makespan());
+		assert_eq!(None, schedule.get_minimum_slack(&problem));
+
+		schedule.push(scheduled(0, 2, 10, 1));
+		schedule.push(scheduled(1, 14, 10, 0));
+		assert_eq!(24, schedule.get_makespan());
+		assert_eq!(Some(6), schedule.get_minimum_slack(&problem));
+
+		// Strengthening the bounds must not change the slack
+		let mut problem = problem;
+		problem.jobs[1].latest_start = 15;
+		assert_eq!(Some(6), schedule.get_minimum_slack(&problem));
+	}
+
+	#[test]
+	fn test_invalid_schedules() {
+		let problem = two_job_problem();
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 10, 0));
+		assert_eq!(Err(ScheduleViolation::MissingJob(1)), schedule.validate(&problem));
+
+		schedule.push(scheduled(0, 0, 10, 0));
+		assert_eq!(Err(ScheduleViolation::DuplicateJob(0)), schedule.validate(&problem));
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 10, 0));
+		schedule.push(scheduled(1, 11, 10, 1));
+		assert_eq!(Err(ScheduleViolation::ViolatesConstraint(0)), schedule.validate(&problem));
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 10, 2));
+		assert_eq!(Err(ScheduleViolation::InvalidCore(0)), schedule.validate(&problem));
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 11, 10, 0));
+		assert_eq!(Err(ScheduleViolation::MissesDeadline(0)), schedule.validate(&problem));
+
+		let mut pinned = problem.clone();
+		pinned.jobs[0].set_affinity(CoreSet::from_cores(&[1]));
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 10, 0));
+		assert_eq!(Err(ScheduleViolation::ViolatesAffinity(0)), schedule.validate(&pinned));
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 9, 0));
+		assert_eq!(Err(ScheduleViolation::WrongExecutionTime(0)), schedule.validate(&problem));
+	}
+
+	#[test]
+	fn test_overlapping_jobs() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 20),
+				Job::release_to_deadline(1, 5, 10, 30),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(1, 5, 10, 0));
+		schedule.push(scheduled(0, 0, 10, 0));
+		assert_eq!(Err(ScheduleViolation::OverlapsOnCore {
+			core: 0, first: 0, second: 1
+		}), schedule.validate(&problem));
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(1, 5, 10, 0));
+		schedule.push(scheduled(0, 0, 10, 1));
+		assert_eq!(Ok(()), schedule.validate(&problem));
+	}
+
+	#[test]
+	fn test_parallel_jobs() {
+		let mut problem = two_job_problem();
+		problem.jobs[0].set_parallelism(2);
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(0, 0, 10, 0));
+		schedule.push(scheduled(1, 12, 10, 0));
+		assert_eq!(Err(ScheduleViolation::MissingJob(0)), schedule.validate(&problem));
+
+		schedule.push(scheduled(0, 1, 10, 1));
+		assert_eq!(Err(ScheduleViolation::DuplicateJob(0)), schedule.validate(&problem));
+
+		schedule.pop();
+		schedule.push(scheduled(0, 0, 10, 1));
+		assert_eq!(Ok(()), schedule.validate(&problem));
+
+		schedule.push(scheduled(0, 0, 10, 1));
+		assert_eq!(Err(ScheduleViolation::DuplicateJob(0)), schedule.validate(&problem));
+	}
+
+	#[test]
+	fn test_overlapping_resource_users() {
+		let mut problem = two_job_problem();
+		problem.constraints.clear();
+		problem.resources = vec![vec![1, 0]];
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(1, 5, 10, 1));
+		schedule.push(scheduled(0, 0, 10, 0));
+		assert_eq!(Err(ScheduleViolation::OverlapsOnResource {
+			resource: 0, first: 0, second: 1
+		}), schedule.validate(&problem));
+
+		let mut schedule = Schedule::new();
+		schedule.push(scheduled(1, 10, 10, 1));
+		schedule.push(scheduled(0, 0, 10, 0));
+		assert_eq!(Ok(()), schedule.validate(&problem));
+	}
+}
@@ -0,0 +1,182 @@
+use crate::problem::*;
+use crate::schedule::Schedule;
+use std::ops::Range;
+
+/// Describes how the jobs of a problem with preemption points were split into non-preemptive
+/// segments (see `split_into_segments`). A job can only be preempted between two of its segments,
+/// after which it may continue on another core. The segments of each job are consecutive in the
+/// split problem.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Segmentation {
+	/// The problem before its jobs were split
+	pub original_problem: Problem,
+
+	/// `original_jobs[s]` is the index of the job of `original_problem` to which segment `s` belongs
+	original_jobs: Vec<usize>,
+
+	/// `first_segments[j]` is the index of the first segment of job `j`, and
+	/// `first_segments[original_problem.jobs.len()]` is the number of segments
+	first_segments: Vec<usize>,
+}
+
+impl Segmentation {
+	/// Gets the index of the original job to which `segment` belongs
+	pub fn get_original_job(&self, segment: usize) -> usize {
+		self.original_jobs[segment]
+	}
+
+	/// Gets the indices of the segments of the original job `job`
+	pub fn get_segments(&self, job: usize) -> Range<usize> {
+		self.first_segments[job] .. self.first_segments[job + 1]
+	}
+
+	/// Gets the number of original jobs that were split into more than 1 segment
+	pub fn get_num_split_jobs(&self) -> usize {
+		(0 .. self.original_problem.jobs.len()).filter(|job| self.get_segments(*job).len() > 1).count()
+	}
+
+	/// Maps a schedule of the split problem to `original_problem`: each segment is reported as a part
+	/// of its original job, so a job that was split into `n` segments occurs `n` times. The slack of
+	/// each part is relative to the deadline of its original job.
+	pub fn merge_schedule(&self, schedule: &Schedule) -> Schedule {
+		let mut merged = schedule.clone();
+		merged.renumber_jobs(&self.original_jobs);
+		merged
+	}
+}
+
+/// Splits the jobs of `problem` into the non-preemptive segments given by `segments`: each entry
+/// contains a job and the execution times of its segments, which must add up to its execution
+/// time. Jobs without an entry remain a single segment.
+///
+/// Each segment inherits the window of its job, reduced by the execution time of the segments
+/// before and after it, as well as the parallelism and shared resources of its job. Consecutive
+/// segments are chained with finish-to-start constraints without delay. A constraint towards a job
+/// applies to its first segment, a start-to-start constraint from a job applies to its first
+/// segment, and a finish-to-start constraint from a job applies to its last segment.
+pub fn split_into_segments(problem: &Problem, segments: &[(usize, Vec<Time>)]) -> Result<(Problem, Segmentation), String> {
+	assert!(!problem.is_mixed_criticality(), "Jobs of mixed-criticality problems can't be split");
+	let mut execution_times: Vec<Option<&[Time]>> = vec![None; problem.jobs.len()];
+	for (job, job_segments) in segments {
+		if *job >= problem.jobs.len() {
+			return Err(format!("The segments refer to job {}, which doesn't exist", job));
+		}
+		if execution_times[*job].is_some() {
+			return Err(format!("The segments of job {} are given more than once", job));
+		}
+		if job_segments.iter().sum::<Time>() != problem.jobs[*job].get_execution_time() {
+			return Err(format!("The segments of job {} don't add up to its execution time", job));
+		}
+		execution_times[*job] = Some(job_segments);
+	}
+
+	let mut split_problem = Problem {
+		jobs: Vec::new(), constraints: Vec::new(), num_cores: problem.num_cores, resources: Vec::new()
+	};
+	let mut original_jobs = Vec::new();
+	let mut first_segments = Vec::with_capacity(problem.jobs.len() + 1);
+	for (index, job) in problem.jobs.iter().enumerate() {
+		first_segments.push(split_problem.jobs.len());
+		let whole_job = [job.get_execution_time()];
+		let job_segments = execution_times[index].unwrap_or(&whole_job);
+
+		let mut elapsed = 0;
+		for (position, execution_time) in job_segments.iter().enumerate() {
+			let segment = split_problem.jobs.len();
+			let remaining = job.get_execution_time() - elapsed - execution_time;
+			let mut segment_job = Job::release_to_deadline(
+				segment, job.earliest_start + elapsed, *execution_time, job.get_deadline() - remaining
+			);
+			segment_job.set_parallelism(job.get_parallelism());
+			segment_job.set_affinity(job.get_affinity());
+			split_problem.jobs.push(segment_job);
+			original_jobs.push(index);
+			if position > 0 {
+				split_problem.constraints.push(Constraint::new(segment - 1, segment, 0, ConstraintType::FinishToStart));
+			}
+			elapsed += execution_time;
+		}
+	}
+	first_segments.push(split_problem.jobs.len());
+
+	for constraint in &problem.constraints {
+		let before = match constraint.get_type() {
+			ConstraintType::StartToStart => first_segments[constraint.get_before()],
+			ConstraintType::FinishToStart => first_segments[constraint.get_before() + 1] - 1,
+		};
+		split_problem.constraints.push(constraint.with_jobs(before, first_segments[constraint.get_after()]));
+	}
+	split_problem.resources = problem.resources.iter().map(|users| users.iter().flat_map(
+		|job| first_segments[*job] .. first_segments[*job + 1]
+	).collect()).collect();
+
+	Ok((split_problem, Segmentation { original_problem: problem.clone(), original_jobs, first_segments }))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::schedule::*;
+	use crate::segments::*;
+
+	fn create_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 30),
+				Job::release_to_deadline(1, 5, 6, 40),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 2, ConstraintType::FinishToStart),
+				Constraint::new(1, 0, 1, ConstraintType::StartToStart),
+			],
+			num_cores: 1,
+			resources: vec![vec![0]],
+		}
+	}
+
+	#[test]
+	fn test_split_into_segments() {
+		let (split, segmentation) = split_into_segments(&create_problem(), &[(0, vec![3, 2, 5])]).unwrap();
+		split.validate();
+		assert_eq!(vec![
+			Job::release_to_deadline(0, 0, 3, 23),
+			Job::release_to_deadline(1, 3, 2, 25),
+			Job::release_to_deadline(2, 5, 5, 30),
+			Job::release_to_deadline(3, 5, 6, 40),
+		], split.jobs);
+		assert_eq!(vec![
+			Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+			Constraint::new(1, 2, 0, ConstraintType::FinishToStart),
+			Constraint::new(2, 3, 2, ConstraintType::FinishToStart),
+			Constraint::new(3, 0, 1, ConstraintType::StartToStart),
+		], split.constraints);
+		assert_eq!(vec![vec![0, 1, 2]], split.resources);
+
+		assert_eq!(0 .. 3, segmentation.get_segments(0));
+		assert_eq!(3 .. 4, segmentation.get_segments(1));
+		assert_eq!(0, segmentation.get_original_job(2));
+		assert_eq!(1, segmentation.get_original_job(3));
+		assert_eq!(1, segmentation.get_num_split_jobs());
+	}
+
+	#[test]
+	fn test_invalid_segments() {
+		let problem = create_problem();
+		assert!(split_into_segments(&problem, &[(2, vec![1])]).is_err());
+		assert!(split_into_segments(&problem, &[(0, vec![3, 3])]).is_err());
+		assert!(split_into_segments(&problem, &[(1, vec![6]), (1, vec![2, 4])]).is_err());
+	}
+
+	#[test]
+	fn test_merge_schedule() {
+		let (_, segmentation) = split_into_segments(&create_problem(), &[(1, vec![2, 4])]).unwrap();
+		let mut schedule = Schedule::new();
+		schedule.push(ScheduledJob { job: 1, start: 5, finish: 7, core: 0 });
+		schedule.push(ScheduledJob { job: 0, start: 7, finish: 17, core: 0 });
+		schedule.push(ScheduledJob { job: 2, start: 19, finish: 23, core: 0 });
+
+		let merged = segmentation.merge_schedule(&schedule);
+		let jobs: Vec<usize> = merged.get_jobs().iter().map(|scheduled| scheduled.job).collect();
+		assert_eq!(vec![1, 0, 1], jobs);
+		assert_eq!(Some(13), merged.get_minimum_slack(&segmentation.original_problem));
+	}
+}
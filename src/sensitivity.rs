@@ -0,0 +1,94 @@
+use crate::problem::*;
+
+/// Returns a copy of `problem` in which the execution time of each job in `jobs` is multiplied by
+/// `percentage / 100`, rounded up
+pub fn inflate_execution_times(problem: &Problem, jobs: &[usize], percentage: Time) -> Problem {
+	let mut inflated = problem.clone();
+	for &job in jobs {
+		let execution_time = problem.jobs[job].get_execution_time();
+		inflated.jobs[job].set_execution_time((execution_time * percentage + 99) / 100);
+	}
+	inflated
+}
+
+/// Binary-searches the largest percentage by which the execution times of `jobs` can be inflated
+/// (see `inflate_execution_times`) such that `is_acceptable` still returns `true` for the inflated
+/// problem. Returns `None` when `is_acceptable` rejects the original problem.
+///
+/// A typical `is_acceptable` returns `!is_proven_infeasible(problem)`, possibly combined with a
+/// scheduling policy or exact method that must still find a schedule. Note that the result is only
+/// exact when `is_acceptable` is monotone, which scheduling policies are generally not.
+pub fn find_maximum_inflation(
+	problem: &Problem, jobs: &[usize], mut is_acceptable: impl FnMut(&Problem) -> bool
+) -> Option<Time> {
+	if !is_acceptable(problem) {
+		return None;
+	}
+
+	// Any job whose execution time exceeds its window is certainly infeasible
+	let mut max_percentage = Time::MAX;
+	for &job in jobs {
+		let job = &problem.jobs[job];
+		let window = job.get_latest_finish() - job.earliest_start;
+		max_percentage = Time::min(max_percentage, 100 * window / job.get_execution_time());
+	}
+	if max_percentage == Time::MAX {
+		return Some(100);
+	}
+
+	let mut min_percentage = 100;
+	while min_percentage < max_percentage {
+		let percentage = min_percentage + (max_percentage - min_percentage + 1) / 2;
+		if is_acceptable(&inflate_execution_times(problem, jobs, percentage)) {
+			min_percentage = percentage;
+		} else {
+			max_percentage = percentage - 1;
+		}
+	}
+	Some(min_percentage)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::necessary::is_proven_infeasible;
+	use crate::sensitivity::*;
+
+	fn create_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 40),
+				Job::release_to_deadline(1, 0, 20, 40),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn test_inflate_execution_times() {
+		let problem = create_problem();
+		let inflated = inflate_execution_times(&problem, &[1], 133);
+		assert_eq!(10, inflated.jobs[0].get_execution_time());
+		assert_eq!(27, inflated.jobs[1].get_execution_time());
+		assert_eq!(40, inflated.jobs[1].get_latest_finish());
+	}
+
+	#[test]
+	fn test_find_maximum_inflation() {
+		let problem = create_problem();
+		assert!(!is_proven_infeasible(&problem));
+		let is_acceptable = |problem: &Problem| !is_proven_infeasible(problem);
+
+		// Job 0 can grow until 20, since job 1 must finish before 40
+		assert_eq!(Some(200), find_maximum_inflation(&problem, &[0], is_acceptable));
+
+		// Job 1 can grow until 30
+		assert_eq!(Some(150), find_maximum_inflation(&problem, &[1], is_acceptable));
+
+		// Together, they can grow until 13 + 26 = 39, since 131% would give 14 + 27 = 41
+		assert_eq!(Some(130), find_maximum_inflation(&problem, &[0, 1], is_acceptable));
+
+		assert_eq!(None, find_maximum_inflation(&problem, &[0], |_| false));
+	}
+}
@@ -1,5 +1,18 @@
 use crate::problem::Time;
 
+/// Remembers the state that was overwritten by `CoreAvailability::schedule`, which is needed to undo
+/// it using `CoreAvailability::undo`.
+#[derive(Clone, Debug)]
+pub struct ScheduleUndo {
+	finish_time: Time,
+	replaced_finish_time: Time,
+
+	/// The other finish times that were replaced by a gang job, which is empty (and doesn't allocate)
+	/// for ordinary jobs
+	other_replaced_finish_times: Vec<Time>,
+	last_start_time: Time,
+}
+
 #[derive(Clone)]
 pub struct CoreAvailability {
 	finish_times: Vec<Time>,
@@ -14,6 +27,14 @@ impl CoreAvailability {
 		}
 	}
 
+	/// Creates the availability of a platform whose cores are already busy: core `i` is busy until
+	/// `busy_until[i]`
+	pub fn with_busy_cores(busy_until: &[Time]) -> Self {
+		let mut finish_times = busy_until.to_vec();
+		finish_times.sort();
+		Self { finish_times, last_start_time: 0 }
+	}
+
 	pub fn next_start_time(&self) -> Time {
 		Time::max(self.finish_times[0], self.last_start_time)
 	}
@@ -22,11 +43,81 @@ impl CoreAvailability {
 		Time::max(self.finish_times[1], self.last_start_time)
 	}
 
-	pub fn schedule(&mut self, start: Time, duration: Time) {
-		debug_assert!(start >= self.next_start_time());
-		self.finish_times[0] = start + duration;
+	/// Gets the earliest time at which the next job can start when it needs `parallelism` cores at
+	/// the same time. This must not exceed the number of cores.
+	pub fn parallel_start_time(&self, parallelism: u32) -> Time {
+		Time::max(self.finish_times[parallelism as usize - 1], self.last_start_time)
+	}
+
+	pub fn schedule(&mut self, start: Time, duration: Time) -> ScheduleUndo {
+		self.schedule_parallel(start, duration, 1)
+	}
+
+	/// Schedules a job that occupies `parallelism` cores from `start` until `start + duration`
+	pub fn schedule_parallel(&mut self, start: Time, duration: Time, parallelism: u32) -> ScheduleUndo {
+		debug_assert!(start >= self.parallel_start_time(parallelism));
+		let undo = ScheduleUndo {
+			finish_time: start + duration,
+			replaced_finish_time: self.finish_times[0],
+			other_replaced_finish_times: self.finish_times[1 .. parallelism as usize].to_vec(),
+			last_start_time: self.last_start_time,
+		};
+		self.finish_times[0 .. parallelism as usize].fill(start + duration);
 		self.finish_times.sort();
 		self.last_start_time = start;
+		undo
+	}
+
+	/// Schedules a job that occupies the cores whose finish times are `replaced` from `start` until
+	/// `start + duration`. Unlike `schedule_parallel`, these don't need to be the cores that become
+	/// available first, which is needed for jobs that can only run on some of the cores.
+	pub fn schedule_replacing(&mut self, start: Time, duration: Time, replaced: &[Time]) -> ScheduleUndo {
+		debug_assert!(start >= self.last_start_time);
+		let undo = ScheduleUndo {
+			finish_time: start + duration,
+			replaced_finish_time: replaced[0],
+			other_replaced_finish_times: replaced[1 ..].to_vec(),
+			last_start_time: self.last_start_time,
+		};
+		for replaced_finish_time in replaced {
+			debug_assert!(*replaced_finish_time <= start);
+			let index = self.finish_times.binary_search(replaced_finish_time)
+				.expect("Only the finish times of cores can be replaced");
+			self.finish_times.remove(index);
+		}
+		self.finish_times.resize(self.finish_times.len() + replaced.len(), start + duration);
+		self.finish_times.sort();
+		self.last_start_time = start;
+		undo
+	}
+
+	/// Undoes a call to `schedule`, `schedule_parallel`, or `schedule_replacing`. The calls must be
+	/// undone in the reverse order in which they were made.
+	pub fn undo(&mut self, undo: ScheduleUndo) {
+		for _ in 0 ..= undo.other_replaced_finish_times.len() {
+			let index = self.finish_times.binary_search(&undo.finish_time)
+				.expect("Calls to schedule must be undone in reverse order");
+			self.finish_times.remove(index);
+		}
+
+		for replaced_finish_time in std::iter::once(undo.replaced_finish_time).chain(undo.other_replaced_finish_times) {
+			let index = self.finish_times.partition_point(|finish_time| *finish_time < replaced_finish_time);
+			self.finish_times.insert(index, replaced_finish_time);
+		}
+		self.last_start_time = undo.last_start_time;
+	}
+
+	/// Ensures that the next job cannot start before `time`, and returns the previous
+	/// `last_start_time`, which can be passed to `undo_idle`
+	pub fn idle_until(&mut self, time: Time) -> Time {
+		let last_start_time = self.last_start_time;
+		self.last_start_time = Time::max(self.last_start_time, time);
+		last_start_time
+	}
+
+	/// Undoes a call to `idle_until`
+	pub fn undo_idle(&mut self, last_start_time: Time) {
+		self.last_start_time = last_start_time;
 	}
 
 	pub fn merge(&mut self, other: &Self) {
@@ -130,6 +221,95 @@ mod tests {
 		assert_eq!(2, availability2.get_num_cores());
 	}
 
+	#[test]
+	fn test_undo() {
+		let mut availability = CoreAvailability::new(3);
+		availability.schedule(0, 10);
+		let undo1 = availability.schedule(0, 5);
+		let undo2 = availability.schedule(0, 20);
+		let undo3 = availability.schedule(7, 1);
+		assert_eq!(8, availability.next_start_time());
+		assert_eq!(10, availability.second_start_time());
+
+		availability.undo(undo3);
+		assert_eq!(5, availability.next_start_time());
+		assert_eq!(10, availability.second_start_time());
+
+		availability.undo(undo2);
+		assert_eq!(0, availability.next_start_time());
+		assert_eq!(5, availability.second_start_time());
+
+		availability.undo(undo1);
+		assert_eq!(0, availability.next_start_time());
+		assert_eq!(0, availability.second_start_time());
+
+		availability.schedule(2, 3);
+		assert_eq!(2, availability.next_start_time());
+		assert_eq!(5, availability.second_start_time());
+	}
+
+	#[test]
+	fn test_parallel_jobs() {
+		let mut availability = CoreAvailability::new(3);
+		availability.schedule(0, 10);
+		assert_eq!(0, availability.parallel_start_time(2));
+		assert_eq!(10, availability.parallel_start_time(3));
+
+		let undo1 = availability.schedule_parallel(2, 5, 2);
+		assert_eq!(7, availability.next_start_time());
+		assert_eq!(7, availability.second_start_time());
+		assert_eq!(10, availability.parallel_start_time(3));
+
+		let undo2 = availability.schedule_parallel(10, 1, 3);
+		assert_eq!(11, availability.parallel_start_time(3));
+
+		availability.undo(undo2);
+		assert_eq!(10, availability.parallel_start_time(3));
+		availability.undo(undo1);
+		assert_eq!(0, availability.next_start_time());
+		assert_eq!(0, availability.parallel_start_time(2));
+		assert_eq!(10, availability.parallel_start_time(3));
+	}
+
+	#[test]
+	fn test_schedule_replacing() {
+		let mut availability = CoreAvailability::with_busy_cores(&[0, 5, 8]);
+		let undo = availability.schedule_replacing(8, 2, &[5, 8]);
+		assert_eq!(8, availability.next_start_time());
+		assert_eq!(10, availability.second_start_time());
+		assert_eq!(10, availability.parallel_start_time(3));
+
+		availability.undo(undo);
+		assert_eq!(0, availability.next_start_time());
+		assert_eq!(5, availability.second_start_time());
+		assert_eq!(8, availability.parallel_start_time(3));
+	}
+
+	#[test]
+	fn test_idle_until() {
+		let mut availability = CoreAvailability::new(2);
+		availability.schedule(0, 10);
+		let last_start_time = availability.idle_until(5);
+		assert_eq!(0, last_start_time);
+		assert_eq!(5, availability.next_start_time());
+		assert_eq!(10, availability.second_start_time());
+
+		availability.undo_idle(last_start_time);
+		assert_eq!(0, availability.next_start_time());
+	}
+
+	#[test]
+	fn test_with_busy_cores() {
+		let mut availability = CoreAvailability::with_busy_cores(&[8, 0, 5]);
+		assert_eq!(3, availability.get_num_cores());
+		assert_eq!(0, availability.next_start_time());
+		assert_eq!(5, availability.second_start_time());
+
+		availability.schedule(1, 10);
+		assert_eq!(5, availability.next_start_time());
+		assert_eq!(8, availability.second_start_time());
+	}
+
 	#[test]
 	fn test_get_number_of_cores() {
 		for num_cores in 1 .. 100 {
@@ -1,16 +1,40 @@
-mod core_availability;
+pub mod core_availability;
+mod monte_carlo;
+mod policy;
+mod verify;
 
+use crate::index_set::DenseIndexSet;
 use crate::problem::*;
-use crate::simulator::core_availability::CoreAvailability;
+use crate::schedule::{Schedule, ScheduledJob};
+use crate::simulator::core_availability::{CoreAvailability, ScheduleUndo};
 
-fn create_predecessor_mapping(problem: &Problem) -> (Vec<Vec<Constraint>>, Time) {
-	let mut maximum_suspension = 0;
+pub use monte_carlo::*;
+pub use policy::*;
+pub use verify::*;
+
+/// The largest delays of the constraints whose `before` job is a given job. These determine when
+/// that job can no longer influence the start time of any of its successors.
+#[derive(Clone, Copy, Default)]
+struct SuccessorDelays {
+	/// The largest delay of the start-to-start constraints
+	after_start: Option<Time>,
+	/// The largest delay of the finish-to-start constraints
+	after_finish: Option<Time>,
+}
+
+fn create_predecessor_mapping(problem: &Problem) -> (Vec<Vec<Constraint>>, Vec<SuccessorDelays>) {
 	let mut mapping = vec![Vec::<Constraint>::new(); problem.jobs.len()];
+	let mut successor_delays = vec![SuccessorDelays::default(); problem.jobs.len()];
 	for constraint in &problem.constraints {
 		mapping[constraint.get_after()].push(*constraint);
-		maximum_suspension = Time::max(maximum_suspension, constraint.get_delay());
+		let delays = &mut successor_delays[constraint.get_before()];
+		let delay = match constraint.get_type() {
+			ConstraintType::StartToStart => &mut delays.after_start,
+			ConstraintType::FinishToStart => &mut delays.after_finish,
+		};
+		*delay = Some(Time::max(delay.unwrap_or(constraint.get_delay()), constraint.get_delay()));
 	}
-	(mapping, maximum_suspension)
+	(mapping, successor_delays)
 }
 
 #[derive(Clone, Copy)]
@@ -18,42 +42,209 @@ struct RunningJob {
 	job: usize,
 	started_at: Time,
 	finishes_at: Time,
+
+	/// Once the next job can't start before this time, all constraints whose `before` job is this
+	/// job are certainly satisfied, so this job can be moved to `finished_jobs`
+	retires_at: Time,
+}
+
+/// The information that is needed to undo a single call to `Simulator::schedule` or
+/// `Simulator::idle_until`
+#[derive(Clone)]
+enum UndoEntry {
+	Schedule {
+		job: usize,
+		core_availability: ScheduleUndo,
+
+		/// The number of jobs that were moved from `running_jobs` to `finished_jobs`
+		num_retired_jobs: usize,
+		missed_deadline: bool,
+	},
+	Idle {
+		last_start_time: Time,
+	},
+}
+
+/// Records the start time, finish time, and core of every job that is scheduled by a `Simulator`.
+/// Unlike `CoreAvailability`, this keeps track of the identity of each core, which is also needed
+/// to respect the affinities of jobs.
+#[derive(Clone)]
+struct ScheduleRecorder {
+	schedule: Schedule,
+	core_finish_times: Vec<Time>,
+
+	/// The finish time of the core of `schedule.get_jobs()[i]` before that job was scheduled
+	replaced_finish_times: Vec<Time>,
+}
+
+impl ScheduleRecorder {
+	fn new(num_cores: usize) -> Self {
+		Self { schedule: Schedule::new(), core_finish_times: vec![0; num_cores], replaced_finish_times: Vec::new() }
+	}
+
+	/// Gets the cores in the affinity of `job`, sorted by the time at which they become available
+	fn get_allowed_cores(&self, job: Job) -> Vec<usize> {
+		let mut cores: Vec<usize> = job.get_affinity().get_cores(self.core_finish_times.len() as u32).collect();
+		cores.sort_by_key(|core| self.core_finish_times[*core]);
+		cores
+	}
+
+	/// Gets the earliest time at which enough cores in the affinity of `job` are available
+	fn get_affinity_start_time(&self, job: Job) -> Time {
+		let cores = self.get_allowed_cores(job);
+		self.core_finish_times[cores[job.get_parallelism() as usize - 1]]
+	}
+
+	/// Records that `job` starts at time `start`, and returns the finish times of the cores that it
+	/// replaced. A gang job is recorded once for each core that it occupies.
+	fn record(&mut self, job: Job, start: Time) -> &[Time] {
+		// Like CoreAvailability, put the job on the (allowed) cores that became available first
+		let cores = self.get_allowed_cores(job);
+		let parallelism = job.get_parallelism() as usize;
+
+		let finish = start + job.get_execution_time();
+		for &core in &cores[.. parallelism] {
+			debug_assert!(self.core_finish_times[core] <= start);
+			self.replaced_finish_times.push(self.core_finish_times[core]);
+			self.core_finish_times[core] = finish;
+			self.schedule.push(ScheduledJob { job: job.get_index(), start, finish, core });
+		}
+		&self.replaced_finish_times[self.replaced_finish_times.len() - parallelism ..]
+	}
+
+	/// Undoes the last call to `record`
+	fn undo(&mut self) {
+		let job = self.schedule.get_jobs().last().expect("There should be a job to undo").job;
+		while self.schedule.get_jobs().last().is_some_and(|scheduled| scheduled.job == job) {
+			let scheduled = self.schedule.pop().unwrap();
+			self.core_finish_times[scheduled.core] = self.replaced_finish_times.pop().unwrap();
+		}
+	}
+}
+
+/// A point in the history of a `Simulator`, to which it can be rolled back using
+/// `Simulator::rollback`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SimulatorCheckpoint {
+	undo_log_length: usize,
 }
 
 #[derive(Clone)]
 pub struct Simulator {
-	finished_jobs: Vec<bool>, // TODO Create IndexSet struct for this
+	finished_jobs: DenseIndexSet,
 	running_jobs: Vec<RunningJob>,
+
+	/// The number of jobs in `finished_jobs` that were passed to `set_initial_state`. These jobs
+	/// are never dispatched by this simulator.
+	num_initially_finished_jobs: usize,
+
+	/// The start and finish time of each job that has been scheduled, indexed by job
+	dispatched_jobs: Vec<Option<RunningJob>>,
 	core_availability: CoreAvailability,
 	predecessor_mapping: Vec<Vec<Constraint>>,
-	maximum_suspension: Time,
-	num_finished_jobs: usize,
+	successor_delays: Vec<SuccessorDelays>,
+
+	/// The shared resources that are used by each job
+	job_resources: Vec<Vec<usize>>,
+	/// The time at which each shared resource becomes available
+	resource_availability: Vec<Time>,
+	/// The previous availabilities of the resources that were claimed by `schedule`
+	replaced_resource_availability: Vec<Time>,
+
 	missed_deadline: bool,
+
+	undo_log: Vec<UndoEntry>,
+	retired_jobs: Vec<RunningJob>,
+
+	recorder: Option<ScheduleRecorder>,
+
+	/// The simulation of the same dispatches in which every job runs for its best-case execution
+	/// time, or `None` when no job can finish early. The start and finish times of each job in
+	/// `best_case` are its earliest ones, and those in this simulator its latest ones. Maximum
+	/// delays are checked in both simulations, since a predecessor that finishes early can leave
+	/// a longer gap before its successor starts.
+	best_case: Option<Box<Simulator>>,
 }
 
 impl Simulator {
 	pub fn new(problem: &Problem) -> Self {
-		let (predecessor_mapping, maximum_suspension) = create_predecessor_mapping(problem);
+		assert!(
+			problem.jobs.iter().all(|job| job.get_parallelism() <= problem.num_cores),
+			"The simulator can't handle jobs that need more cores than the problem has"
+		);
+		let (predecessor_mapping, successor_delays) = create_predecessor_mapping(problem);
+		let mut job_resources = vec![Vec::new(); problem.jobs.len()];
+		for (resource, users) in problem.resources.iter().enumerate() {
+			for &job in users {
+				job_resources[job].push(resource);
+			}
+		}
 		Self {
-			finished_jobs: vec![false; problem.jobs.len()],
+			finished_jobs: DenseIndexSet::new(problem.jobs.len()),
 			running_jobs: Vec::new(),
+			num_initially_finished_jobs: 0,
+			dispatched_jobs: vec![None; problem.jobs.len()],
 			core_availability: CoreAvailability::new(problem.num_cores as usize),
 			predecessor_mapping,
-			maximum_suspension,
-			num_finished_jobs: 0,
+			successor_delays,
+			job_resources,
+			resource_availability: vec![0; problem.resources.len()],
+			replaced_resource_availability: Vec::new(),
 			missed_deadline: false,
+			undo_log: Vec::new(),
+			retired_jobs: Vec::new(),
+			recorder: problem.has_affinities().then(|| ScheduleRecorder::new(problem.num_cores as usize)),
+			best_case: problem.has_execution_time_variation().then(|| {
+				let mut best_case_problem = problem.clone();
+				for job in &mut best_case_problem.jobs {
+					*job = job.get_best_case();
+				}
+				Box::new(Self::new(&best_case_problem))
+			}),
 		}
 	}
 
+	/// Creates a simulator that also records the start time, finish time, and core of every job
+	/// that it schedules. The recorded schedule can be obtained using `get_schedule`.
+	pub fn new_recording(problem: &Problem) -> Self {
+		let mut simulator = Self::new(problem);
+		simulator.recorder = Some(ScheduleRecorder::new(problem.num_cores as usize));
+		simulator
+	}
+
+	/// Starts the simulation from a platform that is already executing other work: core `i` is busy
+	/// until `busy_until[i]`, and the jobs in `finished_jobs` are considered to be finished already,
+	/// so they will never delay their successors. This must be called before scheduling any job.
+	pub fn set_initial_state(&mut self, busy_until: &[Time], finished_jobs: &[usize]) {
+		assert!(self.undo_log.is_empty(), "The initial state must be set before scheduling jobs");
+		assert_eq!(self.core_availability.get_num_cores(), busy_until.len());
+
+		self.core_availability = CoreAvailability::with_busy_cores(busy_until);
+		if let Some(recorder) = &mut self.recorder {
+			recorder.core_finish_times = busy_until.to_vec();
+		}
+		for &job in finished_jobs {
+			assert!(self.finished_jobs.insert(job), "Job {} is finished more than once", job);
+		}
+		self.num_initially_finished_jobs = finished_jobs.len();
+		if let Some(best_case) = &mut self.best_case {
+			best_case.set_initial_state(busy_until, finished_jobs);
+		}
+	}
+
+	/// Predicts the time at which `job` would start if it were scheduled next. Constraint delays are
+	/// treated as suspensions: they postpone the successor, but they don't occupy the core of the
+	/// predecessor, which is available to other jobs as soon as the predecessor finishes. The job
+	/// can't start before all its shared resources have been released by the jobs that use them,
+	/// and a gang job can't start before enough cores (in its affinity) are available.
 	pub fn predict_start_time(&self, job: Job) -> Time {
 		let mut ready_time = job.earliest_start;
 		for constraint in &self.predecessor_mapping[job.get_index()] {
-			if self.finished_jobs[constraint.get_before()] {
+			if self.finished_jobs.contains(constraint.get_before()) {
 				continue;
 			}
-			let running_job = self.running_jobs.iter().find(
-				|rj| rj.job == constraint.get_before()
-			).expect("All predecessors should have started already");
+			let running_job = self.dispatched_jobs[constraint.get_before()]
+				.expect("All predecessors should have started already");
 			let mut ready_bound = constraint.get_delay();
 			if constraint.get_type() == ConstraintType::FinishToStart {
 				ready_bound += running_job.finishes_at;
@@ -63,56 +254,208 @@ impl Simulator {
 			ready_time = Time::max(ready_time, ready_bound);
 		}
 
-		Time::max(ready_time, self.core_availability.next_start_time())
+		for &resource in &self.job_resources[job.get_index()] {
+			ready_time = Time::max(ready_time, self.resource_availability[resource]);
+		}
+
+		ready_time = Time::max(ready_time, self.core_availability.parallel_start_time(job.get_parallelism()));
+		if !job.get_affinity().is_all() {
+			let recorder = self.recorder.as_ref().expect("Simulators of problems with affinities should record");
+			ready_time = Time::max(ready_time, recorder.get_affinity_start_time(job));
+		}
+		ready_time
 	}
 
 	pub fn predict_next_start_time(&self, job: Job) -> Time {
 		let current_start_time = self.predict_start_time(job);
 		let mut next_start_time = current_start_time + job.get_execution_time();
-		if self.core_availability.get_num_cores() > 1 {
-			next_start_time = Time::min(next_start_time, self.core_availability.second_start_time());
+		let parallelism = job.get_parallelism();
+		if self.core_availability.get_num_cores() > parallelism as usize {
+			next_start_time = Time::min(next_start_time, self.core_availability.parallel_start_time(parallelism + 1));
 		}
 		Time::max(current_start_time, next_start_time)
 	}
 
+	/// Finds a constraint whose maximum delay would be exceeded when `job` starts at `start_time`
+	fn find_exceeded_max_delay(&self, job: usize, start_time: Time) -> Option<Constraint> {
+		self.predecessor_mapping[job].iter().find(|constraint| {
+			let (Some(max_delay), Some(before)) = (constraint.get_max_delay(), self.dispatched_jobs[constraint.get_before()]) else {
+				return false;
+			};
+			let reference_time = match constraint.get_type() {
+				ConstraintType::StartToStart => before.started_at,
+				ConstraintType::FinishToStart => before.finishes_at,
+			};
+			start_time > reference_time + max_delay
+		}).copied()
+	}
+
+	/// Like `find_exceeded_max_delay`, but when scheduling `job` next, in this simulation or in the
+	/// simulation with best-case execution times
+	fn find_exceeded_max_delay_in_any_case(&self, job: Job) -> Option<Constraint> {
+		self.find_exceeded_max_delay(job.get_index(), self.predict_start_time(job)).or_else(
+			|| self.best_case.as_ref().and_then(|best_case| best_case.find_exceeded_max_delay_in_any_case(job.get_best_case()))
+		)
+	}
+
+	/// Schedules `job` at the time given by `predict_start_time`. A deadline is missed when the job
+	/// starts after its `latest_start`, or when it starts too long after a predecessor with a maximum
+	/// delay. The latter is checked both when all jobs run for their (worst-case) execution time,
+	/// and when all jobs run for their best-case execution time. Scenarios in which only some jobs
+	/// finish early are not simulated.
 	pub fn schedule(&mut self, job: Job) {
 		let start_time = self.predict_start_time(job);
+		let missed_deadline = self.missed_deadline;
 		if start_time > job.latest_start {
 			self.missed_deadline = true;
 		}
+		// Starting as early as possible may exceed the maximum delay after a predecessor
+		if self.find_exceeded_max_delay(job.get_index(), start_time).is_some() {
+			self.missed_deadline = true;
+		}
 		debug_assert!(start_time >= job.earliest_start);
-		self.core_availability.schedule(start_time, job.get_execution_time());
+		let replaced_finish_times = self.recorder.as_mut().map(|recorder| recorder.record(job, start_time));
+		let core_availability = match replaced_finish_times {
+			// A job with an affinity may need to skip cores that become available earlier
+			Some(replaced_finish_times) if !job.get_affinity().is_all() => self.core_availability.schedule_replacing(
+				start_time, job.get_execution_time(), replaced_finish_times
+			),
+			_ => self.core_availability.schedule_parallel(start_time, job.get_execution_time(), job.get_parallelism()),
+		};
 
+		let mut num_retired_jobs = 0;
 		let mut index = 0;
 		while index < self.running_jobs.len() {
 			let running_job = self.running_jobs[index];
-			if self.core_availability.next_start_time() >= running_job.finishes_at + self.maximum_suspension {
-				debug_assert!(!self.finished_jobs[running_job.job]);
-				self.finished_jobs[running_job.job] = true;
-				self.num_finished_jobs += 1;
+			if self.core_availability.next_start_time() >= running_job.retires_at {
+				let is_new = self.finished_jobs.insert(running_job.job);
+				debug_assert!(is_new);
 				self.running_jobs.swap_remove(index);
+				self.retired_jobs.push(running_job);
+				num_retired_jobs += 1;
 			} else {
 				index += 1;
 			}
 		}
 
-		self.running_jobs.push(RunningJob {
+		self.undo_log.push(UndoEntry::Schedule {
+			job: job.get_index(), core_availability, num_retired_jobs, missed_deadline
+		});
+
+		let finish_time = start_time + job.get_execution_time();
+		for &resource in &self.job_resources[job.get_index()] {
+			self.replaced_resource_availability.push(self.resource_availability[resource]);
+			self.resource_availability[resource] = finish_time;
+		}
+
+		let delays = self.successor_delays[job.get_index()];
+		let retires_at = Time::max(
+			delays.after_start.map_or(start_time, |delay| start_time + delay),
+			delays.after_finish.map_or(start_time, |delay| finish_time + delay)
+		);
+		let running_job = RunningJob {
 			job: job.get_index(),
 			started_at: start_time,
-			finishes_at: start_time + job.get_execution_time()
-		})
+			finishes_at: finish_time,
+			retires_at,
+		};
+		debug_assert!(self.dispatched_jobs[job.get_index()].is_none());
+		self.dispatched_jobs[job.get_index()] = Some(running_job);
+		self.running_jobs.push(running_job);
+		if let Some(best_case) = &mut self.best_case {
+			best_case.schedule(job.get_best_case());
+		}
+	}
+
+	/// Leaves all cores idle until `time`: the next job will not be started before `time`, even when
+	/// it could start earlier. Some problems are only feasible when a core waits for an urgent job,
+	/// instead of starting a less urgent job that is already available.
+	pub fn idle_until(&mut self, time: Time) {
+		let last_start_time = self.core_availability.idle_until(time);
+		self.undo_log.push(UndoEntry::Idle { last_start_time });
+		if let Some(best_case) = &mut self.best_case {
+			best_case.idle_until(time);
+		}
+	}
+
+	/// Creates a checkpoint of the current state of this simulator. Calling `rollback` with this
+	/// checkpoint will undo all calls to `schedule` and `idle_until` that happened after this
+	/// checkpoint was created.
+	///
+	/// This is much cheaper than cloning the simulator, which makes it suitable for depth-first
+	/// searches that need to backtrack.
+	pub fn checkpoint(&self) -> SimulatorCheckpoint {
+		SimulatorCheckpoint { undo_log_length: self.undo_log.len() }
+	}
+
+	/// Restores the state of this simulator to the moment at which `checkpoint` was created. Note
+	/// that rolling back to a checkpoint invalidates all checkpoints that were created after it.
+	pub fn rollback(&mut self, checkpoint: SimulatorCheckpoint) {
+		assert!(checkpoint.undo_log_length <= self.undo_log.len());
+		while self.undo_log.len() > checkpoint.undo_log_length {
+			match self.undo_log.pop().unwrap() {
+				UndoEntry::Schedule { job, core_availability, num_retired_jobs, missed_deadline } => {
+					let running_index = self.running_jobs.iter().position(|rj| rj.job == job)
+						.expect("The job that was scheduled last should still be running");
+					self.running_jobs.swap_remove(running_index);
+					self.dispatched_jobs[job] = None;
+					for &resource in self.job_resources[job].iter().rev() {
+						self.resource_availability[resource] = self.replaced_resource_availability.pop().unwrap();
+					}
+
+					for _ in 0 .. num_retired_jobs {
+						let retired_job = self.retired_jobs.pop().unwrap();
+						let was_finished = self.finished_jobs.remove(retired_job.job);
+						debug_assert!(was_finished);
+						self.running_jobs.push(retired_job);
+					}
+
+					self.core_availability.undo(core_availability);
+					if let Some(recorder) = &mut self.recorder {
+						recorder.undo();
+					}
+					self.missed_deadline = missed_deadline;
+				},
+				UndoEntry::Idle { last_start_time } => self.core_availability.undo_idle(last_start_time),
+			}
+		}
+		if let Some(best_case) = &mut self.best_case {
+			best_case.rollback(checkpoint);
+		}
 	}
 
 	pub fn next_core_available(&self) -> Time {
 		self.core_availability.next_start_time()
 	}
 
+	/// Checks whether a job missed its deadline or exceeded a maximum delay (see `schedule`)
 	pub fn has_missed_deadline(&self) -> bool {
-		self.missed_deadline
+		self.missed_deadline || self.best_case.as_ref().is_some_and(|best_case| best_case.has_missed_deadline())
 	}
 
+	/// Gets the earliest and latest finish time of `job`, or `None` when it hasn't been scheduled.
+	/// The earliest finish time is reached when all jobs run for their best-case execution time,
+	/// and the latest finish time when they run for their (worst-case) execution time.
+	pub fn get_finish_interval(&self, job: usize) -> Option<(Time, Time)> {
+		let latest = self.dispatched_jobs[job]?;
+		let earliest = match &self.best_case {
+			Some(best_case) => best_case.dispatched_jobs[job].expect("Both simulations should have scheduled the job"),
+			None => latest,
+		};
+		Some((earliest.finishes_at, latest.finishes_at))
+	}
+
+	/// Gets the number of jobs that have been scheduled by this simulator. The jobs that were
+	/// finished in the initial state are not counted.
 	pub fn num_dispatched_jobs(&self) -> usize {
-		self.num_finished_jobs + self.running_jobs.len()
+		self.finished_jobs.len() - self.num_initially_finished_jobs + self.running_jobs.len()
+	}
+
+	/// Gets the schedule of all jobs that have been scheduled so far, in the order in which they
+	/// were scheduled. Returns `None` unless this simulator was created using `new_recording`, or
+	/// its problem has affinities.
+	pub fn get_schedule(&self) -> Option<&Schedule> {
+		self.recorder.as_ref().map(|recorder| &recorder.schedule)
 	}
 }
 
@@ -120,6 +463,7 @@ impl Simulator {
 mod tests {
 	use crate::bounds::strengthen_bounds_using_constraints;
 	use crate::problem::*;
+	use crate::schedule::ScheduleViolation;
 	use crate::simulator::Simulator;
 
 	#[test]
@@ -130,7 +474,8 @@ mod tests {
 				Job::release_to_deadline(1, 10, 30, 50)
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		problem.validate();
 
@@ -168,7 +513,8 @@ mod tests {
 				Job::release_to_deadline(8, 0, 13, 60),
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		problem.validate();
 
@@ -205,7 +551,8 @@ mod tests {
 			constraints: vec![
 				Constraint::new(0, 1, 2, ConstraintType::FinishToStart)
 			],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		problem.validate();
 		strengthen_bounds_using_constraints(&mut problem);
@@ -239,7 +586,8 @@ mod tests {
 				Constraint::new(0, 1, 2, ConstraintType::StartToStart),
 				Constraint::new(0, 2, 10, ConstraintType::FinishToStart)
 			],
-			num_cores: 2
+			num_cores: 2,
+			resources: Vec::new()
 		};
 		problem.validate();
 		strengthen_bounds_using_constraints(&mut problem);
@@ -260,6 +608,324 @@ mod tests {
 		assert_eq!(3, good_simulator.num_dispatched_jobs());
 	}
 
+	#[test]
+	fn test_rollback() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 20),
+				Job::release_to_deadline(1, 0, 30, 32),
+				Job::release_to_deadline(2, 0, 30, 100),
+				Job::release_to_deadline(3, 5, 10, 60),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 2, ConstraintType::StartToStart),
+				Constraint::new(0, 2, 10, ConstraintType::FinishToStart)
+			],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		problem.validate();
+		strengthen_bounds_using_constraints(&mut problem);
+
+		let mut simulator = Simulator::new(&problem);
+		let initial = simulator.checkpoint();
+		simulator.schedule(problem.jobs[0]);
+		let after_first_job = simulator.checkpoint();
+
+		simulator.schedule(problem.jobs[2]);
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[3]);
+		assert!(simulator.has_missed_deadline());
+		assert_eq!(4, simulator.num_dispatched_jobs());
+
+		simulator.rollback(after_first_job);
+		assert!(!simulator.has_missed_deadline());
+		assert_eq!(1, simulator.num_dispatched_jobs());
+
+		let mut reference = Simulator::new(&problem);
+		reference.schedule(problem.jobs[0]);
+		for job in &problem.jobs[1..] {
+			assert_eq!(reference.predict_start_time(*job), simulator.predict_start_time(*job));
+			assert_eq!(reference.predict_next_start_time(*job), simulator.predict_next_start_time(*job));
+		}
+
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[2]);
+		simulator.schedule(problem.jobs[3]);
+		assert!(!simulator.has_missed_deadline());
+		assert_eq!(4, simulator.num_dispatched_jobs());
+
+		simulator.rollback(initial);
+		assert_eq!(0, simulator.num_dispatched_jobs());
+		assert_eq!(0, simulator.next_core_available());
+		assert_eq!(0, simulator.predict_start_time(problem.jobs[0]));
+	}
+
+	#[test]
+	fn test_jobs_retire_based_on_own_successors() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 0, 10, 1000),
+				Job::release_to_deadline(3, 0, 10, 100),
+			],
+			constraints: vec![
+				Constraint::new(0, 3, 5, ConstraintType::StartToStart),
+				Constraint::new(1, 2, 500, ConstraintType::FinishToStart),
+			],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		problem.validate();
+
+		let mut simulator = Simulator::new(&problem);
+		simulator.schedule(problem.jobs[0]);
+		simulator.schedule(problem.jobs[1]);
+
+		// Job 0 no longer matters, but job 1 must stay running because of its large delay
+		assert_eq!(1, simulator.running_jobs.len());
+		assert_eq!(1, simulator.running_jobs[0].job);
+		assert_eq!(20, simulator.predict_start_time(problem.jobs[3]));
+		assert_eq!(520, simulator.predict_start_time(problem.jobs[2]));
+	}
+
+	#[test]
+	fn test_delays_do_not_occupy_cores() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 70),
+				Job::release_to_deadline(2, 0, 30, 40),
+			],
+			constraints: vec![Constraint::new(0, 1, 50, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		problem.validate();
+
+		let mut simulator = Simulator::new_recording(&problem);
+		simulator.schedule(problem.jobs[0]);
+		assert_eq!(10, simulator.predict_start_time(problem.jobs[2]));
+		assert_eq!(60, simulator.predict_start_time(problem.jobs[1]));
+
+		// Job 2 runs on the only core while job 1 waits for its delay
+		simulator.schedule(problem.jobs[2]);
+		assert_eq!(60, simulator.predict_start_time(problem.jobs[1]));
+		simulator.schedule(problem.jobs[1]);
+		assert!(!simulator.has_missed_deadline());
+		assert_eq!(Ok(()), simulator.get_schedule().unwrap().validate(&problem));
+	}
+
+	#[test]
+	fn test_max_delays() {
+		let mut bounded = Constraint::new(0, 1, 0, ConstraintType::FinishToStart);
+		bounded.set_max_delay(Some(15));
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 0, 20, 100),
+			],
+			constraints: vec![bounded],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+
+		// Job 1 can start 20 time units after job 0 finished, which exceeds the maximum delay
+		let mut simulator = Simulator::new_recording(&problem);
+		simulator.schedule(problem.jobs[0]);
+		simulator.schedule(problem.jobs[2]);
+		assert!(!simulator.has_missed_deadline());
+		simulator.schedule(problem.jobs[1]);
+		assert!(simulator.has_missed_deadline());
+		assert_eq!(
+			Err(ScheduleViolation::ViolatesConstraint(0)), simulator.get_schedule().unwrap().validate(&problem)
+		);
+
+		let mut simulator = Simulator::new(&problem);
+		for job in [2, 0, 1] {
+			simulator.schedule(problem.jobs[job]);
+		}
+		assert!(!simulator.has_missed_deadline());
+	}
+
+	#[test]
+	fn test_shared_resources() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![],
+			num_cores: 3,
+			resources: vec![vec![0, 2], vec![1, 2]],
+		};
+		problem.validate();
+
+		let mut simulator = Simulator::new_recording(&problem);
+		simulator.schedule(problem.jobs[0]);
+		assert_eq!(0, simulator.predict_start_time(problem.jobs[1]));
+		assert_eq!(10, simulator.predict_start_time(problem.jobs[2]));
+
+		let checkpoint = simulator.checkpoint();
+		simulator.schedule(problem.jobs[2]);
+		assert_eq!(20, simulator.predict_start_time(problem.jobs[1]));
+
+		simulator.rollback(checkpoint);
+		assert_eq!(0, simulator.predict_start_time(problem.jobs[1]));
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[2]);
+		assert_eq!(Ok(()), simulator.get_schedule().unwrap().validate(&problem));
+		assert_eq!(10, simulator.get_schedule().unwrap().get_jobs()[2].start);
+	}
+
+	#[test]
+	fn test_initial_state() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![Constraint::new(0, 1, 50, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		problem.validate();
+
+		let mut simulator = Simulator::new_recording(&problem);
+		simulator.set_initial_state(&[30, 20], &[0]);
+		assert_eq!(0, simulator.num_dispatched_jobs());
+		assert_eq!(20, simulator.predict_start_time(problem.jobs[1]));
+
+		let checkpoint = simulator.checkpoint();
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[2]);
+		assert_eq!(2, simulator.num_dispatched_jobs());
+		simulator.rollback(checkpoint);
+		assert_eq!(0, simulator.num_dispatched_jobs());
+
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[2]);
+		let cores: Vec<(usize, Time)> = simulator.get_schedule().unwrap().get_jobs().iter().map(
+			|j| (j.core, j.start)
+		).collect();
+		assert_eq!(vec![(1, 20), (0, 30)], cores);
+	}
+
+	#[test]
+	fn test_affinities() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 5, 100),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		problem.jobs[0].set_affinity(CoreSet::from_cores(&[0]));
+		problem.jobs[1].set_affinity(CoreSet::from_cores(&[0]));
+		problem.validate();
+
+		let mut simulator = Simulator::new(&problem);
+		simulator.schedule(problem.jobs[0]);
+		let checkpoint = simulator.checkpoint();
+		assert_eq!(10, simulator.predict_start_time(problem.jobs[1]));
+		simulator.schedule(problem.jobs[1]);
+		assert_eq!(10, simulator.predict_start_time(problem.jobs[2]));
+		simulator.schedule(problem.jobs[2]);
+		assert_eq!(Ok(()), simulator.get_schedule().unwrap().validate(&problem));
+		assert_eq!(15, simulator.next_core_available());
+
+		simulator.rollback(checkpoint);
+		assert_eq!(0, simulator.predict_start_time(problem.jobs[2]));
+		simulator.schedule(problem.jobs[2]);
+		assert_eq!(10, simulator.predict_start_time(problem.jobs[1]));
+		simulator.schedule(problem.jobs[1]);
+		let cores: Vec<(usize, Time)> = simulator.get_schedule().unwrap().get_jobs().iter().map(
+			|j| (j.core, j.start)
+		).collect();
+		assert_eq!(vec![(0, 0), (1, 0), (0, 10)], cores);
+		assert_eq!(Ok(()), simulator.get_schedule().unwrap().validate(&problem));
+	}
+
+	#[test]
+	fn test_idle_until() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 2, 5, 7),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+		problem.validate();
+
+		let mut simulator = Simulator::new(&problem);
+		let initial = simulator.checkpoint();
+		simulator.schedule(problem.jobs[0]);
+		simulator.schedule(problem.jobs[1]);
+		assert!(simulator.has_missed_deadline());
+
+		simulator.rollback(initial);
+		simulator.idle_until(2);
+		assert_eq!(2, simulator.next_core_available());
+		assert_eq!(2, simulator.predict_start_time(problem.jobs[0]));
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[0]);
+		assert!(!simulator.has_missed_deadline());
+		assert_eq!(17, simulator.next_core_available());
+
+		simulator.rollback(initial);
+		assert_eq!(0, simulator.next_core_available());
+		assert_eq!(0, simulator.num_dispatched_jobs());
+	}
+
+	#[test]
+	fn test_recording() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 20),
+				Job::release_to_deadline(1, 0, 30, 32),
+				Job::release_to_deadline(2, 0, 30, 100),
+				Job::release_to_deadline(3, 5, 10, 60),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 2, ConstraintType::StartToStart),
+				Constraint::new(0, 2, 10, ConstraintType::FinishToStart)
+			],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		problem.validate();
+		strengthen_bounds_using_constraints(&mut problem);
+
+		assert!(Simulator::new(&problem).get_schedule().is_none());
+
+		let mut simulator = Simulator::new_recording(&problem);
+		simulator.schedule(problem.jobs[0]);
+		let checkpoint = simulator.checkpoint();
+		simulator.schedule(problem.jobs[3]);
+		simulator.rollback(checkpoint);
+		assert_eq!(1, simulator.get_schedule().unwrap().get_jobs().len());
+
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[2]);
+		simulator.schedule(problem.jobs[3]);
+		assert!(!simulator.has_missed_deadline());
+
+		let schedule = simulator.get_schedule().unwrap();
+		assert_eq!(Ok(()), schedule.validate(&problem));
+		let start_times: Vec<Time> = schedule.get_jobs().iter().map(|j| j.start).collect();
+		assert_eq!(vec![0, 2, 30, 32], start_times);
+		assert_ne!(schedule.get_jobs()[0].core, schedule.get_jobs()[1].core);
+	}
+
 	#[test]
 	fn test_predict_start_time_with_one_core() {
 		let problem = Problem {
@@ -268,7 +934,8 @@ mod tests {
 				Job::release_to_deadline(1, 10, 30, 50)
 			],
 			constraints: vec![],
-			num_cores: 1
+			num_cores: 1,
+			resources: Vec::new()
 		};
 		problem.validate();
 
@@ -291,7 +958,8 @@ mod tests {
 				Job::release_to_deadline(1, 10, 30, 50)
 			],
 			constraints: vec![],
-			num_cores: 2
+			num_cores: 2,
+			resources: Vec::new()
 		};
 		problem.validate();
 
@@ -310,4 +978,99 @@ mod tests {
 		assert_eq!(10, simulator.predict_start_time(problem.jobs[0]));
 		assert_eq!(30, simulator.predict_next_start_time(problem.jobs[0]));
 	}
+
+	#[test]
+	fn test_parallel_jobs() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 50),
+				Job::release_to_deadline(1, 0, 5, 50),
+				Job::release_to_deadline(2, 0, 20, 50),
+			],
+			constraints: vec![],
+			num_cores: 3,
+			resources: Vec::new()
+		};
+		problem.jobs[1].set_parallelism(2);
+		problem.jobs[2].set_parallelism(3);
+		problem.validate();
+
+		let mut simulator = Simulator::new_recording(&problem);
+		simulator.schedule(problem.jobs[0]);
+		assert_eq!(0, simulator.predict_start_time(problem.jobs[1]));
+		assert_eq!(5, simulator.predict_next_start_time(problem.jobs[1]));
+		assert_eq!(10, simulator.predict_start_time(problem.jobs[2]));
+
+		let checkpoint = simulator.checkpoint();
+		simulator.schedule(problem.jobs[1]);
+		assert_eq!(3, simulator.get_schedule().unwrap().get_jobs().len());
+		simulator.rollback(checkpoint);
+		assert_eq!(1, simulator.get_schedule().unwrap().get_jobs().len());
+
+		simulator.schedule(problem.jobs[1]);
+		simulator.schedule(problem.jobs[2]);
+		assert!(!simulator.has_missed_deadline());
+
+		let schedule = simulator.get_schedule().unwrap();
+		assert_eq!(Ok(()), schedule.validate(&problem));
+		let start_times: Vec<Time> = schedule.get_jobs().iter().map(|j| j.start).collect();
+		assert_eq!(vec![0, 0, 0, 10, 10, 10], start_times);
+	}
+
+	#[test]
+	fn test_best_case_execution_times() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 5, 100),
+				Job::release_to_deadline(2, 0, 12, 100),
+				Job::release_to_deadline(3, 0, 12, 100),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+		problem.constraints[0].set_max_delay(Some(5));
+		let simulate = |problem: &Problem| {
+			let mut simulator = Simulator::new(problem);
+			for job in [0, 2, 3, 1] {
+				simulator.schedule(problem.jobs[job]);
+			}
+			simulator
+		};
+
+		// Job 1 starts at time 12, when job 2 finishes, which is 2 time units after job 0 finishes
+		let simulator = simulate(&problem);
+		assert!(!simulator.has_missed_deadline());
+		assert_eq!(Some((10, 10)), simulator.get_finish_interval(0));
+
+		// When job 0 finishes at time 2, job 1 still can't start before time 12, when job 2 finishes
+		problem.jobs[0].set_sag_details(0, 2, 0);
+		let mut simulator = simulate(&problem);
+		assert!(simulator.has_missed_deadline());
+		assert_eq!(Some((2, 10)), simulator.get_finish_interval(0));
+		assert_eq!(Some((14, 22)), simulator.get_finish_interval(3));
+
+		let checkpoint = simulator.checkpoint();
+		simulator.rollback(checkpoint);
+		assert_eq!(Some((14, 22)), simulator.get_finish_interval(3));
+
+		let mut simulator = Simulator::new(&problem);
+		simulator.schedule(problem.jobs[0]);
+		let checkpoint = simulator.checkpoint();
+		simulator.schedule(problem.jobs[2]);
+		simulator.schedule(problem.jobs[3]);
+		simulator.rollback(checkpoint);
+		assert_eq!(None, simulator.get_finish_interval(3));
+		// Job 1 starts right after job 0 finishes, regardless of its execution time
+		simulator.schedule(problem.jobs[1]);
+		assert!(!simulator.has_missed_deadline());
+		assert_eq!(Some((7, 15)), simulator.get_finish_interval(1));
+
+		// SAG job files allow a best-case execution time of 0
+		problem.jobs[2].set_sag_details(0, 0, 0);
+		let simulator = simulate(&problem);
+		assert_eq!(Some((0, 12)), simulator.get_finish_interval(2));
+		assert_eq!(Some((12, 22)), simulator.get_finish_interval(3));
+	}
 }
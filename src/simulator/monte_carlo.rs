@@ -0,0 +1,233 @@
+use crate::generator::Random;
+use crate::problem::*;
+use crate::schedule::Schedule;
+use crate::simulator::{simulate_fixed_priority, Simulator};
+
+/// The result of `estimate_feasibility`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FeasibilityEstimate {
+	pub num_samples: usize,
+
+	/// The number of samples in which all jobs met their deadlines
+	pub num_feasible_samples: usize,
+
+	/// The smallest lateness of all samples, where the lateness of a sample is the largest amount of
+	/// time by which any job finished after its deadline. When this is at most 0, some sample met
+	/// all deadlines, and its absolute value is the minimum slack of that sample.
+	pub minimum_lateness: Time,
+
+	/// The schedule of the sample with the smallest lateness
+	pub best_schedule: Schedule,
+}
+
+impl FeasibilityEstimate {
+	/// Gets the fraction of the samples in which all jobs met their deadlines
+	pub fn get_feasible_fraction(&self) -> f64 {
+		self.num_feasible_samples as f64 / self.num_samples as f64
+	}
+}
+
+/// Simulates `num_samples` random dispatch orders on `problem`, to get a sense of how close it is
+/// to being (in)feasible when the necessary tests and the scheduling policies are inconclusive.
+/// Each sample simulates the work-conserving scheduler of `simulate_fixed_priority` with random
+/// priorities. The same `seed` always gives the same estimate.
+///
+/// The constraints of `problem` must not be cyclic, and `num_samples` must be positive.
+pub fn estimate_feasibility(problem: &Problem, num_samples: usize, seed: u64) -> FeasibilityEstimate {
+	let mut estimate: Option<FeasibilityEstimate> = None;
+	simulate_random_orders(problem, num_samples, seed, |simulator| {
+		let schedule = simulator.get_schedule().unwrap();
+		let lateness = -schedule.get_minimum_slack(problem).unwrap_or(0);
+		let num_feasible_samples = if simulator.has_missed_deadline() { 0 } else { 1 };
+
+		match &mut estimate {
+			Some(estimate) => {
+				estimate.num_samples += 1;
+				estimate.num_feasible_samples += num_feasible_samples;
+				if lateness < estimate.minimum_lateness {
+					estimate.minimum_lateness = lateness;
+					estimate.best_schedule = schedule.clone();
+				}
+			},
+			None => estimate = Some(FeasibilityEstimate {
+				num_samples: 1, num_feasible_samples, minimum_lateness: lateness, best_schedule: schedule.clone()
+			}),
+		}
+	});
+	estimate.unwrap()
+}
+
+/// The result of `locate_deadline_misses`. The time until the largest deadline is split into
+/// regions of `region_length` time units, and the counters are indexed by job and then by region.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DeadlineMissHeatmap {
+	pub num_samples: usize,
+
+	/// The number of samples in which at least 1 job finished after its deadline. Only these samples
+	/// contribute to the counters.
+	pub num_failed_samples: usize,
+
+	pub region_length: Time,
+
+	/// The number of failed samples in which a job finished after its deadline, where the region is
+	/// the one that contains its deadline
+	pub misses: Vec<Vec<usize>>,
+
+	/// The number of failed samples in which a job ran in a region while a late job had to wait: after
+	/// the earliest start of the late job, but before its start time
+	pub blocking: Vec<Vec<usize>>,
+}
+
+impl DeadlineMissHeatmap {
+	pub fn get_num_regions(&self) -> usize {
+		self.misses.first().map_or(0, |regions| regions.len())
+	}
+
+	/// Gets the total number of failed samples in which `job` finished after its deadline
+	pub fn get_num_misses(&self, job: usize) -> usize {
+		self.misses[job].iter().sum()
+	}
+}
+
+/// Simulates the same random dispatch orders as `estimate_feasibility`, and counts which jobs miss
+/// their deadlines in which time regions, and which jobs keep these late jobs waiting. This points
+/// at the part of the job set that makes `problem` hard (or impossible) to schedule. The time until
+/// the largest deadline is split into at most `num_regions` regions of equal length.
+///
+/// The constraints of `problem` must not be cyclic, and `num_samples` and `num_regions` must be
+/// positive.
+pub fn locate_deadline_misses(problem: &Problem, num_samples: usize, seed: u64, num_regions: usize) -> DeadlineMissHeatmap {
+	assert!(num_regions > 0, "There must be at least 1 region");
+	let horizon = problem.jobs.iter().map(|job| job.get_deadline()).max().unwrap_or(0);
+	let region_length = Time::max(1, (horizon + num_regions as Time - 1) / num_regions as Time);
+	let num_regions = Time::max(1, (horizon + region_length - 1) / region_length) as usize;
+	let get_region = |time: Time| usize::min(num_regions - 1, Time::max(0, time / region_length) as usize);
+
+	let mut heatmap = DeadlineMissHeatmap {
+		num_samples: 0,
+		num_failed_samples: 0,
+		region_length,
+		misses: vec![vec![0; num_regions]; problem.jobs.len()],
+		blocking: vec![vec![0; num_regions]; problem.jobs.len()],
+	};
+	let mut is_blocking = vec![vec![false; num_regions]; problem.jobs.len()];
+	simulate_random_orders(problem, num_samples, seed, |simulator| {
+		heatmap.num_samples += 1;
+		let schedule = simulator.get_schedule().unwrap();
+		let late_jobs: Vec<usize> = schedule.get_lateness(problem).into_iter().enumerate().filter_map(
+			|(job, lateness)| lateness.is_some_and(|lateness| lateness > 0).then_some(job)
+		).collect();
+		if late_jobs.is_empty() {
+			return;
+		}
+		heatmap.num_failed_samples += 1;
+
+		let mut start_times = vec![Time::MAX; problem.jobs.len()];
+		for scheduled in schedule.get_jobs() {
+			start_times[scheduled.job] = Time::min(start_times[scheduled.job], scheduled.start);
+		}
+		for row in &mut is_blocking {
+			row.fill(false);
+		}
+		for late_job in late_jobs {
+			heatmap.misses[late_job][get_region(problem.jobs[late_job].get_deadline())] += 1;
+
+			let wait_start = problem.jobs[late_job].earliest_start;
+			let wait_end = start_times[late_job];
+			for scheduled in schedule.get_jobs() {
+				let start = Time::max(scheduled.start, wait_start);
+				let end = Time::min(scheduled.finish, wait_end);
+				if scheduled.job == late_job || start >= end {
+					continue;
+				}
+				is_blocking[scheduled.job][get_region(start) ..= get_region(end - 1)].fill(true);
+			}
+		}
+		for (counters, row) in heatmap.blocking.iter_mut().zip(&is_blocking) {
+			for (counter, blocking) in counters.iter_mut().zip(row) {
+				*counter += *blocking as usize;
+			}
+		}
+	});
+	heatmap
+}
+
+/// Simulates `num_samples` random dispatch orders on `problem`, and passes each simulator to
+/// `handle_sample`. The same `seed` always gives the same samples.
+fn simulate_random_orders(problem: &Problem, num_samples: usize, seed: u64, mut handle_sample: impl FnMut(&Simulator)) {
+	assert!(num_samples > 0, "There must be at least 1 sample");
+	let mut random = Random { state: seed };
+	let mut priorities = vec![0; problem.jobs.len()];
+	for _ in 0 .. num_samples {
+		for priority in &mut priorities {
+			*priority = random.next_u64() as u32;
+		}
+		handle_sample(&simulate_fixed_priority(problem, &priorities));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::problem::*;
+	use crate::simulator::*;
+
+	#[test]
+	fn test_estimate_feasibility() {
+		// Only the orders in which job 1 runs before job 0 are feasible
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 30),
+				Job::release_to_deadline(1, 0, 10, 12),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let estimate = estimate_feasibility(&problem, 100, 5);
+		assert_eq!(estimate, estimate_feasibility(&problem, 100, 5));
+		assert_eq!(100, estimate.num_samples);
+		assert!(estimate.num_feasible_samples > 0 && estimate.num_feasible_samples < 100);
+		assert!(estimate.get_feasible_fraction() > 0.0 && estimate.get_feasible_fraction() < 1.0);
+		assert_eq!(-2, estimate.minimum_lateness);
+		assert_eq!(Ok(()), estimate.best_schedule.validate(&problem));
+
+		let mut infeasible = problem.clone();
+		infeasible.jobs[0] = Job::release_to_deadline(0, 0, 10, 15);
+		let estimate = estimate_feasibility(&infeasible, 50, 5);
+		assert_eq!(0, estimate.num_feasible_samples);
+		assert_eq!(5, estimate.minimum_lateness);
+	}
+
+	#[test]
+	fn test_locate_deadline_misses() {
+		// Job 1 misses its deadline whenever another job runs first
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 30),
+				Job::release_to_deadline(1, 0, 10, 12),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let heatmap = locate_deadline_misses(&problem, 100, 5, 10);
+		assert_eq!(heatmap, locate_deadline_misses(&problem, 100, 5, 10));
+		assert_eq!(100, heatmap.num_samples);
+		assert_eq!(100 - estimate_feasibility(&problem, 100, 5).num_feasible_samples, heatmap.num_failed_samples);
+		assert!(heatmap.num_failed_samples > 0);
+		assert_eq!(10, heatmap.region_length);
+		assert_eq!(10, heatmap.get_num_regions());
+
+		assert_eq!(0, heatmap.get_num_misses(0));
+		assert_eq!(heatmap.num_failed_samples, heatmap.get_num_misses(1));
+		assert_eq!(heatmap.num_failed_samples, heatmap.misses[1][1]);
+		assert_eq!(0, heatmap.get_num_misses(2));
+
+		// Exactly 1 of the other jobs runs first, and job 1 never blocks itself
+		assert_eq!(heatmap.num_failed_samples, heatmap.blocking[0][0] + heatmap.blocking[2][0]);
+		assert!(heatmap.blocking[1].iter().all(|count| *count == 0));
+		assert!(heatmap.blocking.iter().all(|regions| regions[2 ..].iter().all(|count| *count == 0)));
+	}
+}
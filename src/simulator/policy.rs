@@ -0,0 +1,192 @@
+use crate::problem::*;
+use crate::simulator::Simulator;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Simulates a global work-conserving non-preemptive scheduler that dispatches the jobs of
+/// `problem` itself: whenever a core becomes available, it starts the job that can start the
+/// earliest, and it breaks ties using `priority` (lower is better) and then the job index.
+///
+/// A job can only be dispatched after all its predecessors have been dispatched. Returns the
+/// (recording) simulator after all jobs have been dispatched, so the caller can check
+/// `has_missed_deadline` and inspect the schedule using `get_schedule`.
+///
+/// The jobs whose predecessors have been dispatched are kept in two queues: `released` contains
+/// the jobs that could start when the next core becomes available, ordered by priority, and
+/// `pending` contains the other jobs, ordered by a lower bound on their start time. Since the
+/// predicted start time of a job can only grow while other jobs are dispatched, stale entries are
+/// corrected when they reach the front of their queue.
+fn simulate_by_priority<P: Ord>(problem: &Problem, priority: impl Fn(&Job) -> P) -> Simulator {
+	let mut simulator = Simulator::new_recording(problem);
+	let mut remaining_predecessors = vec![0usize; problem.jobs.len()];
+	let mut successors = vec![Vec::<usize>::new(); problem.jobs.len()];
+	for constraint in &problem.constraints {
+		remaining_predecessors[constraint.get_after()] += 1;
+		successors[constraint.get_before()].push(constraint.get_after());
+	}
+
+	let mut pending = BinaryHeap::new();
+	let mut released = BinaryHeap::new();
+	let pending_entry = |simulator: &Simulator, job: &Job| Reverse((
+		simulator.predict_start_time(*job), priority(job), job.get_index()
+	));
+	for job in &problem.jobs {
+		if remaining_predecessors[job.get_index()] == 0 {
+			pending.push(pending_entry(&simulator, job));
+		}
+	}
+
+	for _ in 0 .. problem.jobs.len() {
+		let next_core_time = simulator.next_core_available();
+		while let Some(Reverse((start_bound, _, job))) = pending.peek() && *start_bound <= next_core_time {
+			let job = &problem.jobs[*job];
+			pending.pop();
+			released.push(Reverse((priority(job), job.get_index())));
+		}
+
+		// The first released job that can really start at `next_core_time` beats all other jobs.
+		// Released jobs that need to wait (for instance for their resources) are pending again.
+		let mut next_job = None;
+		while let Some(Reverse((_, job))) = released.pop() {
+			let job = &problem.jobs[job];
+			if simulator.predict_start_time(*job) <= next_core_time {
+				next_job = Some(job);
+				break;
+			}
+			pending.push(pending_entry(&simulator, job));
+		}
+
+		// Otherwise, all jobs start after `next_core_time`, and the pending job with the smallest
+		// start time (and priority) goes first, once its start time is up to date
+		while next_job.is_none() {
+			let Reverse((start_bound, _, job)) = pending.pop().expect("The constraints should not be cyclic");
+			let job = &problem.jobs[job];
+			if simulator.predict_start_time(*job) == start_bound {
+				next_job = Some(job);
+			} else {
+				pending.push(pending_entry(&simulator, job));
+			}
+		}
+
+		let next_job = next_job.unwrap();
+		simulator.schedule(*next_job);
+		for &successor in &successors[next_job.get_index()] {
+			remaining_predecessors[successor] -= 1;
+			if remaining_predecessors[successor] == 0 {
+				pending.push(pending_entry(&simulator, &problem.jobs[successor]));
+			}
+		}
+	}
+
+	simulator
+}
+
+/// Simulates the global non-preemptive fixed-priority (G-NP-FP) scheduler on `problem`, where
+/// `priorities[i]` is the priority of job `i`, and a lower value means a higher priority.
+///
+/// The problem is schedulable under G-NP-FP if and only if the returned simulator has not missed
+/// any deadline.
+pub fn simulate_fixed_priority(problem: &Problem, priorities: &[u32]) -> Simulator {
+	assert_eq!(problem.jobs.len(), priorities.len());
+	simulate_by_priority(problem, |job| priorities[job.get_index()])
+}
+
+/// Simulates the global non-preemptive earliest-deadline-first (G-NP-EDF) scheduler on `problem`,
+/// which prioritizes the job with the earliest latest finish time.
+///
+/// When the returned simulator has not missed any deadline, the problem is certainly feasible, and
+/// `get_schedule` returns a feasible schedule.
+pub fn simulate_earliest_deadline_first(problem: &Problem) -> Simulator {
+	simulate_by_priority(problem, |job| job.get_latest_finish())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::problem::*;
+	use crate::simulator::*;
+
+	#[test]
+	fn test_fixed_priority_with_one_core() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 15),
+				Job::release_to_deadline(2, 5, 10, 100),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+
+		let good_simulator = simulate_fixed_priority(&problem, &[1, 0, 2]);
+		assert!(!good_simulator.has_missed_deadline());
+		let schedule = good_simulator.get_schedule().unwrap();
+		assert_eq!(Ok(()), schedule.validate(&problem));
+		let order: Vec<usize> = schedule.get_jobs().iter().map(|j| j.job).collect();
+		assert_eq!(vec![1, 0, 2], order);
+
+		// Job 1 must wait until job 0 finishes, which is too late
+		assert!(simulate_fixed_priority(&problem, &[0, 1, 2]).has_missed_deadline());
+	}
+
+	#[test]
+	fn test_fixed_priority_is_work_conserving() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 12),
+				Job::release_to_deadline(1, 1, 2, 3),
+			],
+			constraints: vec![],
+			num_cores: 1,
+			resources: Vec::new()
+		};
+
+		// Even though job 1 has a higher priority, job 0 is already running when job 1 is released
+		let simulator = simulate_fixed_priority(&problem, &[1, 0]);
+		assert!(simulator.has_missed_deadline());
+		assert_eq!(0, simulator.get_schedule().unwrap().get_jobs()[0].job);
+	}
+
+	#[test]
+	fn test_fixed_priority_respects_constraints() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 100),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![Constraint::new(2, 0, 5, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+
+		let simulator = simulate_fixed_priority(&problem, &[0, 1, 2]);
+		assert!(!simulator.has_missed_deadline());
+		let schedule = simulator.get_schedule().unwrap();
+		assert_eq!(Ok(()), schedule.validate(&problem));
+		let start_times: Vec<(usize, Time)> = schedule.get_jobs().iter().map(|j| (j.job, j.start)).collect();
+		assert_eq!(vec![(1, 0), (2, 0), (0, 15)], start_times);
+	}
+
+	#[test]
+	fn test_earliest_deadline_first() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 15),
+				Job::release_to_deadline(2, 0, 10, 30),
+				Job::release_to_deadline(3, 12, 10, 100),
+			],
+			constraints: vec![],
+			num_cores: 2,
+			resources: Vec::new()
+		};
+
+		let simulator = simulate_earliest_deadline_first(&problem);
+		assert!(!simulator.has_missed_deadline());
+		let schedule = simulator.get_schedule().unwrap();
+		assert_eq!(Ok(()), schedule.validate(&problem));
+		let order: Vec<usize> = schedule.get_jobs().iter().map(|j| j.job).collect();
+		assert_eq!(vec![1, 2, 0, 3], order);
+	}
+}
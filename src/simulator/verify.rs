@@ -0,0 +1,143 @@
+use crate::problem::*;
+use crate::schedule::Schedule;
+use crate::simulator::Simulator;
+
+/// A single entry of an externally supplied dispatch order: the job that is dispatched, and
+/// optionally the time at which it is supposed to start
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Dispatch {
+	pub job: usize,
+	pub start: Option<Time>,
+}
+
+/// The reason why a dispatch order is not a feasible solution of a `Problem`. Each variant
+/// contains the position (in the dispatch order) of the first dispatch that is wrong, except for
+/// `MissingJob`, which contains the index of the first job that is not dispatched, and
+/// `ExceedsMaxDelay`, which contains the violated constraint.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DispatchViolation {
+	/// The job is duplicated, unknown, or dispatched before one of its predecessors
+	InvalidOrder(usize),
+	MissingJob(usize),
+	/// The job can't start at its given start time, because no core is available yet, or because
+	/// some constraint would be violated. It could start at `earliest_start` instead.
+	CannotStartAt { position: usize, earliest_start: Time },
+	MissesDeadline(usize),
+	/// The `after` job of the constraint starts too long after its `before` job
+	ExceedsMaxDelay(Constraint),
+}
+
+/// Replays `order` through the `Simulator` to check whether it is a feasible solution of `problem`.
+/// Each job is started as early as possible, unless its dispatch has an explicit start time. Like
+/// the simulator, this checks maximum delays when all jobs run for their best-case execution time
+/// as well.
+///
+/// Returns the resulting schedule, or the first violation that is encountered.
+pub fn verify_dispatch_order(problem: &Problem, order: &[Dispatch]) -> Result<Schedule, DispatchViolation> {
+	let jobs: Vec<usize> = order.iter().map(|dispatch| dispatch.job).collect();
+	problem.check_dispatch_prefix(&jobs).map_err(DispatchViolation::InvalidOrder)?;
+	if jobs.len() < problem.jobs.len() {
+		let mut is_dispatched = vec![false; problem.jobs.len()];
+		for &job in &jobs {
+			is_dispatched[job] = true;
+		}
+		return Err(DispatchViolation::MissingJob(is_dispatched.iter().position(|d| !d).unwrap()));
+	}
+
+	let mut simulator = Simulator::new_recording(problem);
+	for (position, dispatch) in order.iter().enumerate() {
+		let job = problem.jobs[dispatch.job];
+		if let Some(start) = dispatch.start {
+			let earliest_start = simulator.predict_start_time(job);
+			if earliest_start > start {
+				return Err(DispatchViolation::CannotStartAt { position, earliest_start });
+			}
+			simulator.idle_until(start);
+		}
+		let start = simulator.predict_start_time(job);
+		let exceeded_constraint = simulator.find_exceeded_max_delay_in_any_case(job);
+		simulator.schedule(job);
+		if simulator.has_missed_deadline() {
+			if start > job.latest_start {
+				return Err(DispatchViolation::MissesDeadline(position));
+			}
+			let constraint = exceeded_constraint.expect("The simulator should only miss a deadline or a maximum delay");
+			return Err(DispatchViolation::ExceedsMaxDelay(constraint));
+		}
+	}
+
+	Ok(simulator.get_schedule().unwrap().clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::problem::*;
+	use crate::simulator::*;
+
+	fn dispatch(job: usize, start: Option<Time>) -> Dispatch {
+		Dispatch { job, start }
+	}
+
+	fn problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 0, 10, 15),
+				Job::release_to_deadline(2, 0, 10, 100),
+			],
+			constraints: vec![Constraint::new(0, 2, 5, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new()
+		}
+	}
+
+	#[test]
+	fn test_valid_dispatch_order() {
+		let problem = problem();
+		let schedule = verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(1, Some(3)), dispatch(2, None)
+		]).unwrap();
+		assert_eq!(Ok(()), schedule.validate(&problem));
+		let start_times: Vec<Time> = schedule.get_jobs().iter().map(|j| j.start).collect();
+		assert_eq!(vec![0, 3, 15], start_times);
+	}
+
+	#[test]
+	fn test_invalid_dispatch_orders() {
+		let problem = problem();
+		assert_eq!(Err(DispatchViolation::InvalidOrder(1)), verify_dispatch_order(&problem, &[
+			dispatch(1, None), dispatch(2, None), dispatch(0, None)
+		]));
+		assert_eq!(Err(DispatchViolation::MissingJob(1)), verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(2, None)
+		]));
+		assert_eq!(Err(DispatchViolation::CannotStartAt { position: 2, earliest_start: 15 }), verify_dispatch_order(
+			&problem, &[dispatch(0, None), dispatch(1, None), dispatch(2, Some(12))]
+		));
+		assert_eq!(Err(DispatchViolation::MissesDeadline(1)), verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(1, Some(6)), dispatch(2, None)
+		]));
+	}
+
+	#[test]
+	fn test_exceeds_max_delay() {
+		let mut problem = problem();
+		problem.constraints[0].set_max_delay(Some(8));
+		assert!(verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(1, None), dispatch(2, Some(18))
+		]).is_ok());
+		assert_eq!(Err(DispatchViolation::ExceedsMaxDelay(problem.constraints[0])), verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(1, None), dispatch(2, Some(19))
+		]));
+
+
+		// When job 0 finishes at time 7, job 2 must start at time 7 + 8 = 15 or earlier
+		problem.jobs[0].set_sag_details(0, 7, 0);
+		assert!(verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(1, None), dispatch(2, None)
+		]).is_ok());
+		assert_eq!(Err(DispatchViolation::ExceedsMaxDelay(problem.constraints[0])), verify_dispatch_order(&problem, &[
+			dispatch(0, None), dispatch(1, None), dispatch(2, Some(16))
+		]));
+	}
+}
@@ -20,7 +20,7 @@ impl SortedJobIterator {
 
 	/// Creates a new `SortedJobIterator`, where all jobs are sorted by their result of the
 	/// `compute_value` function.
-	pub fn new<T>(jobs: &Vec<Job>, compute_value: T) -> SortedJobIterator where T : Fn(&Job) -> Time {
+	pub fn new<T>(jobs: &[Job], compute_value: T) -> SortedJobIterator where T : Fn(&Job) -> Time {
 		let mut fat_jobs: Vec<_> = jobs.iter().map(
 			|j| FatJob { job: j.get_index(), value : compute_value(j) }
 		).collect();
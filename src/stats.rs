@@ -0,0 +1,343 @@
+use crate::problem::*;
+use std::fmt::{Display, Formatter};
+
+/// The number of buckets in `ProblemStats::tightness_histogram`
+pub const NUM_TIGHTNESS_BUCKETS: usize = 10;
+
+/// Some statistics of a problem, which are useful to classify (benchmark) problems
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemStats {
+	pub num_jobs: usize,
+	pub num_constraints: usize,
+
+	/// The time between the earliest `earliest_start` and the latest latest finish time of all jobs
+	pub horizon: Time,
+
+	/// The total execution time of all jobs, divided by the horizon and the number of cores
+	pub utilization_per_core: f64,
+
+	/// The sum of the densities of all jobs, divided by the number of cores. The density of a job
+	/// is its execution time divided by the length of its window.
+	pub density_per_core: f64,
+
+	/// The number of jobs in the longest chain of constraints, or `None` when the constraints are
+	/// cyclic
+	pub max_chain_depth: Option<usize>,
+
+	/// Bucket `i` counts the jobs whose density (tightness) is in `(i / 10, (i + 1) / 10]`. Jobs
+	/// with an empty window are put in the last bucket.
+	pub tightness_histogram: [usize; NUM_TIGHTNESS_BUCKETS],
+}
+
+/// Computes the number of jobs in the longest chain of constraints that ends at each job, using
+/// Kahn's algorithm. Returns `None` when the constraints are cyclic.
+fn compute_chain_depths(problem: &Problem) -> Option<Vec<usize>> {
+	let mut successors = vec![Vec::new(); problem.jobs.len()];
+	let mut remaining_predecessors = vec![0usize; problem.jobs.len()];
+	for constraint in &problem.constraints {
+		successors[constraint.get_before()].push(constraint.get_after());
+		remaining_predecessors[constraint.get_after()] += 1;
+	}
+
+	let mut depths = vec![1usize; problem.jobs.len()];
+	let mut next_jobs: Vec<usize> = (0 .. problem.jobs.len()).filter(
+		|job| remaining_predecessors[*job] == 0
+	).collect();
+	let mut num_visited_jobs = 0;
+	while let Some(job) = next_jobs.pop() {
+		num_visited_jobs += 1;
+		for &successor in &successors[job] {
+			depths[successor] = usize::max(depths[successor], depths[job] + 1);
+			remaining_predecessors[successor] -= 1;
+			if remaining_predecessors[successor] == 0 {
+				next_jobs.push(successor);
+			}
+		}
+	}
+
+	if num_visited_jobs < problem.jobs.len() {
+		return None;
+	}
+	Some(depths)
+}
+
+impl ProblemStats {
+	pub fn new(problem: &Problem) -> Self {
+		let horizon_start = problem.jobs.iter().map(|job| job.earliest_start).min().unwrap_or(0);
+		let horizon_end = problem.jobs.iter().map(|job| job.get_latest_finish()).max().unwrap_or(0);
+		let horizon = horizon_end - horizon_start;
+		let num_cores = problem.num_cores as f64;
+
+		let total_execution_time: Time = problem.jobs.iter().map(|job| job.get_execution_time()).sum();
+		let utilization_per_core = if horizon > 0 {
+			total_execution_time as f64 / (horizon as f64 * num_cores)
+		} else {
+			0.0
+		};
+
+		let mut total_density = 0.0;
+		let mut tightness_histogram = [0; NUM_TIGHTNESS_BUCKETS];
+		for job in &problem.jobs {
+			let window = job.get_latest_finish() - job.earliest_start;
+			let mut bucket = NUM_TIGHTNESS_BUCKETS - 1;
+			if window > 0 {
+				total_density += job.get_execution_time() as f64 / window as f64;
+				let scaled_execution_time = job.get_execution_time() * NUM_TIGHTNESS_BUCKETS as Time;
+				let scaled_density = (scaled_execution_time + window - 1) / window;
+				bucket = Time::clamp(scaled_density, 1, NUM_TIGHTNESS_BUCKETS as Time) as usize - 1;
+			}
+			tightness_histogram[bucket] += 1;
+		}
+
+		Self {
+			num_jobs: problem.jobs.len(),
+			num_constraints: problem.constraints.len(),
+			horizon,
+			utilization_per_core,
+			density_per_core: total_density / num_cores,
+			max_chain_depth: compute_chain_depths(problem).map(|depths| depths.into_iter().max().unwrap_or(0)),
+			tightness_histogram,
+		}
+	}
+}
+
+impl Display for ProblemStats {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Number of jobs: {}", self.num_jobs)?;
+		writeln!(f, "Number of constraints: {}", self.num_constraints)?;
+		writeln!(f, "Horizon: {}", self.horizon)?;
+		writeln!(f, "Utilization per core: {:.3}", self.utilization_per_core)?;
+		writeln!(f, "Density per core: {:.3}", self.density_per_core)?;
+		match self.max_chain_depth {
+			Some(depth) => writeln!(f, "Maximum chain depth: {}", depth)?,
+			None => writeln!(f, "Maximum chain depth: cyclic")?,
+		}
+		write!(f, "Window tightness histogram:")?;
+		for (bucket, count) in self.tightness_histogram.iter().enumerate() {
+			write!(f, " {}-{}%: {}", 10 * bucket, 10 * (bucket + 1), count)?;
+		}
+		Ok(())
+	}
+}
+
+/// How much the bound strengthening narrowed the execution windows of the jobs of a problem, where
+/// the execution window of a job is the interval between its earliest start time and its latest
+/// finish time
+#[derive(Debug, Clone, PartialEq)]
+pub struct NarrowingReport {
+	/// The `(earliest start, latest finish)` of each job before the strengthening
+	pub original_windows: Vec<(Time, Time)>,
+	/// The `(earliest start, latest finish)` of each job after the strengthening
+	pub windows: Vec<(Time, Time)>,
+
+	/// The number of jobs whose start time became fixed: their earliest start time is equal to their
+	/// latest start time after the strengthening, but not before
+	pub num_fixed_jobs: usize,
+
+	/// The average fraction of the execution windows that was removed by the strengthening
+	pub average_shrinkage: f64,
+}
+
+impl NarrowingReport {
+	/// Compares the windows of the jobs of `original` with those of `strengthened`, which must have
+	/// the same jobs in the same order
+	pub fn new(original: &Problem, strengthened: &Problem) -> Self {
+		assert_eq!(original.jobs.len(), strengthened.jobs.len());
+		let get_windows = |problem: &Problem| -> Vec<(Time, Time)> {
+			problem.jobs.iter().map(|job| (job.earliest_start, job.get_latest_finish())).collect()
+		};
+
+		let num_fixed_jobs = original.jobs.iter().zip(&strengthened.jobs).filter(
+			|(original, strengthened)| original.earliest_start < original.latest_start &&
+				strengthened.earliest_start == strengthened.latest_start
+		).count();
+		let total_shrinkage: f64 = original.jobs.iter().zip(&strengthened.jobs).map(|(original, strengthened)| {
+			let original_length = original.get_latest_finish() - original.earliest_start;
+			let length = strengthened.get_latest_finish() - strengthened.earliest_start;
+			(original_length - length) as f64 / original_length as f64
+		}).sum();
+
+		Self {
+			original_windows: get_windows(original),
+			windows: get_windows(strengthened),
+			num_fixed_jobs,
+			average_shrinkage: if original.jobs.is_empty() { 0.0 } else { total_shrinkage / original.jobs.len() as f64 },
+		}
+	}
+}
+
+impl Display for NarrowingReport {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for (job, (original, window)) in self.original_windows.iter().zip(&self.windows).enumerate() {
+			writeln!(f, "Job {} can run in [{}, {}] instead of [{}, {}]", job, window.0, window.1, original.0, original.1)?;
+		}
+		write!(
+			f, "The start time of {} jobs became fixed, and the windows shrank by {:.1}% on average",
+			self.num_fixed_jobs, 100.0 * self.average_shrinkage
+		)
+	}
+}
+
+/// The verdict of an analysis, as reported in the job features (see `JobFeatures`)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Verdict {
+	Feasible,
+	Infeasible,
+	/// The analysis couldn't decide whether the problem is feasible
+	Unknown,
+}
+
+impl Verdict {
+	pub fn get_name(self) -> &'static str {
+		match self {
+			Verdict::Feasible => "feasible",
+			Verdict::Infeasible => "infeasible",
+			Verdict::Unknown => "unknown",
+		}
+	}
+}
+
+/// Some properties of a single job, which are useful to train models that predict a good dispatch
+/// order
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobFeatures {
+	/// The latest start time of the job minus its earliest start time
+	pub slack: Time,
+
+	/// The latest finish time of the job minus its earliest start time
+	pub window_length: Time,
+
+	/// The number of constraints towards the job
+	pub in_degree: usize,
+	/// The number of constraints from the job
+	pub out_degree: usize,
+
+	/// The number of jobs in the longest chain of constraints that ends at the job, or `None` when
+	/// the constraints are cyclic
+	pub chain_depth: Option<usize>,
+
+	/// The execution time of all jobs (including this one) that falls within the window of the job,
+	/// divided by the length of its window and the number of cores. The execution time of each job is
+	/// assumed to be spread evenly over its window, and windows are at least 1 time unit long.
+	pub local_utilization: f64,
+}
+
+impl JobFeatures {
+	/// Extracts the features of all jobs of `problem`
+	pub fn extract(problem: &Problem) -> Vec<Self> {
+		let mut in_degrees = vec![0; problem.jobs.len()];
+		let mut out_degrees = vec![0; problem.jobs.len()];
+		for constraint in &problem.constraints {
+			in_degrees[constraint.get_after()] += 1;
+			out_degrees[constraint.get_before()] += 1;
+		}
+		let chain_depths = compute_chain_depths(problem);
+		let get_window_length = |job: &Job| Time::max(job.get_latest_finish() - job.earliest_start, 1);
+
+		problem.jobs.iter().enumerate().map(|(index, job)| {
+			let window_length = get_window_length(job);
+			let demand: f64 = problem.jobs.iter().map(|other| {
+				let overlap = Time::min(job.earliest_start + window_length, other.earliest_start + get_window_length(other)) -
+					Time::max(job.earliest_start, other.earliest_start);
+				Time::max(overlap, 0) as f64 * other.get_execution_time() as f64 / get_window_length(other) as f64
+			}).sum();
+
+			Self {
+				slack: job.latest_start - job.earliest_start,
+				window_length: job.get_latest_finish() - job.earliest_start,
+				in_degree: in_degrees[index],
+				out_degree: out_degrees[index],
+				chain_depth: chain_depths.as_ref().map(|depths| depths[index]),
+				local_utilization: demand / (window_length as f64 * problem.num_cores as f64),
+			}
+		}).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::problem::*;
+	use crate::stats::{JobFeatures, NarrowingReport, ProblemStats};
+
+	#[test]
+	fn test_problem_stats() {
+		let mut problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 100),
+				Job::release_to_deadline(1, 50, 25, 100),
+				Job::release_to_deadline(2, 20, 10, 30),
+				Job::release_to_deadline(3, 0, 5, 50),
+			],
+			constraints: vec![
+				Constraint::new(0, 1, 0, ConstraintType::FinishToStart),
+				Constraint::new(1, 2, 0, ConstraintType::StartToStart),
+				Constraint::new(3, 2, 0, ConstraintType::StartToStart),
+			],
+			num_cores: 2,
+			resources: Vec::new(),
+		};
+
+		let stats = ProblemStats::new(&problem);
+		assert_eq!(4, stats.num_jobs);
+		assert_eq!(3, stats.num_constraints);
+		assert_eq!(100, stats.horizon);
+		assert_eq!(0.25, stats.utilization_per_core);
+		assert!((stats.density_per_core - 0.85).abs() < 1e-9);
+		assert_eq!(Some(3), stats.max_chain_depth);
+		assert_eq!([2, 0, 0, 0, 1, 0, 0, 0, 0, 1], stats.tightness_histogram);
+
+		problem.constraints.push(Constraint::new(2, 0, 0, ConstraintType::StartToStart));
+		assert_eq!(None, ProblemStats::new(&problem).max_chain_depth);
+	}
+
+	#[test]
+	fn test_narrowing_report() {
+		let original = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 20),
+				Job::release_to_deadline(1, 0, 10, 20),
+				Job::release_to_deadline(2, 5, 5, 10),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let mut strengthened = original.clone();
+		strengthened.jobs[0].set_latest_finish(10);
+		strengthened.jobs[1].earliest_start = 10;
+
+		let report = NarrowingReport::new(&original, &strengthened);
+		assert_eq!(vec![(0, 10), (10, 20), (5, 10)], report.windows);
+		assert_eq!(vec![(0, 20), (0, 20), (5, 10)], report.original_windows);
+		// The start time of job 2 was already fixed
+		assert_eq!(2, report.num_fixed_jobs);
+		assert!((report.average_shrinkage - 1.0 / 3.0).abs() < 1e-9);
+		assert!(report.to_string().contains("Job 1 can run in [10, 20] instead of [0, 20]"));
+	}
+
+	#[test]
+	fn test_job_features() {
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 20),
+				Job::release_to_deadline(1, 10, 5, 20),
+				Job::release_to_deadline(2, 40, 5, 50),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+
+		let features = JobFeatures::extract(&problem);
+		assert_eq!(3, features.len());
+		assert_eq!(10, features[0].slack);
+		assert_eq!(20, features[0].window_length);
+		assert_eq!((0, 1), (features[0].in_degree, features[0].out_degree));
+		assert_eq!((1, 0), (features[1].in_degree, features[1].out_degree));
+		assert_eq!(Some(2), features[1].chain_depth);
+		// Job 0 contributes half of its execution time to the window of job 1
+		assert!((features[0].local_utilization - 0.75).abs() < 1e-9);
+		assert!((features[1].local_utilization - 1.0).abs() < 1e-9);
+		assert!((features[2].local_utilization - 0.5).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,262 @@
+use crate::necessary::{NecessaryTest, TestProfile, TestStatistics};
+use crate::problem::*;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The strengthened bounds of a single job in an `AnalysisState`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct JobBounds {
+	pub earliest_start: Time,
+	pub latest_start: Time,
+
+	/// The execution time and deadline of the job, which are stored to recognize states that were
+	/// saved for a different order of the jobs
+	pub execution_time: Time,
+	pub deadline: Time,
+}
+
+/// The results of an earlier analysis of a problem: the strengthened bounds of its jobs, the
+/// constraints that are implied by their windows, and the statistics of the necessary tests. When
+/// the same problem is analyzed again (for instance during a sensitivity sweep), loading this state
+/// makes it possible to skip the bound strengthening and the search for implied constraints.
+///
+/// All job indices and times refer to the original problem: before it was permuted or normalized.
+#[derive(Debug, Clone)]
+pub struct AnalysisState {
+	/// The fingerprint of the problem (see `compute_fingerprint`)
+	pub fingerprint: u64,
+	pub bounds: Vec<JobBounds>,
+	pub implied_constraints: Vec<Constraint>,
+	pub profile: TestProfile,
+}
+
+impl AnalysisState {
+	/// Creates the state of `problem`, whose jobs must have their strengthened bounds
+	pub fn new(
+		fingerprint: u64, problem: &Problem, implied_constraints: Vec<Constraint>, profile: TestProfile
+	) -> Self {
+		let bounds = problem.jobs.iter().map(|job| JobBounds {
+			earliest_start: job.earliest_start,
+			latest_start: job.latest_start,
+			execution_time: job.get_execution_time(),
+			deadline: job.get_deadline(),
+		}).collect();
+		Self { fingerprint, bounds, implied_constraints, profile }
+	}
+
+	/// Tightens the bounds of the jobs of `problem` to the stored bounds, and adds the stored
+	/// implied constraints to it. Returns `false` and leaves `problem` unchanged when this state
+	/// doesn't belong to `problem`: when the jobs are in a different order, or when one of the
+	/// stored bounds is weaker than the bounds of `problem`.
+	///
+	/// Note that jobs with the same window and execution time can't be told apart, so swapping two
+	/// such jobs with different constraints is not detected.
+	pub fn apply_to(&self, problem: &mut Problem) -> bool {
+		if self.bounds.len() != problem.jobs.len() {
+			return false;
+		}
+		let is_compatible = self.bounds.iter().zip(&problem.jobs).all(|(bounds, job)| {
+			bounds.execution_time == job.get_execution_time() && bounds.deadline == job.get_deadline() &&
+				bounds.earliest_start >= job.earliest_start && bounds.latest_start <= job.latest_start
+		});
+		let has_unknown_jobs = self.implied_constraints.iter().any(
+			|constraint| constraint.get_before() >= problem.jobs.len() || constraint.get_after() >= problem.jobs.len()
+		);
+		if !is_compatible || has_unknown_jobs {
+			return false;
+		}
+
+		for (bounds, job) in self.bounds.iter().zip(&mut problem.jobs) {
+			job.earliest_start = bounds.earliest_start;
+			job.latest_start = bounds.latest_start;
+		}
+		problem.constraints.extend_from_slice(&self.implied_constraints);
+		true
+	}
+}
+
+/// Formats `state` as CSV, where each line starts with its kind:
+/// - `fingerprint, <fingerprint in hexadecimal>`
+/// - `job, <earliest start>, <latest start>, <execution time>, <deadline>` for each job, in order
+/// - `implied, <before index>, <after index>, <delay>, <f-s or s-s>` for each implied constraint
+/// - `test, <name>, <runs>, <detections>, <total time in nanoseconds>` for each necessary test
+pub fn format_analysis_state(state: &AnalysisState) -> String {
+	let mut csv = format!("fingerprint, {:016x}\n", state.fingerprint);
+	for bounds in &state.bounds {
+		csv.push_str(&format!(
+			"job, {}, {}, {}, {}\n", bounds.earliest_start, bounds.latest_start, bounds.execution_time, bounds.deadline
+		));
+	}
+	for constraint in &state.implied_constraints {
+		let constraint_type = match constraint.get_type() {
+			ConstraintType::FinishToStart => "f-s",
+			ConstraintType::StartToStart => "s-s",
+		};
+		csv.push_str(&format!(
+			"implied, {}, {}, {}, {}\n", constraint.get_before(), constraint.get_after(),
+			constraint.get_delay(), constraint_type
+		));
+	}
+	for test in NecessaryTest::ALL {
+		let statistics = state.profile.get_statistics(test);
+		csv.push_str(&format!(
+			"test, {}, {}, {}, {}\n", test.get_name(), statistics.num_runs,
+			statistics.num_detections, statistics.total_time.as_nanos()
+		));
+	}
+	csv
+}
+
+/// Parses an analysis state in the format of `format_analysis_state`
+pub fn parse_analysis_state_text(raw_text: &str) -> Result<AnalysisState, String> {
+	let mut fingerprint = None;
+	let mut bounds = Vec::new();
+	let mut implied_constraints = Vec::new();
+	let mut profile = TestProfile::new();
+
+	for line in raw_text.lines() {
+		if line.trim().is_empty() { continue; }
+		let string_values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+		let parse_time = |index: usize| string_values.get(index).and_then(|value| value.parse::<Time>().ok()).ok_or_else(
+			|| format!("Couldn't parse number {} in line of analysis state: {}", index, line)
+		);
+		let parse_count = |index: usize| parse_time(index).and_then(
+			|value| u64::try_from(value).map_err(|_| format!("Negative count in line of analysis state: {}", line))
+		);
+
+		match (string_values[0], string_values.len()) {
+			("fingerprint", 2) => fingerprint = Some(u64::from_str_radix(string_values[1], 16).map_err(
+				|_| format!("Couldn't parse fingerprint in line of analysis state: {}", line)
+			)?),
+			("job", 5) => bounds.push(JobBounds {
+				earliest_start: parse_time(1)?,
+				latest_start: parse_time(2)?,
+				execution_time: parse_time(3)?,
+				deadline: parse_time(4)?,
+			}),
+			("implied", 5) => {
+				let constraint_type = match string_values[4] {
+					"f-s" => ConstraintType::FinishToStart,
+					"s-s" => ConstraintType::StartToStart,
+					_ => return Err(format!("Unexpected constraint type in line of analysis state: {}", line)),
+				};
+				implied_constraints.push(Constraint::new(
+					parse_count(1)? as usize, parse_count(2)? as usize, parse_time(3)?, constraint_type
+				));
+			},
+			("test", 5) => {
+				let test = NecessaryTest::ALL.into_iter().find(|test| test.get_name() == string_values[1]).ok_or_else(
+					|| format!("Unknown necessary test in line of analysis state: {}", line)
+				)?;
+				profile.set_statistics(test, TestStatistics {
+					num_runs: parse_count(2)?,
+					num_detections: parse_count(3)?,
+					total_time: Duration::from_nanos(parse_count(4)?),
+				});
+			},
+			_ => return Err(format!("Unexpected line in analysis state: {}", line)),
+		}
+	}
+
+	let fingerprint = fingerprint.ok_or("The analysis state doesn't contain a fingerprint")?;
+	Ok(AnalysisState { fingerprint, bounds, implied_constraints, profile })
+}
+
+/// Gets the path of the file in `directory` in which the state of the problem with the given
+/// fingerprint is stored
+pub fn get_analysis_state_path(directory: &str, fingerprint: u64) -> PathBuf {
+	Path::new(directory).join(format!("{:016x}.state.csv", fingerprint))
+}
+
+/// Writes `state` to its file in `directory` (see `get_analysis_state_path`), replacing the state
+/// of an earlier analysis of the same problem
+pub fn save_analysis_state(state: &AnalysisState, directory: &str) {
+	create_dir_all(directory).expect("Couldn't create analysis state directory");
+	write(get_analysis_state_path(directory, state.fingerprint), format_analysis_state(state))
+		.expect("Couldn't write analysis state file");
+}
+
+/// Loads the state of the problem with the given fingerprint from `directory`. Returns `None` when
+/// it hasn't been saved, or when its file is malformed or belongs to another fingerprint.
+pub fn load_analysis_state(directory: &str, fingerprint: u64) -> Option<AnalysisState> {
+	let raw_text = read_to_string(get_analysis_state_path(directory, fingerprint)).ok()?;
+	parse_analysis_state_text(&raw_text).ok().filter(|state| state.fingerprint == fingerprint)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::necessary::*;
+	use crate::warm_start::*;
+	use std::time::Duration;
+
+	fn create_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 5, 20),
+				Job::release_to_deadline(1, 2, 3, 20),
+			],
+			constraints: vec![Constraint::new(0, 1, 1, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: vec![],
+		}
+	}
+
+	#[test]
+	fn test_format_and_parse_analysis_state() {
+		let mut strengthened = create_problem();
+		strengthened.jobs[1].earliest_start = 6;
+		strengthened.jobs[0].latest_start = 13;
+		let mut profile = TestProfile::new();
+		profile.set_statistics(NecessaryTest::Load, TestStatistics {
+			num_runs: 3, num_detections: 1, total_time: Duration::from_nanos(1234)
+		});
+		let implied = vec![Constraint::new(1, 0, 2, ConstraintType::StartToStart)];
+		let state = AnalysisState::new(0xabc, &strengthened, implied.clone(), profile);
+
+		let parsed = parse_analysis_state_text(&format_analysis_state(&state)).unwrap();
+		assert_eq!(0xabc, parsed.fingerprint);
+		assert_eq!(state.bounds, parsed.bounds);
+		assert_eq!(implied, parsed.implied_constraints);
+		for test in NecessaryTest::ALL {
+			assert_eq!(state.profile.get_statistics(test), parsed.profile.get_statistics(test));
+		}
+
+		assert!(parse_analysis_state_text("job, 1, 2, 3, 4\n").is_err());
+		assert!(parse_analysis_state_text("fingerprint, abc\ntest, unknown, 1, 1, 1\n").is_err());
+	}
+
+	#[test]
+	fn test_apply_analysis_state() {
+		let mut strengthened = create_problem();
+		strengthened.jobs[1].earliest_start = 6;
+		let implied = vec![Constraint::new(1, 0, 2, ConstraintType::StartToStart)];
+		let state = AnalysisState::new(0, &strengthened, implied, TestProfile::new());
+
+		let mut problem = create_problem();
+		assert!(state.apply_to(&mut problem));
+		assert_eq!(strengthened.jobs, problem.jobs);
+		assert_eq!(2, problem.constraints.len());
+
+		let mut reversed = create_problem();
+		reversed.jobs.reverse();
+		reversed.update_job_indices();
+		let original_reversed = reversed.clone();
+		assert!(!state.apply_to(&mut reversed));
+		assert_eq!(original_reversed, reversed);
+	}
+
+	#[test]
+	fn test_save_and_load_analysis_state() {
+		let directory = std::env::temp_dir().join(format!("np-feasibility-warm-start-{}", std::process::id()));
+		let directory = directory.to_str().unwrap();
+		let state = AnalysisState::new(42, &create_problem(), Vec::new(), TestProfile::new());
+		save_analysis_state(&state, directory);
+		let loaded = load_analysis_state(directory, 42);
+		let missing = load_analysis_state(directory, 43);
+		std::fs::remove_dir_all(directory).unwrap();
+
+		assert_eq!(state.bounds, loaded.unwrap().bounds);
+		assert!(missing.is_none());
+	}
+}
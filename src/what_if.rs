@@ -0,0 +1,188 @@
+use crate::necessary::strengthen_and_test;
+use crate::problem::*;
+
+/// A problem together with the results of the necessary analyses, which can be used to quickly
+/// analyze small modifications of the problem (what-if analysis), for instance in interactive
+/// design tools.
+///
+/// The bounds that the analyses derived for a problem remain valid for every modification that
+/// can only make the problem *harder*: adding a constraint, increasing the execution time of a job,
+/// or shrinking the window of a job. Such modifications start from the strengthened bounds of the
+/// original problem, so the bound strengthening needs far fewer iterations, and a problem that was
+/// already proven infeasible stays infeasible without running any test. Other modifications are
+/// analyzed from scratch.
+#[derive(Debug, Clone)]
+pub struct AnalyzedProblem {
+	problem: Problem,
+
+	/// `problem` with the implied constraints and strengthened bounds, or `None` when `problem` is
+	/// proven infeasible. The implied constraints remain valid for the modifications that start
+	/// from it, since those can only make the problem harder.
+	strengthened: Option<Problem>,
+}
+
+impl AnalyzedProblem {
+	/// Analyzes `problem` from scratch
+	pub fn new(problem: Problem) -> Self {
+		let mut strengthened = problem.clone();
+		let is_infeasible = strengthen_and_test(&mut strengthened);
+		Self { problem, strengthened: if is_infeasible { None } else { Some(strengthened) } }
+	}
+
+	/// Reanalyzes the modified problem, starting from `strengthened`, which must contain bounds that
+	/// are valid for `problem`
+	fn reanalyze(problem: Problem, mut strengthened: Problem) -> Self {
+		let is_infeasible = strengthen_and_test(&mut strengthened);
+		Self { problem, strengthened: if is_infeasible { None } else { Some(strengthened) } }
+	}
+
+	pub fn get_problem(&self) -> &Problem {
+		&self.problem
+	}
+
+	/// Gets the problem with the implied constraints and strengthened bounds, or `None` when the
+	/// problem is proven infeasible
+	pub fn get_strengthened_problem(&self) -> Option<&Problem> {
+		self.strengthened.as_ref()
+	}
+
+	/// Returns `true` if the necessary tests proved that the problem is infeasible. When this
+	/// returns `false`, the problem may or may not be feasible.
+	pub fn is_proven_infeasible(&self) -> bool {
+		self.strengthened.is_none()
+	}
+
+	/// Analyzes a copy of this problem to which `constraint` is added
+	pub fn with_constraint(&self, constraint: Constraint) -> Self {
+		let mut problem = self.problem.clone();
+		problem.constraints.push(constraint);
+		problem.validate();
+
+		match &self.strengthened {
+			Some(strengthened) => {
+				let mut strengthened = strengthened.clone();
+				strengthened.constraints.push(constraint);
+				Self::reanalyze(problem, strengthened)
+			},
+			None => Self { problem, strengthened: None },
+		}
+	}
+
+	/// Analyzes a copy of this problem in which the job with the index of `job` is replaced by `job`
+	pub fn with_job(&self, job: Job) -> Self {
+		let index = job.get_index();
+		let mut problem = self.problem.clone();
+		let old_job = problem.jobs[index];
+		problem.jobs[index] = job;
+
+		let is_harder = job.earliest_start >= old_job.earliest_start &&
+			job.get_latest_finish() <= old_job.get_latest_finish() &&
+			job.get_execution_time() >= old_job.get_execution_time();
+		if !is_harder {
+			return Self::new(problem);
+		}
+
+		match &self.strengthened {
+			Some(strengthened) => {
+				let mut strengthened = strengthened.clone();
+				let old_bounds = strengthened.jobs[index];
+				let mut new_job = job;
+				new_job.earliest_start = Time::max(job.earliest_start, old_bounds.earliest_start);
+				new_job.set_latest_finish(Time::min(job.get_latest_finish(), old_bounds.get_latest_finish()));
+				strengthened.jobs[index] = new_job;
+				Self::reanalyze(problem, strengthened)
+			},
+			None => Self { problem, strengthened: None },
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::necessary::is_proven_infeasible;
+	use crate::problem::*;
+	use crate::what_if::AnalyzedProblem;
+
+	fn create_problem() -> Problem {
+		Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 10, 40),
+				Job::release_to_deadline(1, 0, 20, 40),
+				Job::release_to_deadline(2, 10, 5, 50),
+			],
+			constraints: vec![Constraint::new(0, 1, 0, ConstraintType::FinishToStart)],
+			num_cores: 2,
+			resources: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn test_with_constraint() {
+		let analyzed = AnalyzedProblem::new(create_problem());
+		assert!(!analyzed.is_proven_infeasible());
+		assert_eq!(20, analyzed.get_strengthened_problem().unwrap().jobs[1].latest_start);
+
+		let feasible = analyzed.with_constraint(Constraint::new(1, 2, 0, ConstraintType::FinishToStart));
+		assert!(!feasible.is_proven_infeasible());
+		assert_eq!(2, feasible.get_problem().constraints.len());
+		assert_eq!(feasible.get_problem().constraints, feasible.get_strengthened_problem().unwrap().constraints);
+		assert_eq!(30, feasible.get_strengthened_problem().unwrap().jobs[2].earliest_start);
+
+		let infeasible = feasible.with_constraint(Constraint::new(2, 0, 0, ConstraintType::FinishToStart));
+		assert!(infeasible.is_proven_infeasible());
+		assert!(is_proven_infeasible(infeasible.get_problem()));
+		assert!(infeasible.with_constraint(Constraint::new(0, 2, 0, ConstraintType::StartToStart)).is_proven_infeasible());
+	}
+
+	#[test]
+	fn test_with_job() {
+		let analyzed = AnalyzedProblem::new(create_problem());
+
+		let harder = analyzed.with_job(Job::release_to_deadline(1, 0, 25, 40));
+		assert!(!harder.is_proven_infeasible());
+		assert_eq!(25, harder.get_problem().jobs[1].get_execution_time());
+		assert_eq!(15, harder.get_strengthened_problem().unwrap().jobs[1].latest_start);
+		assert_eq!(5, harder.get_strengthened_problem().unwrap().jobs[0].latest_start);
+
+		let infeasible = harder.with_job(Job::release_to_deadline(0, 0, 20, 40));
+		assert!(infeasible.is_proven_infeasible());
+
+		// Making the problem easier again requires a fresh analysis
+		let easier = infeasible.with_job(Job::release_to_deadline(1, 0, 5, 40));
+		assert!(!easier.is_proven_infeasible());
+		assert_eq!(35, easier.get_strengthened_problem().unwrap().jobs[1].latest_start);
+		assert_eq!(*easier.get_problem(), Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 20, 40),
+				Job::release_to_deadline(1, 0, 5, 40),
+				Job::release_to_deadline(2, 10, 5, 50),
+			],
+			..create_problem()
+		});
+	}
+
+	#[test]
+	fn test_implied_constraints() {
+		// Job 1 can't finish before job 0 must start, so job 0 must precede job 1 on the only core
+		let problem = Problem {
+			jobs: vec![
+				Job::release_to_deadline(0, 0, 8, 10),
+				Job::release_to_deadline(1, 1, 5, 20),
+				Job::release_to_deadline(2, 30, 5, 40),
+			],
+			constraints: Vec::new(),
+			num_cores: 1,
+			resources: Vec::new(),
+		};
+		let implied_constraint = Constraint::new(0, 1, 0, ConstraintType::FinishToStart);
+		let analyzed = AnalyzedProblem::new(problem);
+		assert!(analyzed.get_problem().constraints.is_empty());
+		assert_eq!(vec![implied_constraint], analyzed.get_strengthened_problem().unwrap().constraints);
+		assert_eq!(8, analyzed.get_strengthened_problem().unwrap().jobs[1].earliest_start);
+
+		// The implied constraint is kept, and merged with an equivalent constraint
+		let constrained = analyzed.with_constraint(implied_constraint);
+		assert_eq!(vec![implied_constraint], constrained.get_problem().constraints);
+		assert_eq!(vec![implied_constraint], constrained.get_strengthened_problem().unwrap().constraints);
+	}
+}
@@ -0,0 +1,93 @@
+//! Cross-checks the necessary tests and the bound strengthening against an exhaustive search on
+//! random tiny problems. Run it with `cargo test --features cross-check --test cross_check`.
+
+use np_feasibility::certificate::*;
+use np_feasibility::exact::{ExactResult, for_each_feasible_schedule, solve_by_enumeration};
+use np_feasibility::necessary::*;
+use np_feasibility::problem::*;
+use np_feasibility::simulator::simulate_earliest_deadline_first;
+use proptest::prelude::*;
+
+/// Generates problems with at most 5 jobs and 2 cores, and acyclic constraints
+fn tiny_problem() -> impl Strategy<Value = Problem> {
+	(1 ..= 5usize, 1 ..= 2u32).prop_flat_map(|(num_jobs, num_cores)| {
+		let jobs = prop::collection::vec((0 .. 20 as Time, 1 .. 10 as Time, 0 .. 15 as Time), num_jobs);
+		let constraints = prop::collection::vec(
+			(0 .. num_jobs, 0 .. num_jobs, 0 .. 5 as Time, any::<bool>()), 0 ..= num_jobs
+		);
+		(jobs, constraints, Just(num_cores))
+	}).prop_map(|(jobs, constraints, num_cores)| {
+		let jobs = jobs.into_iter().enumerate().map(
+			|(index, (release, execution_time, slack))| Job::release_to_deadline(
+				index, release, execution_time, release + execution_time + slack
+			)
+		).collect();
+		let constraints = constraints.into_iter().filter(|(before, after, _, _)| before < after).map(
+			|(before, after, delay, is_start_to_start)| Constraint::new(before, after, delay, if is_start_to_start {
+				ConstraintType::StartToStart
+			} else {
+				ConstraintType::FinishToStart
+			})
+		).collect();
+
+		let mut problem = Problem { jobs, constraints, num_cores, resources: Vec::new() };
+		problem.merge_duplicate_constraints();
+		problem.validate();
+		problem
+	})
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(2000))]
+
+	#[test]
+	fn necessary_tests_never_reject_feasible_problems(problem in tiny_problem()) {
+		if let ExactResult::Feasible(schedule) = solve_by_enumeration(&problem) {
+			prop_assert_eq!(Ok(()), schedule.validate(&problem));
+			prop_assert_eq!(Ok(()), check_schedule_certificate(&problem, &create_schedule_certificate(&schedule)));
+			prop_assert_eq!(None, problem.find_contradictory_constraint());
+
+			let index = ProblemIndex::new(&problem);
+			for test in NecessaryTest::ALL {
+				prop_assert!(!test.run(&problem, &index), "The {} test rejected a feasible problem", test.get_name());
+			}
+			prop_assert!(!is_proven_infeasible(&problem));
+		}
+	}
+
+	#[test]
+	fn strengthening_keeps_all_feasible_schedules(problem in tiny_problem()) {
+		let mut strengthened = problem.clone();
+		let is_infeasible = strengthen_and_test(&mut strengthened);
+
+		let mut num_schedules = 0;
+		for_each_feasible_schedule(&problem, |schedule| {
+			num_schedules += 1;
+			for scheduled in schedule.get_jobs() {
+				let job = &strengthened.jobs[scheduled.job];
+				assert!(
+					job.earliest_start <= scheduled.start && scheduled.start <= job.latest_start,
+					"Job {} starts at {}, outside its strengthened bounds [{}, {}]",
+					scheduled.job, scheduled.start, job.earliest_start, job.latest_start
+				);
+			}
+		});
+		prop_assert!(num_schedules == 0 || !is_infeasible);
+	}
+
+	#[test]
+	fn infeasibility_certificates_are_sound(problem in tiny_problem()) {
+		if let Some(certificate) = find_infeasibility_certificate(&problem) {
+			prop_assert_eq!(Ok(()), verify_infeasibility_certificate(&problem, &certificate));
+			prop_assert_eq!(ExactResult::Infeasible, solve_by_enumeration(&problem));
+		}
+	}
+
+	#[test]
+	fn policy_agrees_with_enumeration(problem in tiny_problem()) {
+		let simulator = simulate_earliest_deadline_first(&problem);
+		if !simulator.has_missed_deadline() {
+			prop_assert!(matches!(solve_by_enumeration(&problem), ExactResult::Feasible(_)));
+		}
+	}
+}
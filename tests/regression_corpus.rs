@@ -0,0 +1,35 @@
+//! Replays every regression corpus (any directory named `regression`) below `test-problems`, and
+//! checks that none of its problems makes the analyses panic, hang, or contradict each other. Use
+//! `--record-regressions` to add problems to such a corpus.
+
+use np_feasibility::regression::{check_regression, parse_regression_corpus};
+use std::fs::read_dir;
+use std::path::Path;
+
+fn find_corpus_directories(directory: &Path, corpus_directories: &mut Vec<String>) {
+	for entry in read_dir(directory).expect("Couldn't read test problem directory") {
+		let path = entry.expect("Couldn't read test problem directory").path();
+		if path.is_dir() {
+			if path.file_name().unwrap() == "regression" {
+				corpus_directories.push(path.to_string_lossy().into_owned());
+			}
+			find_corpus_directories(&path, corpus_directories);
+		}
+	}
+}
+
+#[test]
+fn replay_regression_corpus() {
+	let mut corpus_directories = Vec::new();
+	find_corpus_directories(Path::new("test-problems"), &mut corpus_directories);
+	assert!(!corpus_directories.is_empty());
+
+	let mut num_problems = 0;
+	for directory in corpus_directories {
+		for (path, problem) in parse_regression_corpus(&directory) {
+			assert_eq!(Ok(()), check_regression(&problem), "{}", path);
+			num_problems += 1;
+		}
+	}
+	assert!(num_problems > 0);
+}